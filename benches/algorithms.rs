@@ -0,0 +1,197 @@
+//! How fast each built-in implementation plays, how many guesses it takes and how much it
+//! allocates, on the same sample of answers every time.
+//!
+//! Plays every 50th official answer with each implementation (or only the ones named as
+//! arguments), after one game to set up whatever is shared between games, e.g.
+//! `cargo bench --bench algorithms -- vecrem cutoff cache`. Running all of them takes several
+//! minutes, most of it in the slowest few.
+//!
+//! `--save <file>` writes the results down, and `--baseline <file>` compares against results
+//! written earlier, failing if any implementation got slower or allocates more by more than
+//! `--tolerance` (0.25 by default, as timings are noisy), or takes more guesses at all.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use wordle_solver::{answers::Answers, evaluate_all_with, registry::Registry};
+
+/// Counts every allocation made, to see which implementations allocate in their inner loops.
+struct Counting;
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static ALLOCATED: AtomicU64 = AtomicU64::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED.fetch_add(new_size as u64, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// Plays one game in this many of the official answers.
+const SAMPLE_EVERY: usize = 50;
+
+/// How an implementation did, per game.
+#[derive(Debug, Clone, Copy)]
+struct Measurement {
+    ms: f64,
+    guesses: f64,
+    allocations: f64,
+    bytes: f64,
+}
+
+fn main() {
+    let mut names = Vec::new();
+    let (mut save, mut baseline, mut tolerance) = (None, None, 0.25);
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--save" => save = args.next().map(PathBuf::from),
+            "--baseline" => baseline = args.next().map(PathBuf::from),
+            "--tolerance" => {
+                tolerance = match args.next().and_then(|t| t.parse().ok()) {
+                    Some(tolerance) => tolerance,
+                    None => fail("--tolerance takes a fraction, like 0.25"),
+                }
+            }
+            // Passed by `cargo bench` to every benchmark.
+            "--bench" => {}
+            name => names.push(name.to_string()),
+        }
+    }
+    let registry = Registry::builtin();
+    if names.is_empty() {
+        names = registry.names().map(str::to_string).collect();
+    }
+    let answers: Vec<_> = Answers::official()
+        .iter()
+        .map(|(_, answer)| answer)
+        .step_by(SAMPLE_EVERY)
+        .collect();
+
+    println!(
+        "{:12} {:>10} {:>8} {:>12} {:>12}",
+        "", "ms/game", "guesses", "allocs/game", "bytes/game"
+    );
+    let mut results = Vec::new();
+    for name in &names {
+        let Some(factory) = registry.get(name) else {
+            fail(&format!("unknown implementation '{}'", name));
+        };
+        // Set up what is shared between games (like the cache of masks), which is only done once.
+        evaluate_all_with(["zonal"], &factory, &Default::default());
+
+        let (allocations, allocated) = (
+            ALLOCATIONS.load(Ordering::Relaxed),
+            ALLOCATED.load(Ordering::Relaxed),
+        );
+        let stats = evaluate_all_with(answers.iter().copied(), &factory, &Default::default());
+        let games = stats.games.max(1) as f64;
+        let measurement = Measurement {
+            ms: stats.elapsed.as_secs_f64() * 1000.0 / games,
+            guesses: stats.average_score(),
+            allocations: (ALLOCATIONS.load(Ordering::Relaxed) - allocations) as f64 / games,
+            bytes: (ALLOCATED.load(Ordering::Relaxed) - allocated) as f64 / games,
+        };
+        println!(
+            "{:12} {:10.2} {:8.4} {:12.0} {:12.0}",
+            name, measurement.ms, measurement.guesses, measurement.allocations, measurement.bytes
+        );
+        results.push((name.clone(), measurement));
+    }
+
+    if let Some(path) = save {
+        let lines: String = results
+            .iter()
+            .map(|(name, m)| {
+                format!(
+                    "{} {} {} {} {}\n",
+                    name, m.ms, m.guesses, m.allocations, m.bytes
+                )
+            })
+            .collect();
+        if let Err(e) = std::fs::write(&path, lines) {
+            fail(&format!("could not save to '{}': {}", path.display(), e));
+        }
+    }
+    if let Some(path) = baseline {
+        let before = match read_results(&path) {
+            Ok(before) => before,
+            Err(e) => fail(&format!("could not read '{}': {}", path.display(), e)),
+        };
+        let mut regressions = Vec::new();
+        for (name, after) in &results {
+            let Some(before) = before.get(name) else {
+                continue;
+            };
+            if after.ms > before.ms * (1.0 + tolerance) {
+                regressions.push(format!(
+                    "{} takes {:.2}ms a game, up from {:.2}ms",
+                    name, after.ms, before.ms
+                ));
+            }
+            if after.allocations > before.allocations * (1.0 + tolerance) {
+                regressions.push(format!(
+                    "{} allocates {:.0} times a game, up from {:.0}",
+                    name, after.allocations, before.allocations
+                ));
+            }
+            if after.guesses > before.guesses + 1e-9 {
+                regressions.push(format!(
+                    "{} takes {:.4} guesses a game, up from {:.4}",
+                    name, after.guesses, before.guesses
+                ));
+            }
+        }
+        if !regressions.is_empty() {
+            for regression in regressions {
+                eprintln!("{}", regression);
+            }
+            std::process::exit(1);
+        }
+        println!("no regressions against '{}'", path.display());
+    }
+}
+
+/// Reads results written by `--save`.
+fn read_results(path: &Path) -> Result<HashMap<String, Measurement>, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    text.lines()
+        .map(|line| {
+            let fields: Vec<_> = line.split_whitespace().collect();
+            let number = |i: usize| -> Result<f64, String> {
+                fields
+                    .get(i)
+                    .and_then(|field| field.parse().ok())
+                    .ok_or_else(|| format!("'{}' is not a saved result", line))
+            };
+            let measurement = Measurement {
+                ms: number(1)?,
+                guesses: number(2)?,
+                allocations: number(3)?,
+                bytes: number(4)?,
+            };
+            Ok((fields[0].to_string(), measurement))
+        })
+        .collect()
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(1);
+}
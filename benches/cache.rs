@@ -0,0 +1,44 @@
+//! How well the cache of `Cached` holds up when several games fill it in at once.
+//!
+//! Plays every answer on pools of 1, 2, 4 and 8 workers (or the numbers given as arguments). The
+//! cache is shared by the whole process, so only the first pool fills it in from scratch, and the
+//! rest read what it left: to compare how each pool fills it in, run one pool per process, e.g.
+//! `cargo bench --bench cache -- 4`.
+
+use std::time::Duration;
+use wordle_solver::{algorithms::Cached, answers::Answers, evaluate_parallel_with};
+
+fn main() {
+    let pools: Vec<usize> = std::env::args()
+        .skip(1)
+        .filter_map(|arg| arg.parse().ok())
+        .collect();
+    let pools = if pools.is_empty() {
+        vec![1, 2, 4, 8]
+    } else {
+        pools
+    };
+    let answers: Vec<_> = Answers::official()
+        .iter()
+        .map(|(_, answer)| answer)
+        .collect();
+    for (i, &workers) in pools.iter().enumerate() {
+        let stats = evaluate_parallel_with(
+            answers.iter().copied(),
+            &Cached::new,
+            &Default::default(),
+            workers,
+        );
+        println!(
+            "{} workers, {} cache: {:.2}s ({:.1}ms per game)",
+            workers,
+            if i == 0 { "cold" } else { "warm" },
+            stats.elapsed.as_secs_f64(),
+            per_game(stats.elapsed, stats.games)
+        );
+    }
+}
+
+fn per_game(elapsed: Duration, games: usize) -> f64 {
+    elapsed.as_secs_f64() * 1000.0 / games.max(1) as f64
+}
@@ -0,0 +1,51 @@
+//! How fast each kernel works out feedback, which shows whether compiling it again for wider
+//! instructions is still paying off.
+//!
+//! The kernel is picked once for the whole process, so with no arguments this runs itself once for
+//! the portable kernel and once for the one the CPU would pick. Pass a kernel (`avx2`, `neon` or
+//! `portable`) to only time that one, e.g. `cargo bench --bench kernel -- portable`.
+
+use std::process::Command;
+use std::time::Instant;
+use wordle_solver::{
+    algorithms::{set_kernel, Kernel},
+    analysis::opener_table,
+    dictionary,
+};
+
+fn main() {
+    let kernel: Option<Kernel> = std::env::args().skip(1).find_map(|arg| arg.parse().ok());
+    let Some(kernel) = kernel else {
+        let exe = std::env::current_exe().expect("the benchmark knows where it is");
+        for kernel in [Kernel::Portable, Kernel::detect()] {
+            let status = Command::new(&exe)
+                .arg(kernel.to_string())
+                .status()
+                .expect("the benchmark can run itself");
+            assert!(status.success(), "the {} run failed", kernel);
+            if kernel == Kernel::detect() {
+                break;
+            }
+        }
+        return;
+    };
+
+    set_kernel(kernel).unwrap();
+    // Every word as an opener against every word, which is all feedback and a little counting.
+    let words: Vec<_> = dictionary().iter().map(|&(word, _)| word).collect();
+    let mut fastest = f64::INFINITY;
+    for _ in 0..5 {
+        let start = Instant::now();
+        let table = opener_table(&words, &words);
+        fastest = fastest.min(start.elapsed().as_secs_f64());
+        assert_eq!(table.len(), words.len());
+    }
+    println!(
+        "{}: {:.0}ms for {} guesses against {} answers ({:.2}ns per feedback)",
+        kernel,
+        fastest * 1000.0,
+        words.len(),
+        words.len(),
+        fastest * 1e9 / (words.len() * words.len()) as f64
+    );
+}
@@ -0,0 +1,44 @@
+//! How fast `Cached` plays with each layout of its cache.
+//!
+//! The layout is shared by the whole process, so with no arguments this runs itself once for each
+//! layout. Pass a layout (`guess-major` or `candidate-major`) to only time that one, e.g.
+//! `cargo bench --bench layout -- candidate-major`.
+
+use std::process::Command;
+use wordle_solver::{
+    algorithms::{CacheLayout, Cached},
+    answers::Answers,
+    evaluate_all_with,
+};
+
+fn main() {
+    let layout: Option<CacheLayout> = std::env::args().skip(1).find_map(|arg| arg.parse().ok());
+    let Some(layout) = layout else {
+        let exe = std::env::current_exe().expect("the benchmark knows where it is");
+        for layout in [CacheLayout::GuessMajor, CacheLayout::CandidateMajor] {
+            let status = Command::new(&exe)
+                .arg(layout.to_string())
+                .status()
+                .expect("the benchmark can run itself");
+            assert!(status.success(), "the {} run failed", layout);
+        }
+        return;
+    };
+
+    Cached::set_layout(layout).unwrap();
+    let answers: Vec<_> = Answers::official()
+        .iter()
+        .map(|(_, answer)| answer)
+        .collect();
+    for pass in ["cold", "warm"] {
+        let stats = evaluate_all_with(answers.iter().copied(), &Cached::new, &Default::default());
+        println!(
+            "{}, {} cache: {:.2}s ({:.1}ms per game, {:.4} average score)",
+            layout,
+            pass,
+            stats.elapsed.as_secs_f64(),
+            stats.elapsed.as_secs_f64() * 1000.0 / stats.games.max(1) as f64,
+            stats.average_score()
+        );
+    }
+}
@@ -1,8 +1,87 @@
 use std::io::prelude::*;
 
 const DICTIONARY: &str = include_str!("dictionary.txt");
+const DICTIONARY_SOURCE: &str =
+    "Wordle's allowed guesses, weighted by how often each occurs in the Google Books Ngram corpus";
+
+/// How many of the best openers to embed.
+const OPENERS: usize = 100;
+
+/// The feedback `guess` gets against `answer`, packed the same way as `Correctness::pack`.
+fn packed_feedback(answer: &[u8], guess: &[u8]) -> usize {
+    // 0 is correct, 1 misplaced and 2 wrong.
+    let mut c = [2u8; 5];
+    let mut misplaced = [0u8; 26];
+    for (i, (&a, &g)) in answer.iter().zip(guess).enumerate() {
+        if a == g {
+            c[i] = 0;
+        } else {
+            misplaced[(a - b'a') as usize] += 1;
+        }
+    }
+    for (&g, c) in guess.iter().zip(c.iter_mut()) {
+        if *c == 2 && misplaced[(g - b'a') as usize] > 0 {
+            *c = 1;
+            misplaced[(g - b'a') as usize] -= 1;
+        }
+    }
+    c.iter().fold(0, |acc, &c| acc * 3 + usize::from(c))
+}
+
+/// The `OPENERS` words with the most expected information as a first guess, when every word is
+/// equally likely to be the answer, best first. This adds up the same way as
+/// `analysis::opener_table`, so the entropies match it exactly.
+fn best_openers(words: &[&str]) -> Vec<(String, f64)> {
+    let n = words.len() as f64;
+    let mut table: Vec<_> = words
+        .iter()
+        .map(|guess| {
+            let mut buckets = [0usize; 243];
+            for answer in words {
+                buckets[packed_feedback(answer.as_bytes(), guess.as_bytes())] += 1;
+            }
+            let mut entropy = 0.0;
+            for &b in buckets.iter().filter(|&&b| b != 0) {
+                let p = b as f64 / n;
+                entropy -= p * p.log2();
+            }
+            (guess.to_string(), entropy)
+        })
+        .collect();
+    table.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    table.truncate(OPENERS);
+    table
+}
+
+/// Today's date as YYYY-MM-DD, or the one in SOURCE_DATE_EPOCH for reproducible builds.
+fn today() -> String {
+    let secs = match std::env::var("SOURCE_DATE_EPOCH") {
+        Ok(epoch) => epoch
+            .parse()
+            .expect("SOURCE_DATE_EPOCH is a number of seconds"),
+        Err(_) => std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("the clock is after 1970")
+            .as_secs(),
+    };
+    // Howard Hinnant's civil_from_days.
+    let z = (secs / 86400) as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
 
 fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=dictionary.txt");
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+
     let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap());
     let mut f = std::fs::File::create(out_dir.join("dictionary.rs"))
         .expect("could not create file in OUT_DIR");
@@ -16,14 +95,47 @@ fn main() {
     }));
     words.sort_unstable_by_key(|&(_, count)| std::cmp::Reverse(count));
 
+    writeln!(f, "#[allow(clippy::large_const_arrays)]").unwrap();
     writeln!(
         f,
         "pub const DICTIONARY: [(&str, usize); {}] = [",
         words.len()
     )
     .unwrap();
-    for (word, count) in words {
+    for (word, count) in &words {
         writeln!(f, "(\"{}\", {}),", word, count).unwrap();
     }
-    write!(f, "];").unwrap();
+    writeln!(f, "];").unwrap();
+
+    // FNV-1a over the sorted dictionary, so anything derived from it can record which version of
+    // the word list it was built against. The build script can't use the crate, so this repeats
+    // `profile::fnv1a`.
+    let mut checksum: u64 = 0xcbf29ce484222325;
+    for (word, count) in &words {
+        for b in format!("{} {}\n", word, count).bytes() {
+            checksum ^= u64::from(b);
+            checksum = checksum.wrapping_mul(0x100000001b3);
+        }
+    }
+    writeln!(f, "pub const DICTIONARY_CHECKSUM: u64 = {:#x};", checksum).unwrap();
+    writeln!(
+        f,
+        "pub const DICTIONARY_SOURCE: &str = {:?};",
+        DICTIONARY_SOURCE
+    )
+    .unwrap();
+    // When this version of the dictionary was generated, which is whenever it last changed.
+    writeln!(f, "pub const DICTIONARY_GENERATED: &str = {:?};", today()).unwrap();
+
+    // Working these out takes a while, so it is done once here rather than every time the
+    // solver starts.
+    let mut f = std::fs::File::create(out_dir.join("openers.rs"))
+        .expect("could not create file in OUT_DIR");
+    let names: Vec<_> = words.iter().map(|&(word, _)| word).collect();
+    let openers = best_openers(&names);
+    writeln!(f, "const OPENERS: [(&str, f64); {}] = [", openers.len()).unwrap();
+    for (word, entropy) in &openers {
+        writeln!(f, "(\"{}\", {:?}),", word, entropy).unwrap();
+    }
+    writeln!(f, "];").unwrap();
 }
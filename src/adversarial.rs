@@ -0,0 +1,240 @@
+//! A host that doesn't pick the answer up front, as in Absurdle: after every guess it gives
+//! whichever feedback keeps the most candidates in play, so the guesser has to pin the answer
+//! down completely before it is found.
+//!
+//! Since the host always does the same thing, every game against it is the same for a guesser
+//! that always makes the same guesses, and its score is the most guesses that guesser could ever
+//! need (give or take ties).
+
+use crate::{
+    candidate_answers, letter_count, score_or_panic, Correctness, GameMode, GameResult, Guess,
+    Guesser, Mask, Wordle, WordleError, MAX_GUESSES,
+};
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Plays games where the answer is picked as late as possible, to make them last as long as it
+/// can.
+pub struct AdversarialWordle {
+    rules: Wordle,
+    candidates: Vec<&'static str>,
+}
+
+impl Default for AdversarialWordle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AdversarialWordle {
+    /// A host that can end up with any of the words that can be the answer.
+    pub fn new() -> Self {
+        let rules = Wordle::new();
+        let candidates = candidate_answers()
+            .iter()
+            .map(|&(word, _)| word)
+            .filter(|word| rules.can_be_answer(word))
+            .collect();
+        Self { rules, candidates }
+    }
+
+    /// Only ends up with one of `candidates`, as when the real game has fewer answers than it
+    /// allows guesses.
+    pub fn with_candidates(mut self, candidates: impl IntoIterator<Item = &'static str>) -> Self {
+        self.candidates = candidates.into_iter().collect();
+        self
+    }
+
+    pub fn play<G: Guesser>(&self, guesser: G) -> Option<usize> {
+        self.play_with_mode(guesser, GameMode::Normal)
+    }
+
+    /// Plays like [`play`](Self::play), by the rules of `mode`.
+    ///
+    /// # Panics
+    ///
+    /// If the guesser breaks the rules, as when it guesses a word that isn't in the dictionary.
+    pub fn play_with_mode<G: Guesser>(&self, guesser: G, mode: GameMode) -> Option<usize> {
+        score_or_panic(self.try_play(guesser, mode))
+    }
+
+    /// Plays like [`play_with_mode`](Self::play_with_mode), but returns an error rather than
+    /// panicking if the guesser breaks the rules, or
+    /// [`TooManyGuesses`](WordleError::TooManyGuesses) if it never finds the answer.
+    pub fn try_play<G: Guesser>(&self, guesser: G, mode: GameMode) -> Result<usize, WordleError> {
+        let game = self.try_play_game(guesser, mode)?;
+        if game.solved() {
+            Ok(game.guesses.len())
+        } else {
+            Err(WordleError::TooManyGuesses(MAX_GUESSES))
+        }
+    }
+
+    /// Plays like [`play_with_mode`](Self::play_with_mode), keeping track of everything that
+    /// happened in the game. The answer is the one the host ended up with, or if the guesser
+    /// never found it, one of those it could still have ended up with.
+    ///
+    /// # Panics
+    ///
+    /// If the guesser breaks the rules, as when it guesses a word that isn't in the dictionary.
+    pub fn play_game<G: Guesser>(&self, guesser: G, mode: GameMode) -> GameResult {
+        match self.try_play_game(guesser, mode) {
+            Ok(game) => game,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Plays a game until the answer is found or `MAX_GUESSES` have been made, unless the guesser
+    /// breaks the rules.
+    fn try_play_game<G: Guesser>(
+        &self,
+        mut guesser: G,
+        mode: GameMode,
+    ) -> Result<GameResult, WordleError> {
+        let mut candidates = self.candidates.clone();
+        let mut game = GameResult {
+            answer: candidates.first().copied().unwrap_or_default(),
+            guesses: Vec::new(),
+            masks: Vec::new(),
+            remaining: Vec::new(),
+            times: Vec::new(),
+        };
+        let mut history: Vec<Guess> = Vec::new();
+        for i in 1..=MAX_GUESSES {
+            let start = Instant::now();
+            let guess = guesser.guess(&history);
+            let time = start.elapsed();
+            self.rules
+                .check_rules(&guess, letter_count(game.answer), &history, mode)?;
+            if !self.rules.dictionary.contains(&*guess) {
+                return Err(WordleError::UnknownWord(guess.into_owned()));
+            }
+            let (mask, left) = worst_feedback(&guess, &candidates);
+            candidates = left;
+            game.answer = candidates.first().copied().unwrap_or(game.answer);
+            game.guesses.push(guess.to_string());
+            game.masks.push(mask);
+            game.remaining.push(candidates.len());
+            game.times.push(time);
+            if mask == Correctness::SOLVED {
+                guesser.finish(i);
+                return Ok(game);
+            }
+            history.push(Guess { word: guess, mask });
+        }
+        Ok(game)
+    }
+}
+
+/// The feedback to `guess` that keeps the most of `candidates` in play, along with those it keeps.
+///
+/// Between feedbacks that keep as many, the one revealing the least is given: the fewest green
+/// letters, then the fewest yellow ones. So the answer is only ever given away once it is the
+/// last candidate left.
+fn worst_feedback(guess: &str, candidates: &[&'static str]) -> (Mask, Vec<&'static str>) {
+    let mut buckets: HashMap<Mask, Vec<&'static str>> = HashMap::new();
+    for &candidate in candidates {
+        buckets
+            .entry(Correctness::compute(candidate, guess))
+            .or_default()
+            .push(candidate);
+    }
+    let revealed = |mask: &Mask, c| {
+        mask[..letter_count(guess)]
+            .iter()
+            .filter(|&&m| m == c)
+            .count()
+    };
+    buckets
+        .into_iter()
+        .max_by_key(|(mask, words)| {
+            (
+                words.len(),
+                std::cmp::Reverse(revealed(mask, Correctness::Correct)),
+                std::cmp::Reverse(revealed(mask, Correctness::Misplaced)),
+                // Only to make the choice the same every time.
+                Correctness::pack(&mask[..letter_count(guess)]),
+            )
+        })
+        .unwrap_or((Correctness::SOLVED, Vec::new()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{worst_feedback, AdversarialWordle};
+    use crate::{Correctness, GameMode, Guess, Guesser, WordleError};
+    use std::borrow::Cow;
+
+    #[test]
+    fn keeps_the_most_candidates() {
+        let candidates = ["fight", "light", "might", "night", "tawny"];
+        let (mask, left) = worst_feedback("fight", &candidates);
+        assert_eq!(mask, Correctness::compute("light", "fight"));
+        assert_eq!(left, ["light", "might", "night"]);
+        // Only the last one left is ever given away.
+        let (mask, left) = worst_feedback("might", &["might", "night"]);
+        assert_eq!(left, ["night"]);
+        assert_ne!(mask, Correctness::SOLVED);
+        let (mask, _) = worst_feedback("night", &["night"]);
+        assert_eq!(mask, Correctness::SOLVED);
+    }
+
+    /// Guesses the last of its words that could still be the answer.
+    struct Last(&'static [&'static str]);
+
+    impl Guesser for Last {
+        fn guess(&mut self, history: &[Guess]) -> Cow<'static, str> {
+            let mut left = self
+                .0
+                .iter()
+                .filter(|word| history.iter().all(|g| g.matches(word)));
+            Cow::Borrowed(left.next_back().unwrap())
+        }
+    }
+
+    /// Guesses the same word every time.
+    struct Always(&'static str);
+
+    impl Guesser for Always {
+        fn guess(&mut self, _history: &[Guess]) -> Cow<'static, str> {
+            Cow::Borrowed(self.0)
+        }
+    }
+
+    #[test]
+    fn plays_until_one_is_left() {
+        const WORDS: &[&str] = &["fight", "light", "might", "night"];
+        let w = AdversarialWordle::new().with_candidates(WORDS.iter().copied());
+        let game = w.play_game(Last(WORDS), GameMode::Normal);
+        // Guessing one at a time, the host holds out until the last one.
+        assert_eq!(game.guesses, ["night", "might", "light", "fight"]);
+        assert_eq!(game.answer, "fight");
+        assert_eq!(game.remaining, [3, 2, 1, 1]);
+        assert!(game.solved());
+        assert_eq!(w.play(Last(WORDS)), Some(4));
+    }
+
+    #[test]
+    fn checks_the_rules() {
+        let w = AdversarialWordle::new();
+        let result = w.try_play(Always("zzzzz"), GameMode::Normal);
+        assert_eq!(result, Err(WordleError::UnknownWord("zzzzz".to_string())));
+        let result = w.try_play(Always("tares"), GameMode::Normal);
+        assert_eq!(result, Err(WordleError::TooManyGuesses(32)));
+        assert_eq!(w.play(Always("tares")), None);
+    }
+
+    #[test]
+    fn returns_unsolved_games() {
+        let w = AdversarialWordle::new();
+        let game = w.play_game(Always("tares"), GameMode::Normal);
+        assert!(!game.solved());
+        assert_eq!(game.score(), None);
+        assert_eq!(game.guesses.len(), 32);
+        assert!(game.guesses.iter().all(|guess| guess == "tares"));
+        // The host could still have ended up with the answer it gives.
+        let left = *game.remaining.last().unwrap();
+        assert!(left > 1);
+        assert_eq!(Correctness::compute(game.answer, "tares"), game.masks[0]);
+    }
+}
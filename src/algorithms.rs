@@ -1,3 +1,5 @@
+pub mod tiebreak;
+pub use tiebreak::{BreaksTies, TieBreak};
 mod unoptimised;
 pub use unoptimised::Unoptimised;
 mod allocs;
@@ -18,5 +20,50 @@ mod sigmoid;
 pub use sigmoid::Sigmoid;
 mod escore;
 pub use escore::Escore;
+mod budget;
+mod estimate;
+pub(crate) use estimate::est_steps_left;
 mod cache;
-pub use cache::Cached;
+mod exact;
+mod explain;
+pub use explain::{GuessExplanation, ScoredGuess};
+mod matrix;
+pub use matrix::PatternMatrix;
+mod minimax;
+pub use minimax::Minimax;
+mod optimal;
+pub use optimal::Optimal;
+mod fixed;
+pub(crate) mod kernel;
+pub use kernel::{kernel, set_kernel, Kernel};
+pub(crate) mod summation;
+pub use summation::{set_summation, summation, Summation};
+mod precision;
+pub use precision::Precision;
+mod search;
+pub use search::{Calibration, SearchSchedule, SearchWidth};
+mod telemetry;
+pub use telemetry::{enable_telemetry, telemetry, Telemetry};
+mod twoply;
+pub use twoply::TwoPly;
+mod words;
+pub use cache::{CacheLayout, CacheStorage, Cached};
+pub use words::WordIndex;
+mod opener;
+pub use opener::Opener;
+mod configured;
+pub use configured::{Arithmetic, Configured, Metric, Strategy, TieBreakRule};
+#[cfg(feature = "scripting")]
+mod scripted;
+#[cfg(feature = "scripting")]
+pub use scripted::Scripted;
+
+use crate::analysis::opener_shortlist;
+
+/// The best opener on the [shortlist](crate::analysis::opener_shortlist), if the dictionary in
+/// use has one. It maximises expected information (and, for the official dictionary, minimises
+/// the expected score too). The guessers would take a long time to work it out every game, so
+/// they guess it straight away when there is one, and work one out otherwise.
+fn first_guess() -> Option<&'static str> {
+    opener_shortlist().first().map(|&(word, _)| word)
+}
@@ -0,0 +1,75 @@
+use std::time::{Duration, Instant};
+
+/// How many guesses Wordle allows, which the budget is shared out between.
+const ALLOWED_GUESSES: usize = 6;
+
+/// A wall-clock time limit on a whole game, shared out between its guesses.
+///
+/// Each guess gets an equal share of whatever is left for the guesses Wordle still allows, and
+/// stops considering candidates once its share is used up. Candidates are considered likeliest
+/// first, so a guess that runs out of time has still looked at the best ones, and a game whose
+/// early guesses took long narrows the search more in the later ones.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TimeBudget {
+    per_game: Duration,
+    spent: Duration,
+}
+
+impl TimeBudget {
+    pub(crate) fn new(per_game: Duration) -> Self {
+        Self {
+            per_game,
+            spent: Duration::ZERO,
+        }
+    }
+
+    /// When a guess started at `start`, after `guesses` earlier ones, has to stop looking.
+    pub(crate) fn deadline(&self, start: Instant, guesses: usize) -> Instant {
+        let left = self.per_game.saturating_sub(self.spent);
+        let guesses_left = ALLOWED_GUESSES.saturating_sub(guesses).max(1);
+        start + left / guesses_left as u32
+    }
+
+    /// Records that a guess started at `start` is done.
+    pub(crate) fn spend(&mut self, start: Instant) {
+        self.spent += start.elapsed();
+    }
+
+    /// Makes the whole budget available again, for a new game.
+    pub(crate) fn reset(&mut self) {
+        self.spent = Duration::ZERO;
+    }
+}
+
+/// Whether the search for a guess has to stop, since its `deadline` (if any) has passed.
+pub(crate) fn out_of_time(deadline: Option<Instant>) -> bool {
+    deadline.is_some_and(|deadline| Instant::now() >= deadline)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TimeBudget;
+    use crate::{algorithms::Escore, Wordle};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn shares_what_is_left() {
+        let start = Instant::now();
+        let mut budget = TimeBudget::new(Duration::from_secs(60));
+        assert_eq!(budget.deadline(start, 0), start + Duration::from_secs(10));
+        assert_eq!(budget.deadline(start, 4), start + Duration::from_secs(30));
+        assert_eq!(budget.deadline(start, 9), start + Duration::from_secs(60));
+
+        budget.spent = Duration::from_secs(55);
+        assert_eq!(budget.deadline(start, 5), start + Duration::from_secs(5));
+        budget.spend(start - Duration::from_secs(10));
+        assert_eq!(budget.deadline(start, 5), start);
+    }
+
+    #[test]
+    fn out_of_time_still_guesses() {
+        // Every guess considers at least the likeliest candidate, however little time is left.
+        let guesser = Escore::new().with_time_budget(Duration::ZERO);
+        assert!(Wordle::new().play("cigar", guesser).is_some());
+    }
+}
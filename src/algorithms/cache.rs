@@ -1,51 +1,378 @@
-use crate::{Correctness, Guess, Guesser, DICTIONARY, MAX_MASK_ENUM};
+use super::budget::{out_of_time, TimeBudget};
+use super::estimate::est_steps_left;
+use super::exact::Exact;
+use super::explain::{Explanations, GuessExplanation, ScoredGuess};
+use super::first_guess;
+use super::matrix::{self, PatternMatrix};
+use super::precision::{information_f32, Precision};
+use super::search::{Calibration, Search, SearchSchedule, SearchWidth};
+use super::summation;
+use super::telemetry::Lookups;
+use super::tiebreak::{Best, BreaksTies, Frequency, TieBreak};
+use super::words::{weighted_indexed, words, WordIndex, Words};
+use crate::profile::default_cache_dir;
+use crate::{dictionary, letter_count, word_len, Correctness, Guess, Guesser};
+use memmap2::MmapMut;
 use once_cell::sync::OnceCell;
-use once_cell::unsync::OnceCell as UnSyncOnceCell;
 use std::borrow::Cow;
-use std::cell::Cell;
-use std::num::NonZeroU8;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-static INITIAL: OnceCell<Vec<(&'static str, f64, usize)>> = OnceCell::new();
-static PATTERNS: OnceCell<Vec<[Correctness; 5]>> = OnceCell::new();
+// A packed mask only fits in a `u8` (with one value to spare for "not computed yet") for words of
+// up to five letters, since there are 3^5 = 243 of them.
+const MAX_CACHED_WORD_LEN: usize = 5;
+pub(super) const MAX_CACHED_PATTERNS: usize = 3usize.pow(MAX_CACHED_WORD_LEN as u32);
 
-#[derive(Copy, Clone)]
-struct CacheValue(NonZeroU8);
+// How many bytes the CPU caches together, which two threads writing to the same one (even at
+// different bytes) would fight over.
+pub(super) const CACHE_LINE: usize = 64;
 
-impl CacheValue {
-    fn new(val: u8) -> Self {
-        Self(NonZeroU8::new(val.wrapping_add(1)).unwrap())
+#[repr(align(64))]
+struct CacheLine([AtomicU8; CACHE_LINE]);
+
+/// How the cache of [`Cached`] lays out the mask of every guess against every answer.
+///
+/// Both the step that prunes the candidates after a guess and the step that sums up how likely
+/// each mask is go through the candidates for one guess at a time, but they touch different
+/// subsets of them, so which layout is faster depends on the machine and on how far into a game
+/// most of the time is spent. `benches/layout.rs` compares the two.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CacheLayout {
+    /// The masks of one guess against every answer are next to each other.
+    #[default]
+    GuessMajor,
+    /// The masks of every guess against one answer are next to each other.
+    CandidateMajor,
+}
+
+impl FromStr for CacheLayout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "guess-major" => Ok(Self::GuessMajor),
+            "candidate-major" => Ok(Self::CandidateMajor),
+            _ => Err(format!(
+                "unknown cache layout '{}', expected guess-major or candidate-major",
+                s
+            )),
+        }
     }
+}
+
+impl fmt::Display for CacheLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::GuessMajor => "guess-major",
+            Self::CandidateMajor => "candidate-major",
+        })
+    }
+}
+
+static LAYOUT: OnceCell<CacheLayout> = OnceCell::new();
 
-    fn get(&self) -> u8 {
-        self.0.get() - 1
+/// Where the cache of [`Cached`] keeps its masks, which is picked to stay within the
+/// [memory budget](Cached::set_memory_budget).
+///
+/// A mask already takes a single byte, with 243 of its 256 values in use, so there is nothing to
+/// be gained from compressing them. What can be saved is the rows that are never used: with the
+/// default guess-major layout, each row is one guess, and only the likeliest third or so of the
+/// words are ever considered as guesses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStorage {
+    /// Every row is allocated up front, which is the fastest.
+    Dense,
+    /// A row is only allocated when it is first used, and once this many have been, the masks
+    /// of any other row are worked out again every time they are needed.
+    LazyRows(usize),
+    /// Every row is in a memory-mapped temporary file, which the operating system can write out
+    /// to disk and drop from memory whenever it needs the space.
+    Mapped,
+    /// Every mask was worked out up front by the precompute command, and is read from a
+    /// [`PatternMatrix`](super::PatternMatrix) file as it is needed.
+    Precomputed,
+}
+
+impl fmt::Display for CacheStorage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Dense => f.write_str("dense"),
+            Self::LazyRows(rows) => write!(f, "lazy rows (at most {})", rows),
+            Self::Mapped => f.write_str("memory-mapped"),
+            Self::Precomputed => f.write_str("precomputed"),
+        }
     }
 }
 
-const NUM_WORDS: usize = DICTIONARY.len();
-const CELL: Cell<Option<CacheValue>> = Cell::new(None);
-const ROW: [Cell<Option<CacheValue>>; NUM_WORDS] = [CELL; NUM_WORDS];
+static MEMORY_BUDGET: OnceCell<Option<usize>> = OnceCell::new();
+
+static PATTERN_FILE: OnceCell<Option<PathBuf>> = OnceCell::new();
+
+// The mask of every guess against every answer, with a row for each word in the dictionary, which
+// is either the guess or the answer depending on the layout. Each cell is 0 until it is computed,
+// and then the packed mask + 1.
+//
+// It is shared by every thread, so that parallel games fill it in together rather than each
+// building their own. A cell only ever goes from 0 to the one value it can have, so relaxed
+// atomics are enough, and a thread that misses another's write just computes the same mask again.
+// Each row starts on its own cache line, so threads working on different rows don't keep taking
+// the same line from each other.
+struct Cache {
+    cells: Cells,
+    lines_per_row: usize,
+    layout: CacheLayout,
+    // Why the masks aren't kept where they were meant to be.
+    fallbacks: Vec<String>,
+}
+
+enum Cells {
+    Dense(Vec<CacheLine>),
+    LazyRows {
+        rows: Vec<OnceCell<Box<[CacheLine]>>>,
+        // How many rows have been allocated, which (when threads race to allocate the last few)
+        // can go a little over `max_rows`.
+        allocated: AtomicUsize,
+        max_rows: usize,
+    },
+    // The file is laid out the same way as `Dense`.
+    Mapped(MmapMut),
+    // A private mapping of a file laid out the same way, with every cell already filled in.
+    Precomputed(MmapMut),
+}
 
-struct Cache([[Cell<Option<CacheValue>>; NUM_WORDS]; NUM_WORDS]);
 impl Cache {
+    #[cfg(test)]
+    fn new(num_words: usize, layout: CacheLayout) -> Self {
+        Self::with_storage(num_words, layout, CacheStorage::Dense)
+    }
+
+    /// Lays out the cache in whichever storage is fastest while staying within `budget` bytes.
+    fn within(num_words: usize, layout: CacheLayout, budget: Option<usize>) -> Self {
+        let storage = Self::storage_within(num_words, layout, budget);
+        Self::with_storage(num_words, layout, storage)
+    }
+
+    fn storage_within(
+        num_words: usize,
+        layout: CacheLayout,
+        budget: Option<usize>,
+    ) -> CacheStorage {
+        let Some(budget) = budget else {
+            return CacheStorage::Dense;
+        };
+        let row_bytes = num_words.div_ceil(CACHE_LINE) * CACHE_LINE;
+        if num_words * row_bytes <= budget {
+            return CacheStorage::Dense;
+        }
+        // Lazy rows only help if the rows are guesses (every candidate left is an answer whose
+        // row the very first guess uses), and only if the ones that get used fit.
+        let max_rows = budget
+            .saturating_sub(num_words * std::mem::size_of::<OnceCell<Box<[CacheLine]>>>())
+            / row_bytes;
+        if layout == CacheLayout::GuessMajor && max_rows >= num_words.div_ceil(3) {
+            CacheStorage::LazyRows(max_rows)
+        } else {
+            CacheStorage::Mapped
+        }
+    }
+
+    /// The masks precomputed in `path`, if they are for the dictionary in use and `layout`.
+    fn precomputed(path: &Path, layout: CacheLayout) -> Result<Self, String> {
+        Ok(Cache {
+            cells: Cells::Precomputed(matrix::map(path, layout)?),
+            lines_per_row: dictionary().len().div_ceil(CACHE_LINE),
+            layout,
+            fallbacks: Vec::new(),
+        })
+    }
+
+    fn with_storage(num_words: usize, layout: CacheLayout, storage: CacheStorage) -> Self {
+        let lines_per_row = num_words.div_ceil(CACHE_LINE);
+        let cells = match storage {
+            CacheStorage::Dense => Cells::Dense(zeroed_lines(num_words * lines_per_row)),
+            CacheStorage::LazyRows(max_rows) => Cells::LazyRows {
+                rows: (0..num_words).map(|_| OnceCell::new()).collect(),
+                allocated: AtomicUsize::new(0),
+                max_rows,
+            },
+            CacheStorage::Mapped => match map_temporary(num_words * lines_per_row * CACHE_LINE) {
+                Ok(map) => Cells::Mapped(map),
+                Err(e) => {
+                    let mut cache = Self::with_storage(
+                        num_words,
+                        layout,
+                        CacheStorage::LazyRows(num_words / 3),
+                    );
+                    cache.fallbacks.push(format!(
+                        "could not map the cache to a file ({}), so keeping some of it in memory",
+                        e
+                    ));
+                    return cache;
+                }
+            },
+            CacheStorage::Precomputed => panic!("precomputed masks are loaded from a file"),
+        };
+        Cache {
+            cells,
+            lines_per_row,
+            layout,
+            fallbacks: Vec::new(),
+        }
+    }
+
+    fn storage(&self) -> CacheStorage {
+        match &self.cells {
+            Cells::Dense(_) => CacheStorage::Dense,
+            Cells::LazyRows { max_rows, .. } => CacheStorage::LazyRows(*max_rows),
+            Cells::Mapped(_) => CacheStorage::Mapped,
+            Cells::Precomputed(_) => CacheStorage::Precomputed,
+        }
+    }
+
+    /// The cell for the mask of `guess_idx` against `answer_idx`, unless it isn't being cached.
     #[inline]
-    fn get(&self) -> &[[Cell<Option<CacheValue>>; NUM_WORDS]; NUM_WORDS] {
-        &self.0
+    fn get(&self, guess_idx: usize, answer_idx: usize) -> Option<&AtomicU8> {
+        let (row, column) = match self.layout {
+            CacheLayout::GuessMajor => (guess_idx, answer_idx),
+            CacheLayout::CandidateMajor => (answer_idx, guess_idx),
+        };
+        match &self.cells {
+            Cells::Dense(lines) => {
+                Some(&lines[row * self.lines_per_row + column / CACHE_LINE].0[column % CACHE_LINE])
+            }
+            Cells::LazyRows {
+                rows,
+                allocated,
+                max_rows,
+            } => {
+                let row = match rows[row].get() {
+                    Some(row) => row,
+                    None if allocated.load(Ordering::Relaxed) >= *max_rows => return None,
+                    None => rows[row].get_or_init(|| {
+                        allocated.fetch_add(1, Ordering::Relaxed);
+                        zeroed_lines(self.lines_per_row)
+                    }),
+                };
+                Some(&row[column / CACHE_LINE].0[column % CACHE_LINE])
+            }
+            Cells::Mapped(map) | Cells::Precomputed(map) => {
+                let i = (row * self.lines_per_row) * CACHE_LINE + column;
+                assert!(i < map.len());
+                // SAFETY: `AtomicU8` has the same layout as `u8`, the index is in bounds, and the
+                // map is only ever accessed through these atomics.
+                Some(unsafe { &*(map.as_ptr().add(i) as *const AtomicU8) })
+            }
+        }
     }
 }
 
-impl Default for Cache {
-    fn default() -> Self {
-        Cache([ROW; NUM_WORDS])
-    }
+fn zeroed_lines<C: FromIterator<CacheLine>>(n: usize) -> C {
+    (0..n)
+        .map(|_| CacheLine([(); CACHE_LINE].map(|_| AtomicU8::new(0))))
+        .collect()
 }
-thread_local! {
-    static COMPUTES: UnSyncOnceCell<Box<Cache >> = Default::default();
+
+/// Maps a new temporary file of `len` zero bytes into memory. The file goes in the user's cache
+/// directory, since the temporary directory is often kept in memory itself.
+fn map_temporary(len: usize) -> std::io::Result<MmapMut> {
+    let dir = default_cache_dir().unwrap_or_else(std::env::temp_dir);
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("masks-{}.tmp", std::process::id()));
+    let file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&path)?;
+    file.set_len(len as u64)?;
+    // SAFETY: the file was just created by this process, which is the only one that uses it.
+    let map = unsafe { MmapMut::map_mut(&file) };
+    // The mapping keeps the file alive (except on Windows, where it can't be removed while in
+    // use, and is left behind).
+    let _ = std::fs::remove_file(&path);
+    map
+}
+
+static COMPUTES: OnceCell<Cache> = OnceCell::new();
+
+/// The cache shared by every guesser that uses it, which is set up (as the layout, memory budget
+/// and pattern file say) the first time one is created.
+fn computes() -> &'static Cache {
+    COMPUTES.get_or_init(|| {
+        let layout = Cached::layout();
+        let precomputed = match PATTERN_FILE.get_or_init(|| None) {
+            Some(path) => Some(Cache::precomputed(path, layout)),
+            // Only use the masks the precompute command saved by default if they are there.
+            None => PatternMatrix::default_path(layout)
+                .filter(|path| path.exists())
+                .map(|path| Cache::precomputed(&path, layout)),
+        };
+        match precomputed {
+            Some(Ok(cache)) => cache,
+            Some(Err(e)) => {
+                let mut cache = Cache::within(dictionary().len(), layout, Cached::memory_budget());
+                cache
+                    .fallbacks
+                    .insert(0, format!("not using the precomputed masks: {}", e));
+                cache
+            }
+            None => Cache::within(dictionary().len(), layout, Cached::memory_budget()),
+        }
+    })
+}
+
+/// Looks masks up in the cache [`Cached`] keeps, for other guessers that work out the masks of
+/// the same words over and over, counting the lookups for the telemetry.
+pub(super) struct Masks {
+    cache: &'static Cache,
+    words: &'static Words,
+    lookups: Lookups,
+}
+
+impl Masks {
+    /// # Panics
+    ///
+    /// If the words in the dictionary have more than five letters.
+    pub(super) fn new() -> Self {
+        assert!(
+            word_len() <= MAX_CACHED_WORD_LEN,
+            "the cache only fits words of up to {} letters",
+            MAX_CACHED_WORD_LEN
+        );
+        Self {
+            cache: computes(),
+            words: words(),
+            lookups: Lookups::default(),
+        }
+    }
+
+    /// The packed mask of `guess` against `answer`.
+    #[inline]
+    pub(super) fn packed(&mut self, guess: WordIndex, answer: WordIndex) -> usize {
+        get_correctness_packed(self.cache, self.words, guess, answer, &mut self.lookups)
+    }
+
+    /// Adds the lookups made so far to the run's telemetry.
+    pub(super) fn record(&mut self) {
+        std::mem::take(&mut self.lookups).record();
+    }
 }
 
+#[derive(Clone)]
 pub struct Cached {
-    remaining: Cow<'static, Vec<(&'static str, f64, usize)>>,
-    patterns: Cow<'static, Vec<[Correctness; 5]>>,
+    remaining: Cow<'static, [(WordIndex, f64)]>,
     entropy: Vec<f64>,
+    tiebreak: Arc<dyn TieBreak>,
+    lambda: f64,
+    budget: Option<TimeBudget>,
+    precision: Precision,
+    schedule: SearchSchedule,
+    calibration: Option<Arc<Calibration>>,
+    explanations: Option<Explanations>,
 }
 
 impl Default for Cached {
@@ -54,200 +381,272 @@ impl Default for Cached {
     }
 }
 
-// This is an estimation function for how many _more_ guesses are needed given that `entropy`
-// entropy remains. It was constructed by iterative regression.
-//
-// First, I logged the observed remaining entropy + remaining guesses with an implementation that
-// just tries to maximize the -sum of the candidates (entropy-initial.dat). I then ran that through
-// logistical regression (see `escore-regress.r`). That gave
-//
-//   E[guesses] = entropy * 0.2592 + 1.3202
-//   E[guesses] = ln(entropy * 4.066 + 3.755)
-//   E[guesses] = e^(entropy * 0.1346 + 0.2210)
-//   E[guesses] = 1/(entropy * -0.07977 + 0.84147)
-//   E[guesses] = (entropy * 0.09177 + 1.13241)^2
-//   E[guesses] = sqrt(entropy * 1.151 + 1.954)
-//
-// and an average score of 3.7631.
-//
-// Then, I ran the E[score] algorithm using the E[guesses] function determined by each of the first
-// regressions, which gave the commented-out scores in the fn body below. I then proceeded with the
-// best candidate (ln), and re-ran the regression on it, which gave
-//
-//   E[guesses] = ln(entropy * 3.869 + 3.679)
-//
-// and an average score of 3.7176 (worse than the first estimate). Further iterations did not
-// change the parameters much, so I stuck with that last estimat.
-//
-// Below are also the formulas and average scores when using different regressions. Interestingly,
-// the regression that does the best also tends to overestimate the number of guesses remaining,
-// which causes the model to "go for the win" less often, and instead focus on "best information"
-// guesses.
-fn est_steps_left(entropy: f64) -> f64 {
-    // entropy * 0.2592 + 1.3202 // 3.7181
-    // (entropy * 4.066 + 3.755).ln() // 3.7172
-    // (entropy * 0.1346 + 0.2210).exp() // 3.7237
-    // 1.0 / (entropy * -0.07977 + 0.84147) // 3.7246
-    // (entropy * 0.09177 + 1.13241).powi(2) // 3.7176
-    // (entropy * 1.151 + 1.954).sqrt() // 3.7176
-    // (entropy * 3.869 + 3.679).ln() // 3.7176
-    (entropy * 3.870 + 3.679).ln() // 3.7176
-}
 const PRINT_ESTIMATION: bool = false;
 
-const L: f64 = 1.0;
-// How steep is the cut-off?
-const K: f64 = 30000000.0;
-// Where is the cut-off?
-const X0: f64 = 0.00000497;
-// This combination of settings leads to a fairly sharp cut-off around:
-//
-//  word  "raw" p      sigmoid p   count
-// vying 0.000491% -> 15.999853% (1560905)
-// rheum 0.000492% -> 16.735476% (1561474)
-// lamas 0.000492% -> 16.827817% (1561544)
-// kraal 0.000492% -> 17.389054% (1561963)
-// gents 0.000493% -> 25.448008% (1567081)
-// hails 0.000494% -> 29.575062% (1569275)
-// atria 0.000494% -> 30.602258% (1569792)
-// wooed 0.000495% -> 32.304510% (1570628)
-// kinks 0.000495% -> 32.904357% (1570917)
-// sushi 0.000495% -> 34.261053% (1571561)
-// filly 0.000496% -> 39.634216% (1574006)
-// lazar 0.000496% -> 43.073524% (1575508)
-// lapel 0.000497% -> 48.215265% (1577704)
-// cecum 0.000497% -> 48.505414% (1577827)
-// kayak 0.000497% -> 49.307915% (1578167)
-// fundy 0.000498% -> 55.755065% (1580908)
-// haply 0.000498% -> 56.508662% (1581232)
-// bigot 0.000498% -> 57.402526% (1581618)
-// wisps 0.000498% -> 57.407146% (1581620)
-// foals 0.000498% -> 57.875326% (1581823)
-fn sigmoid(p: f64) -> f64 {
-    L / (1.0 + (-K * (p - X0)).exp())
-}
-const PRINT_SIGMOID: bool = false;
+// Once this few candidates are left, only consider the guesses that give the best chance of
+// finishing within the six guesses Wordle allows, as found by playing out every possible feedback.
+const EXACT_SOLVE_LIMIT: usize = 64;
+// How many guesses ahead to look when doing so, since it gets exponentially slower the further
+// ahead it looks. Beyond this, the chance of solving within this many guesses is used instead.
+const EXACT_SOLVE_DEPTH: usize = 3;
+// Once this few candidates are left, replace `est_steps_left` with the exact expected number of
+// guesses, which is where the regression's error matters most. Going higher than this makes the
+// score worse (3.7172 at 20), since the exact expectation is only as good as the sigmoid's guess
+// at how likely each word is to be the answer.
+const EXACT_SCORE_LIMIT: usize = 8;
 
 impl Cached {
+    /// # Panics
+    ///
+    /// If the words in the dictionary have more than five letters.
     pub fn new() -> Self {
-        let remaining = Cow::Borrowed(INITIAL.get_or_init(|| {
-            let sum: usize = DICTIONARY.iter().map(|(_, count)| count).sum();
-
-            if PRINT_SIGMOID {
-                for &(word, count) in DICTIONARY.iter().rev() {
-                    let p = count as f64 / sum as f64;
-                    println!(
-                        "{} {:.6}% -> {:.6}% ({})",
-                        word,
-                        100.0 * p,
-                        100.0 * sigmoid(p),
-                        count
-                    );
-                }
-            }
+        assert!(
+            word_len() <= MAX_CACHED_WORD_LEN,
+            "the cache only fits words of up to {} letters",
+            MAX_CACHED_WORD_LEN
+        );
+        computes();
+        Self {
+            remaining: Cow::Borrowed(weighted_indexed()),
+            entropy: Vec::new(),
+            tiebreak: Arc::new(Frequency),
+            lambda: 1.0,
+            budget: None,
+            precision: Precision::F64,
+            schedule: SearchSchedule::default(),
+            calibration: None,
+            explanations: None,
+        }
+    }
 
-            let words: Vec<_> = DICTIONARY
-                .iter()
-                .copied()
-                .enumerate()
-                .map(|(idx, (word, count))| (word, sigmoid(count as f64 / sum as f64), idx))
-                .collect();
+    /// Picks how the cache lays out its masks from now on. The cache is shared by every `Cached`
+    /// in the process, so this fails if it is already in use with a different layout.
+    pub fn set_layout(layout: CacheLayout) -> Result<(), String> {
+        match LAYOUT.try_insert(layout) {
+            Ok(_) => Ok(()),
+            Err((current, layout)) if *current == layout => Ok(()),
+            Err((current, _)) => Err(format!("the cache is already laid out {}", current)),
+        }
+    }
 
-            words
-        }));
+    /// How the cache lays out its masks, which is guess-major unless another layout was picked
+    /// before the first `Cached` was created.
+    pub fn layout() -> CacheLayout {
+        *LAYOUT.get_or_init(CacheLayout::default)
+    }
 
-        COMPUTES.with(|c| {
-            c.get_or_init(|| Box::default());
-        });
+    /// Keeps the cache within `bytes` of memory from now on, by only allocating the rows that
+    /// get used, or failing that by keeping it in a memory-mapped file. The cache is shared by
+    /// every `Cached` in the process, so this fails if it is already in use with a different
+    /// budget.
+    pub fn set_memory_budget(bytes: usize) -> Result<(), String> {
+        match MEMORY_BUDGET.try_insert(Some(bytes)) {
+            Ok(_) => Ok(()),
+            Err((current, budget)) if *current == budget => Ok(()),
+            Err((None, _)) => Err("the cache is already in use without a memory budget".into()),
+            Err((Some(current), _)) => Err(format!(
+                "the cache is already in use with a memory budget of {} bytes",
+                current
+            )),
+        }
+    }
 
-        Self {
-            remaining,
-            patterns: Cow::Borrowed(PATTERNS.get_or_init(|| Correctness::patterns().collect())),
-            entropy: Vec::new(),
+    /// How many bytes the cache may take, if it is limited.
+    pub fn memory_budget() -> Option<usize> {
+        *MEMORY_BUDGET.get_or_init(|| None)
+    }
+
+    /// Reads the masks from `path`, as saved by [`PatternMatrix::save`], rather than from where
+    /// the precompute command saves them by default (if they are there) or working them out as
+    /// they are needed. The cache is shared by every `Cached` in the process, so this fails if it
+    /// is already in use with other masks.
+    pub fn set_pattern_file(path: impl Into<PathBuf>) -> Result<(), String> {
+        match PATTERN_FILE.try_insert(Some(path.into())) {
+            Ok(_) => Ok(()),
+            Err((current, path)) if *current == path => Ok(()),
+            Err(_) => Err("the cache is already in use with other masks".into()),
         }
     }
+
+    /// Where the cache keeps its masks, once the first `Cached` has been created.
+    pub fn storage() -> Option<CacheStorage> {
+        COMPUTES.get().map(Cache::storage)
+    }
+
+    /// Why the cache doesn't keep its masks where it was set up to, such as precomputed masks that
+    /// couldn't be read or a temporary file that couldn't be mapped, once the first `Cached` has
+    /// been created.
+    pub fn fallbacks() -> &'static [String] {
+        COMPUTES.get().map_or(&[], |cache| &cache.fallbacks)
+    }
+
+    /// How much to favour guesses that are likely to be the answer over ones that give more
+    /// information: the chance of being the answer is raised to the power `lambda` before it is
+    /// used to estimate the score, as [`Sigmoid`](super::Sigmoid) does. 0 treats every candidate
+    /// as a sure win, higher values discount the unlikely ones more, and the default is 1.
+    pub fn with_lambda(mut self, lambda: f64) -> Self {
+        self.lambda = lambda;
+        self
+    }
+
+    /// Limits how long a whole game may take, by considering fewer candidates for a guess once
+    /// its share of the time is used up. This makes the guesses (slightly) worse, and no longer
+    /// the same from one run to the next.
+    pub fn with_time_budget(mut self, per_game: Duration) -> Self {
+        self.budget = Some(TimeBudget::new(per_game));
+        self
+    }
+
+    /// Works out how likely each feedback is in `precision`, which is faster in single precision
+    /// on large candidate sets, but may pick a different guess when two are about as good.
+    pub fn with_precision(mut self, precision: Precision) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Considers `width` of the candidates as guesses, rather than a third of them.
+    pub fn with_search_width(mut self, width: SearchWidth) -> Self {
+        self.schedule.default = width;
+        self
+    }
+
+    /// Considers as many of the candidates as guesses as `schedule` says for each guess.
+    pub fn with_search_schedule(mut self, schedule: SearchSchedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+
+    /// Considers as many of the candidates as `calibration` has tuned the width to, and helps it
+    /// tune the width further.
+    pub fn with_calibration(mut self, calibration: Arc<Calibration>) -> Self {
+        self.calibration = Some(calibration);
+        self
+    }
+
+    /// Hands `report` the `top` guesses it scored best every turn, along with what they scored.
+    pub fn with_explanations(
+        mut self,
+        top: usize,
+        report: impl Fn(&GuessExplanation) + Send + Sync + 'static,
+    ) -> Self {
+        self.explanations = Some(Explanations::new(top, report));
+        self
+    }
 }
 
+/// The packed mask of `guess` against `answer`, which are only looked up in `words` if it hasn't
+/// been cached yet.
 #[inline]
 fn get_correctness_packed(
-    row: &[Cell<Option<CacheValue>>],
-    guess: &str,
-    answer: &str,
-    answer_idx: usize,
-) -> u8 {
-    let cell = &row[answer_idx];
-    match cell.get() {
-        Some(a) => a.get(),
-        None => {
-            let correctness = Correctness::pack(&Correctness::compute(answer, guess));
-            cell.set(Some(CacheValue::new(correctness)));
+    cache: &Cache,
+    words: &Words,
+    guess: WordIndex,
+    answer: WordIndex,
+    lookups: &mut Lookups,
+) -> usize {
+    lookups.lookups += 1;
+    let compute = || Correctness::compute_packed(words.word(answer), words.word(guess));
+    let Some(cell) = cache.get(usize::from(guess), usize::from(answer)) else {
+        return compute();
+    };
+    match cell.load(Ordering::Relaxed) {
+        0 => {
+            let correctness = compute();
+            cell.store(correctness as u8 + 1, Ordering::Relaxed);
             correctness
         }
+        cached => {
+            lookups.hits += 1;
+            usize::from(cached - 1)
+        }
     }
 }
 
-#[derive(Debug, Copy, Clone)]
-struct Candidate {
-    word: &'static str,
-    e_score: f64,
+impl BreaksTies for Cached {
+    fn tiebreak_mut(&mut self) -> &mut Arc<dyn TieBreak> {
+        &mut self.tiebreak
+    }
 }
 
 impl Guesser for Cached {
-    fn guess(&mut self, history: &[Guess]) -> String {
+    fn guess(&mut self, history: &[Guess]) -> Cow<'static, str> {
+        let start = Instant::now();
         let score = history.len() as f64;
+        let mut lookups = Lookups::default();
 
         if let Some(last) = history.last() {
-            let reference = Correctness::pack(&last.mask);
-            let last_idx = self
-                .remaining
-                .iter()
-                .find(|(word, _, _)| &*last.word == *word)
-                .unwrap()
-                .2;
-            COMPUTES.with(|c| {
-                let row = &c.get().unwrap().get()[last_idx];
-                if matches!(self.remaining, Cow::Owned(_)) {
-                    self.remaining.to_mut().retain(|(word, _, word_idx)| {
-                        reference == get_correctness_packed(row, &last.word, word, *word_idx)
-                    });
-                } else {
-                    self.remaining = Cow::Owned(
-                        self.remaining
-                            .iter()
-                            .filter(|(word, _, word_idx)| {
-                                reference
-                                    == get_correctness_packed(row, &last.word, word, *word_idx)
-                            })
-                            .copied()
-                            .collect(),
-                    );
-                }
-            });
+            let reference = Correctness::pack(&last.mask[..letter_count(&last.word)]);
+            // The last guess need not be one of the remaining candidates (e.g. when a human picked
+            // it), so look its row up in the full word list.
+            let last_idx = WordIndex::of(&last.word).unwrap();
+            let cache = COMPUTES.get().unwrap();
+            let mut matches = |word| {
+                reference == get_correctness_packed(cache, words(), last_idx, word, &mut lookups)
+            };
+            if matches!(self.remaining, Cow::Owned(_)) {
+                self.remaining.to_mut().retain(|&(word, _)| matches(word));
+            } else {
+                self.remaining = Cow::Owned(
+                    self.remaining
+                        .iter()
+                        .filter(|&&(word, _)| matches(word))
+                        .copied()
+                        .collect(),
+                );
+            }
         }
         if history.is_empty() {
-            self.patterns = Cow::Borrowed(PATTERNS.get().unwrap());
             // NOTE: I did a manual run with this commented out and it indeed produced "tares" as
             // the first guess. It slows down the run by a lot though.
-            return "tares".to_string();
-        } else {
-            assert!(!self.patterns.is_empty());
+            if let Some(opener) = first_guess() {
+                if let Some(explanations) = &self.explanations {
+                    explanations.report_opener(opener);
+                }
+                lookups.record();
+                return Cow::Borrowed(opener);
+            }
         }
 
-        let remaining_p: f64 = self.remaining.iter().map(|&(_, p, _)| p).sum();
-        let remaining_entropy = -self
-            .remaining
-            .iter()
-            .map(|&(_, p, _)| {
-                let p = p / remaining_p;
-                p * p.log2()
-            })
-            .sum::<f64>();
+        let remaining_p = summation::sum(self.remaining.iter().map(|&(_, p)| p));
+        let remaining_entropy = -summation::sum(self.remaining.iter().map(|&(_, p)| {
+            let p = p / remaining_p;
+            p * p.log2()
+        }));
         self.entropy.push(remaining_entropy);
 
-        let mut best: Option<Candidate> = None;
-        let mut i = 0;
-        let stop = (self.remaining.len() / 3).max(20);
-        for &(word, count, word_idx) in &*self.remaining {
+        let mut exact = Exact::default();
+        let guesses_left = 6usize.saturating_sub(history.len()).min(EXACT_SOLVE_DEPTH);
+        let candidates: Vec<_> = if self.remaining.len() <= EXACT_SOLVE_LIMIT {
+            self.remaining.iter().map(|&(w, p)| (w.word(), p)).collect()
+        } else {
+            Vec::new()
+        };
+        let shortlist = if guesses_left > 0 && self.remaining.len() <= EXACT_SOLVE_LIMIT {
+            let p_solve: Vec<_> = candidates
+                .iter()
+                .map(|&(word, _)| exact.solve_probability_with(word, &candidates, guesses_left))
+                .collect();
+            let max = p_solve.iter().copied().fold(0.0, f64::max);
+            Some(
+                p_solve
+                    .into_iter()
+                    .map(|p| p >= max - 1e-9)
+                    .collect::<Vec<_>>(),
+            )
+        } else {
+            None
+        };
+
+        let deadline = self.budget.map(|b| b.deadline(start, history.len()));
+        let mut best = Best::new(&*self.tiebreak);
+        let width = self.schedule.width(history.len() + 1, || remaining_entropy);
+        let mut search = Search::new(width, self.calibration.as_deref(), self.remaining.len());
+        let mut top = self.explanations.as_ref().map(Explanations::shortlist);
+        let words = words();
+        for (n, &(word_idx, count)) in self.remaining.iter().enumerate() {
+            if matches!(&shortlist, Some(shortlist) if !shortlist[n]) {
+                continue;
+            }
             // considering a world where we _did_ guess `word` and got `pattern` as the
             // correctness. now, compute what _then_ is left.
 
@@ -255,44 +654,66 @@ impl Guesser for Cached {
             // that result in that pattern, we can instead keep a running total for each pattern
             // simultaneously by storing them in an array. We can do this since each candidate-word
             // pair deterministically produces only one mask.
-            let mut totals = [0.0f64; MAX_MASK_ENUM];
-
-            COMPUTES.with(|c| {
-                let row = &c.get().unwrap().get()[word_idx];
-                for (candidate, count, candidate_idx) in &*self.remaining {
-                    let idx = get_correctness_packed(row, word, candidate, *candidate_idx);
-                    totals[usize::from(idx)] += count;
+            let cache = COMPUTES.get().unwrap();
+            let mut mask =
+                |candidate| get_correctness_packed(cache, words, word_idx, candidate, &mut lookups);
+            let e_info = match self.precision {
+                Precision::F64 => {
+                    let mut totals = [0.0f64; MAX_CACHED_PATTERNS];
+                    for &(candidate, count) in &*self.remaining {
+                        totals[mask(candidate)] += count;
+                    }
+                    search.count_buckets(|| totals.iter().filter(|&&t| t != 0.0).count());
+                    -summation::sum(totals.into_iter().filter(|t| *t != 0.0).map(|p| {
+                        let p_of_this_pattern = p / remaining_p;
+                        p_of_this_pattern * p_of_this_pattern.log2()
+                    }))
                 }
-            });
-
-            let sum: f64 = totals
-                .into_iter()
-                .filter(|t| *t != 0.0)
-                .map(|p| {
-                    let p_of_this_pattern = p as f64 / remaining_p as f64;
-                    p_of_this_pattern * p_of_this_pattern.log2()
-                })
-                .sum();
-
-            let p_word = count as f64 / remaining_p as f64;
-            let e_info = -sum;
-            let e_score = p_word * (score + 1.0)
-                + (1.0 - p_word) * (score + est_steps_left(remaining_entropy - e_info));
-            if let Some(c) = best {
-                // Which one gives us a lower (expected) score?
-                if e_score < c.e_score {
-                    best = Some(Candidate { word, e_score });
+                Precision::F32 => {
+                    let mut totals = [0.0f32; MAX_CACHED_PATTERNS];
+                    for &(candidate, count) in &*self.remaining {
+                        totals[mask(candidate)] += count as f32;
+                    }
+                    search.count_buckets(|| totals.iter().filter(|&&t| t != 0.0).count());
+                    information_f32(&totals, remaining_p)
                 }
+            };
+
+            let word = words.word(word_idx);
+            let p_word = (count / remaining_p).powf(self.lambda);
+            let e_score = if self.remaining.len() <= EXACT_SCORE_LIMIT {
+                score + exact.expected_guesses_with(word, &candidates)
             } else {
-                best = Some(Candidate { word, e_score });
+                p_word * (score + 1.0)
+                    + (1.0 - p_word) * (score + est_steps_left(remaining_entropy - e_info))
+            };
+            // Lower (expected) scores are better.
+            best.consider(word, -e_score);
+            if let Some(top) = &mut top {
+                top.consider(
+                    -e_score,
+                    ScoredGuess {
+                        word,
+                        information: e_info,
+                        probability: count / remaining_p,
+                        expected_score: Some(e_score),
+                    },
+                );
             }
 
-            i += 1;
-            if i >= stop {
+            if search.considered(&best) || out_of_time(deadline) {
                 break;
             }
         }
-        best.unwrap().word.to_string()
+        if let Some(budget) = &mut self.budget {
+            budget.spend(start);
+        }
+        lookups.record();
+        let guess = search.finish(&best);
+        if let (Some(explanations), Some(top)) = (&self.explanations, top) {
+            explanations.report(history.len() + 1, self.remaining.len(), guess, top);
+        }
+        Cow::Borrowed(guess)
     }
 
     fn finish(&self, guesses: usize) {
@@ -307,4 +728,108 @@ impl Guesser for Cached {
             }
         }
     }
+
+    fn reset(&mut self) {
+        self.remaining = Cow::Borrowed(weighted_indexed());
+        self.entropy.clear();
+        if let Some(budget) = &mut self.budget {
+            budget.reset();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_correctness_packed, Cache, CacheLayout, CacheStorage, Cells, Lookups, Words};
+    use crate::Correctness;
+    use std::sync::atomic::Ordering;
+
+    #[test]
+    fn layouts_agree() {
+        let words = ["cigar", "rebut", "sissy", "humph", "awake"];
+        let indexed = Words::new(words);
+        for layout in [CacheLayout::GuessMajor, CacheLayout::CandidateMajor] {
+            let cache = Cache::new(words.len(), layout);
+            for _ in 0..2 {
+                for guess in words {
+                    for answer in words {
+                        assert_eq!(
+                            get_correctness_packed(
+                                &cache,
+                                &indexed,
+                                indexed.index(guess).unwrap(),
+                                indexed.index(answer).unwrap(),
+                                &mut Lookups::default(),
+                            ),
+                            Correctness::compute_packed(answer, guess),
+                            "{} against {} laid out {}",
+                            guess,
+                            answer,
+                            layout
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn storages_agree() {
+        let words = ["cigar", "rebut", "sissy", "humph", "awake"];
+        let indexed = Words::new(words);
+        for storage in [
+            CacheStorage::Dense,
+            CacheStorage::LazyRows(2),
+            CacheStorage::Mapped,
+        ] {
+            let cache = Cache::with_storage(words.len(), CacheLayout::GuessMajor, storage);
+            assert_eq!(cache.storage(), storage);
+            for _ in 0..2 {
+                for guess in words {
+                    for answer in words {
+                        assert_eq!(
+                            get_correctness_packed(
+                                &cache,
+                                &indexed,
+                                indexed.index(guess).unwrap(),
+                                indexed.index(answer).unwrap(),
+                                &mut Lookups::default(),
+                            ),
+                            Correctness::compute_packed(answer, guess),
+                            "{} against {} stored {}",
+                            guess,
+                            answer,
+                            storage
+                        );
+                    }
+                }
+            }
+            if let Cells::LazyRows { allocated, .. } = &cache.cells {
+                assert_eq!(allocated.load(Ordering::Relaxed), 2);
+            }
+        }
+    }
+
+    #[test]
+    fn storage_within_budget() {
+        use CacheLayout::*;
+        // 100 words take 100 rows of two cache lines.
+        let storage = |layout, budget| Cache::storage_within(100, layout, budget);
+        assert_eq!(storage(GuessMajor, None), CacheStorage::Dense);
+        assert_eq!(storage(GuessMajor, Some(12_800)), CacheStorage::Dense);
+        assert!(matches!(
+            storage(GuessMajor, Some(8_000)),
+            CacheStorage::LazyRows(rows) if (34..63).contains(&rows)
+        ));
+        assert_eq!(storage(CandidateMajor, Some(8_000)), CacheStorage::Mapped);
+        assert_eq!(storage(GuessMajor, Some(1_000)), CacheStorage::Mapped);
+    }
+
+    #[test]
+    fn parse_layout() {
+        for layout in [CacheLayout::GuessMajor, CacheLayout::CandidateMajor] {
+            assert_eq!(layout.to_string().parse(), Ok(layout));
+        }
+        assert!("row-major".parse::<CacheLayout>().is_err());
+    }
 }
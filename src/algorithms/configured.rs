@@ -0,0 +1,522 @@
+use super::search::{entropy_of_counts, Search, SearchSchedule, SearchWidth};
+use super::tiebreak::{self, Best, TieBreak};
+use super::{fixed, summation};
+use crate::{
+    candidate_answers, dictionary, dictionary_info, letter_count, word_len, Correctness, Guess,
+    Guesser, GuesserFactory,
+};
+use once_cell::sync::OnceCell;
+use std::borrow::Cow;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// A strategy composed from a handful of existing scoring primitives, so that different
+/// behaviours can be tried without writing any Rust.
+///
+/// Strategies are written as one rule per line (or separated by `;`), for example:
+///
+/// ```text
+/// opener tares
+/// sort by entropy
+/// tiebreak frequency
+/// if remaining <= 3 guess most frequent
+/// hard-mode on
+/// ```
+///
+/// The rules are:
+///
+/// - `opener <word>`: the first guess (default `tares`).
+/// - `sort by entropy|weighted-entropy|frequency|letter-elimination|expected-greens`: how to pick
+///   the best guess (default `entropy`). `weighted-entropy` multiplies the entropy by the chance
+///   of the guess being the answer. `letter-elimination` counts the letters of the guess that
+///   some, but not all, of the candidates contain, since guessing them will either confirm or rule
+///   them out. `expected-greens` is the number of letters the guess is expected to get in the
+///   right place, which matters most in hard mode, where every green has to be kept.
+/// - `tiebreak frequency|alphabetical|fewest-repeats|random <seed>`: how to choose between equally
+///   good guesses (default `frequency`). See [`tiebreak`] for what each one does.
+/// - `if remaining <= <n> guess most frequent`: once only a few candidates are left, just go for
+///   the most likely one.
+/// - `guess from candidates|dictionary`: whether to only guess words that could be the answer, or
+///   any word that could give more information (default `candidates`).
+/// - `hard-mode on|off`: only guess words that reuse every revealed green and yellow letter.
+/// - `search <clause>`: how many of the words that could be guessed to consider, likeliest first
+///   (default all of them), where the clause is one of those of a [`SearchSchedule`], e.g.
+///   `search round 2 all` and `search below 3 bits 0.1 min 5`. Each `search` rule adds a clause.
+/// - `arithmetic float|fixed`: how to work out the entropy (default `float`). `fixed` only uses
+///   integers and a table of logarithms, so it picks exactly the same guesses on every platform.
+/// - `dictionary <version>`: refuse to load the strategy with any other version of the dictionary
+///   than the one it was tuned for (see [`DictionaryInfo::version`](crate::DictionaryInfo::version)).
+///
+/// Lines starting with `#` are ignored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Strategy {
+    pub opener: String,
+    pub sort: Metric,
+    pub tiebreak: TieBreakRule,
+    pub endgame: Option<usize>,
+    pub from_dictionary: bool,
+    pub hard_mode: bool,
+    pub arithmetic: Arithmetic,
+    pub search: SearchSchedule,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    Entropy,
+    WeightedEntropy,
+    Frequency,
+    LetterElimination,
+    ExpectedGreens,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreakRule {
+    Frequency,
+    Alphabetical,
+    FewestRepeats,
+    Random(u64),
+}
+
+/// How a [`Configured`] guesser works out the entropy of a guess.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arithmetic {
+    /// In floating point, which (like the other guessers) may round differently on different
+    /// platforms.
+    Float,
+    /// In fixed point, which gives the same result everywhere.
+    Fixed,
+}
+
+impl TieBreakRule {
+    fn tiebreak(self) -> Arc<dyn TieBreak> {
+        match self {
+            Self::Frequency => Arc::new(tiebreak::Frequency),
+            Self::Alphabetical => Arc::new(tiebreak::Alphabetical),
+            Self::FewestRepeats => Arc::new(tiebreak::FewestRepeats),
+            Self::Random(seed) => Arc::new(tiebreak::Random::new(seed)),
+        }
+    }
+}
+
+impl Default for Strategy {
+    fn default() -> Self {
+        Self {
+            opener: "tares".to_string(),
+            sort: Metric::Entropy,
+            tiebreak: TieBreakRule::Frequency,
+            endgame: None,
+            from_dictionary: false,
+            hard_mode: false,
+            arithmetic: Arithmetic::Float,
+            search: SearchSchedule::from(SearchWidth {
+                fraction: 1.0,
+                min: 0,
+            }),
+        }
+    }
+}
+
+impl FromStr for Strategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut strategy = Self::default();
+        for rule in s.split(['\n', ';']).map(str::trim) {
+            if rule.is_empty() || rule.starts_with('#') {
+                continue;
+            }
+            let words: Vec<_> = rule.split_whitespace().collect();
+            match words[..] {
+                ["opener", word] if letter_count(word) == word_len() => {
+                    strategy.opener = word.to_string()
+                }
+                ["sort", "by", metric] => {
+                    strategy.sort = match metric {
+                        "entropy" => Metric::Entropy,
+                        "weighted-entropy" => Metric::WeightedEntropy,
+                        "frequency" => Metric::Frequency,
+                        "letter-elimination" => Metric::LetterElimination,
+                        "expected-greens" => Metric::ExpectedGreens,
+                        _ => return Err(format!("unknown metric '{}'", metric)),
+                    }
+                }
+                ["tiebreak", "random", seed] => {
+                    let seed = seed
+                        .parse()
+                        .map_err(|_| format!("'{}' is not a number", seed))?;
+                    strategy.tiebreak = TieBreakRule::Random(seed);
+                }
+                ["tiebreak", tiebreak] => {
+                    strategy.tiebreak = match tiebreak {
+                        "frequency" => TieBreakRule::Frequency,
+                        "alphabetical" => TieBreakRule::Alphabetical,
+                        "fewest-repeats" => TieBreakRule::FewestRepeats,
+                        _ => return Err(format!("unknown tiebreak '{}'", tiebreak)),
+                    }
+                }
+                ["if", "remaining", "<=" | "≤", n, "guess", "most", "frequent"] => {
+                    let n = n.parse().map_err(|_| format!("'{}' is not a number", n))?;
+                    strategy.endgame = Some(n);
+                }
+                ["guess", "from", "candidates"] => strategy.from_dictionary = false,
+                ["guess", "from", "dictionary"] => strategy.from_dictionary = true,
+                ["hard-mode", "on"] => strategy.hard_mode = true,
+                ["hard-mode", "off"] => strategy.hard_mode = false,
+                ["search", ref clause @ ..] => strategy.search.add_clause(clause)?,
+                ["arithmetic", "float"] => strategy.arithmetic = Arithmetic::Float,
+                ["arithmetic", "fixed"] => strategy.arithmetic = Arithmetic::Fixed,
+                ["dictionary", version] => dictionary_info().check(version)?,
+                _ => return Err(format!("unknown rule '{}'", rule)),
+            }
+        }
+        Ok(strategy)
+    }
+}
+
+impl GuesserFactory for Strategy {
+    type Guesser = Configured;
+
+    fn new_guesser(&self) -> Self::Guesser {
+        Configured::new(self.clone())
+    }
+}
+
+/// A guesser that follows a [`Strategy`].
+#[derive(Clone)]
+pub struct Configured {
+    strategy: Strategy,
+    tiebreak: Arc<dyn TieBreak>,
+    remaining: Vec<(&'static str, usize)>,
+}
+
+impl Default for Configured {
+    fn default() -> Self {
+        Self::new(Strategy::default())
+    }
+}
+
+impl Configured {
+    pub fn new(strategy: Strategy) -> Self {
+        Self {
+            tiebreak: strategy.tiebreak.tiebreak(),
+            strategy,
+            remaining: candidate_answers().to_vec(),
+        }
+    }
+}
+
+impl Guesser for Configured {
+    fn guess(&mut self, history: &[Guess]) -> Cow<'static, str> {
+        if let Some(last) = history.last() {
+            self.remaining.retain(|(word, _)| last.matches(word));
+        }
+        if history.is_empty() {
+            return self.strategy.opener.clone().into();
+        }
+        // The dictionary is sorted by frequency, so the most frequent candidate comes first.
+        // With only one candidate left, no metric can find a better guess than the answer itself.
+        if self.remaining.len() == 1
+            || matches!(self.strategy.endgame, Some(n) if self.remaining.len() <= n)
+        {
+            return Cow::Borrowed(self.remaining[0].0);
+        }
+
+        let remaining_count: usize = self.remaining.iter().map(|&(_, c)| c).sum();
+        let pool: &[(&'static str, usize)] = if self.strategy.from_dictionary {
+            dictionary()
+        } else {
+            &self.remaining
+        };
+
+        let letters = letter_counts(&self.remaining);
+        let positions = position_counts(&self.remaining);
+
+        let width = self.strategy.search.width(history.len() + 1, || {
+            entropy_of_counts(self.remaining.iter().map(|&(_, c)| c))
+        });
+        let mut search = Search::new(width, None, pool.len());
+        let mut best = Best::new(&*self.tiebreak);
+        for &(word, count) in pool {
+            if self.strategy.hard_mode && !history.iter().all(|g| g.allows_in_hard_mode(word)) {
+                continue;
+            }
+            // Guessing the same word again can't tell us anything new.
+            if history.iter().any(|g| g.word == word) {
+                continue;
+            }
+            let p_word = if self.remaining.iter().any(|&(w, _)| w == word) {
+                count as f64 / remaining_count as f64
+            } else {
+                0.0
+            };
+            let arithmetic = self.strategy.arithmetic;
+            let entropy = || entropy(word, &self.remaining, remaining_count, arithmetic);
+            let goodness = match self.strategy.sort {
+                Metric::Frequency => count as f64,
+                Metric::Entropy => entropy(),
+                Metric::WeightedEntropy => p_word * entropy(),
+                // Adding the chance of being the answer (always less than one letter's worth)
+                // prefers candidates when guesses rule out as many letters, so the game still
+                // ends once no letter is in doubt.
+                Metric::LetterElimination => {
+                    letter_elimination(word, &letters, remaining_count) + p_word
+                }
+                Metric::ExpectedGreens => expected_greens(word, &positions, remaining_count),
+            };
+            best.consider(word, goodness);
+            if search.considered(&best) {
+                break;
+            }
+        }
+        Cow::Borrowed(search.finish(&best))
+    }
+
+    fn reset(&mut self) {
+        self.remaining.clear();
+        self.remaining.extend_from_slice(candidate_answers());
+    }
+}
+
+fn entropy(
+    word: &str,
+    remaining: &[(&'static str, usize)],
+    remaining_count: usize,
+    arithmetic: Arithmetic,
+) -> f64 {
+    let mut totals = vec![0usize; Correctness::count(letter_count(word))];
+    for (candidate, count) in remaining {
+        let idx = Correctness::compute_packed(candidate, word);
+        totals[idx] += count;
+    }
+    if arithmetic == Arithmetic::Fixed {
+        return fixed::entropy(&totals, remaining_count);
+    }
+    -summation::sum(totals.into_iter().filter(|t| *t != 0).map(|t| {
+        let p_of_this_pattern = t as f64 / remaining_count as f64;
+        p_of_this_pattern * p_of_this_pattern.log2()
+    }))
+}
+
+static ALPHABET: OnceCell<Vec<char>> = OnceCell::new();
+
+/// Every letter used in the dictionary or the answers, in order, which is more than a to z for
+/// dictionaries such as the Spanish and German ones.
+fn alphabet() -> &'static [char] {
+    ALPHABET.get_or_init(|| {
+        let mut letters: Vec<char> = dictionary()
+            .iter()
+            .chain(candidate_answers())
+            .flat_map(|(word, _)| word.chars())
+            .collect();
+        letters.sort_unstable();
+        letters.dedup();
+        letters
+    })
+}
+
+/// Where `letter` is in the [alphabet](alphabet), if it is used at all.
+fn letter_index(letter: char) -> Option<usize> {
+    alphabet().binary_search(&letter).ok()
+}
+
+/// How many of `remaining` (weighted by count) contain each letter of the [alphabet](alphabet).
+fn letter_counts(remaining: &[(&'static str, usize)]) -> Vec<usize> {
+    let mut letters = vec![0; alphabet().len()];
+    let mut seen = vec![false; letters.len()];
+    for &(word, count) in remaining {
+        seen.fill(false);
+        for i in word.chars().filter_map(letter_index) {
+            if !std::mem::replace(&mut seen[i], true) {
+                letters[i] += count;
+            }
+        }
+    }
+    letters
+}
+
+fn letter_elimination(word: &str, letters: &[usize], remaining_count: usize) -> f64 {
+    let mut seen = vec![false; letters.len()];
+    let mut uncertain = 0;
+    for i in word.chars().filter_map(letter_index) {
+        if !std::mem::replace(&mut seen[i], true) && (1..remaining_count).contains(&letters[i]) {
+            uncertain += 1;
+        }
+    }
+    uncertain as f64
+}
+
+/// How many of `remaining` (weighted by count) have each letter of the [alphabet](alphabet) in
+/// each position, for as many positions as the longest of them has letters.
+fn position_counts(remaining: &[(&'static str, usize)]) -> Vec<Vec<usize>> {
+    let len = remaining
+        .iter()
+        .map(|&(word, _)| letter_count(word))
+        .max()
+        .unwrap_or(0);
+    let mut positions = vec![vec![0; alphabet().len()]; len];
+    for &(word, count) in remaining {
+        for (position, letter) in positions.iter_mut().zip(word.chars()) {
+            if let Some(i) = letter_index(letter) {
+                position[i] += count;
+            }
+        }
+    }
+    positions
+}
+
+fn expected_greens(word: &str, positions: &[Vec<usize>], remaining_count: usize) -> f64 {
+    positions
+        .iter()
+        .zip(word.chars())
+        .filter_map(|(position, letter)| Some(position[letter_index(letter)?]))
+        .map(|count| count as f64 / remaining_count as f64)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Arithmetic, Configured, Metric, Strategy, TieBreakRule};
+    use crate::Wordle;
+
+    #[test]
+    fn parse() {
+        let strategy: Strategy = "sort by entropy; tiebreak alphabetical; \
+            if remaining ≤ 3 guess most frequent; hard-mode on"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            strategy,
+            Strategy {
+                opener: "tares".to_string(),
+                sort: Metric::Entropy,
+                tiebreak: TieBreakRule::Alphabetical,
+                endgame: Some(3),
+                from_dictionary: false,
+                hard_mode: true,
+                arithmetic: Arithmetic::Float,
+                search: Strategy::default().search,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_lines() {
+        let strategy: Strategy =
+            "# comment\nopener crane\n\nsort by frequency\nguess from dictionary"
+                .parse()
+                .unwrap();
+        assert_eq!(strategy.opener, "crane");
+        assert_eq!(strategy.sort, Metric::Frequency);
+        assert!(strategy.from_dictionary);
+    }
+
+    #[test]
+    fn letter_elimination() {
+        let remaining = [("right", 1), ("fight", 1), ("sight", 1)];
+        let letters = super::letter_counts(&remaining);
+        // Only 'r', 'f' and 's' can tell these apart, and 'rifts' has all three.
+        assert_eq!(super::letter_elimination("rifts", &letters, 3), 3.0);
+        assert_eq!(super::letter_elimination("right", &letters, 3), 1.0);
+        assert_eq!(super::letter_elimination("sassy", &letters, 3), 1.0);
+    }
+
+    #[test]
+    fn parse_tiebreaks() {
+        let parse = |s: &str| s.parse::<Strategy>().map(|s| s.tiebreak);
+        assert_eq!(
+            parse("tiebreak fewest-repeats"),
+            Ok(TieBreakRule::FewestRepeats)
+        );
+        assert_eq!(parse("tiebreak random 42"), Ok(TieBreakRule::Random(42)));
+        assert!(parse("tiebreak random").is_err());
+        assert!(parse("tiebreak random soon").is_err());
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!("sort by vibes".parse::<Strategy>().is_err());
+        assert!("if remaining <= few guess most frequent"
+            .parse::<Strategy>()
+            .is_err());
+        assert!("be clever".parse::<Strategy>().is_err());
+        assert!("dictionary 0000000000000000".parse::<Strategy>().is_err());
+        let current = format!("dictionary {}", crate::dictionary_info().version());
+        assert!(current.parse::<Strategy>().is_ok());
+    }
+
+    #[test]
+    fn opener() {
+        let w = Wordle::new();
+        let strategy = "opener right".parse().unwrap();
+        assert_eq!(w.play("right", Configured::new(strategy)), Some(1));
+    }
+
+    #[test]
+    fn plays() {
+        let w = Wordle::new();
+        let strategy: Strategy = "sort by weighted-entropy; if remaining <= 3 guess most frequent"
+            .parse()
+            .unwrap();
+        for answer in ["cigar", "rebut", "sissy"] {
+            assert!(w.play(answer, Configured::new(strategy.clone())).is_some());
+        }
+    }
+
+    #[test]
+    fn search_schedule() {
+        let strategy: Strategy = "search round 2 all\nsearch 0.1 min 1".parse().unwrap();
+        assert_eq!(strategy.search.rounds.len(), 1);
+        assert_eq!(strategy.search.default.fraction, 0.1);
+        let w = Wordle::new();
+        for answer in ["cigar", "rebut", "sissy"] {
+            assert!(w.play(answer, Configured::new(strategy.clone())).is_some());
+        }
+        assert!("search round 2 most".parse::<Strategy>().is_err());
+    }
+
+    #[test]
+    fn fixed_arithmetic_plays_the_same() {
+        let w = Wordle::new();
+        let float: Strategy = "sort by weighted-entropy".parse().unwrap();
+        let fixed: Strategy = "sort by weighted-entropy; arithmetic fixed"
+            .parse()
+            .unwrap();
+        assert_eq!(fixed.arithmetic, Arithmetic::Fixed);
+        for answer in ["cigar", "rebut", "sissy"] {
+            assert_eq!(
+                w.play(answer, Configured::new(fixed.clone())),
+                w.play(answer, Configured::new(float.clone()))
+            );
+        }
+        assert!("arithmetic decimal".parse::<Strategy>().is_err());
+    }
+
+    #[test]
+    fn expected_greens() {
+        let remaining = [("right", 1), ("fight", 1), ("sight", 2)];
+        let positions = super::position_counts(&remaining);
+        assert_eq!(super::expected_greens("sight", &positions, 4), 4.5);
+        assert_eq!(super::expected_greens("abbey", &positions, 4), 0.0);
+    }
+
+    #[test]
+    fn expected_greens_past_five_letters() {
+        let remaining = [("thinks", 1), ("thanks", 1)];
+        let positions = super::position_counts(&remaining);
+        assert_eq!(positions.len(), 6);
+        assert_eq!(super::expected_greens("thinks", &positions, 2), 5.5);
+    }
+
+    #[test]
+    fn plays_with_other_metrics() {
+        let w = Wordle::new();
+        for metric in ["letter-elimination", "expected-greens"] {
+            let strategy: Strategy = format!("sort by {}; guess from dictionary", metric)
+                .parse()
+                .unwrap();
+            for answer in ["cigar", "rebut", "sissy"] {
+                assert!(w.play(answer, Configured::new(strategy.clone())).is_some());
+            }
+        }
+    }
+}
@@ -1,13 +1,18 @@
-use crate::{Correctness, Guess, Guesser, DICTIONARY, MAX_MASK_ENUM};
-use once_cell::sync::OnceCell;
+use super::first_guess;
+use super::kernel::Feedback;
+use super::search::{entropy_of_counts, Calibration, Search, SearchSchedule, SearchWidth};
+use super::summation;
+use super::tiebreak::{Best, BreaksTies, Frequency, TieBreak};
+use crate::{candidate_answers, letter_count, Correctness, Guess, Guesser};
 use std::borrow::Cow;
+use std::sync::Arc;
 
-static INITIAL: OnceCell<Vec<(&'static str, usize)>> = OnceCell::new();
-static PATTERNS: OnceCell<Vec<[Correctness; 5]>> = OnceCell::new();
-
+#[derive(Clone)]
 pub struct Cutoff {
-    remaining: Cow<'static, Vec<(&'static str, usize)>>,
-    patterns: Cow<'static, Vec<[Correctness; 5]>>,
+    remaining: Cow<'static, [(&'static str, usize)]>,
+    tiebreak: Arc<dyn TieBreak>,
+    schedule: SearchSchedule,
+    calibration: Option<Arc<Calibration>>,
 }
 
 impl Default for Cutoff {
@@ -19,20 +24,41 @@ impl Default for Cutoff {
 impl Cutoff {
     pub fn new() -> Self {
         Self {
-            remaining: Cow::Borrowed(INITIAL.get_or_init(|| DICTIONARY.to_vec())),
-            patterns: Cow::Borrowed(PATTERNS.get_or_init(|| Correctness::patterns().collect())),
+            remaining: Cow::Borrowed(candidate_answers()),
+            tiebreak: Arc::new(Frequency),
+            schedule: SearchSchedule::default(),
+            calibration: None,
         }
     }
+
+    /// Considers `width` of the candidates as guesses, rather than a third of them.
+    pub fn with_search_width(mut self, width: SearchWidth) -> Self {
+        self.schedule.default = width;
+        self
+    }
+
+    /// Considers as many of the candidates as guesses as `schedule` says for each guess.
+    pub fn with_search_schedule(mut self, schedule: SearchSchedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+
+    /// Considers as many of the candidates as `calibration` has tuned the width to, and helps it
+    /// tune the width further.
+    pub fn with_calibration(mut self, calibration: Arc<Calibration>) -> Self {
+        self.calibration = Some(calibration);
+        self
+    }
 }
 
-#[derive(Debug, Copy, Clone)]
-struct Candidate {
-    word: &'static str,
-    goodness: f64,
+impl BreaksTies for Cutoff {
+    fn tiebreak_mut(&mut self) -> &mut Arc<dyn TieBreak> {
+        &mut self.tiebreak
+    }
 }
 
 impl Guesser for Cutoff {
-    fn guess(&mut self, history: &[Guess]) -> String {
+    fn guess(&mut self, history: &[Guess]) -> Cow<'static, str> {
         if let Some(last) = history.last() {
             if matches!(self.remaining, Cow::Owned(_)) {
                 self.remaining
@@ -49,17 +75,19 @@ impl Guesser for Cutoff {
             }
         }
         if history.is_empty() {
-            self.patterns = Cow::Borrowed(PATTERNS.get().unwrap());
-            return "tares".to_string();
-        } else {
-            assert!(!self.patterns.is_empty());
+            if let Some(opener) = first_guess() {
+                return Cow::Borrowed(opener);
+            }
         }
 
         let remaining_count: usize = self.remaining.iter().map(|&(_, c)| c).sum();
 
-        let mut best: Option<Candidate> = None;
-        let mut i = 0;
-        let stop = (self.remaining.len() / 3).max(20);
+        let mut best = Best::new(&*self.tiebreak);
+        let width = self.schedule.width(history.len() + 1, || {
+            entropy_of_counts(self.remaining.iter().map(|&(_, c)| c))
+        });
+        let mut search = Search::new(width, self.calibration.as_deref(), self.remaining.len());
+        let mut feedback = Feedback::new(self.remaining.iter().map(|&(word, _)| word).collect());
         for &(word, count) in &*self.remaining {
             // considering a world where we _did_ guess `word` and got `pattern` as the
             // correctness. now, compute what _then_ is left.
@@ -68,23 +96,19 @@ impl Guesser for Cutoff {
             // that result in that pattern, we can instead keep a running total for each pattern
             // simultaneously by storing them in an array. We can do this since each candidate-word
             // pair deterministically produces only one mask.
-            let mut totals = [0usize; MAX_MASK_ENUM];
-            for (candidate, count) in &*self.remaining {
-                let idx = Correctness::pack(&Correctness::compute(candidate, word));
-                totals[usize::from(idx)] += count;
+            let mut totals = vec![0usize; Correctness::count(letter_count(word))];
+            for (idx, (_, count)) in feedback.packed(word).zip(&*self.remaining) {
+                totals[idx] += count;
             }
 
             assert_eq!(totals.iter().sum::<usize>(), remaining_count, "{}", word);
+            search.count_buckets(|| totals.iter().filter(|&&t| t != 0).count());
 
-            let sum: f64 = totals
-                .into_iter()
-                .filter(|t| *t != 0)
-                .map(|t| {
-                    // TODO: apply sigmoid
-                    let p_of_this_pattern = t as f64 / remaining_count as f64;
-                    p_of_this_pattern * p_of_this_pattern.log2()
-                })
-                .sum();
+            let sum = summation::sum(totals.into_iter().filter(|t| *t != 0).map(|t| {
+                // TODO: apply sigmoid
+                let p_of_this_pattern = t as f64 / remaining_count as f64;
+                p_of_this_pattern * p_of_this_pattern.log2()
+            }));
 
             let p_word = count as f64 / remaining_count as f64;
             let entropy = -sum;
@@ -93,20 +117,16 @@ impl Guesser for Cutoff {
             // where remaining_entropy is the existing entropy - entropy
             // and restimate_remaining_guesses is computed by regression over historical data
             let goodness = p_word * entropy;
-            if let Some(c) = best {
-                // Is this one better?
-                if goodness > c.goodness {
-                    best = Some(Candidate { word, goodness });
-                }
-            } else {
-                best = Some(Candidate { word, goodness });
-            }
+            best.consider(word, goodness);
 
-            i += 1;
-            if i >= stop {
+            if search.considered(&best) {
                 break;
             }
         }
-        best.unwrap().word.to_string()
+        Cow::Borrowed(search.finish(&best))
+    }
+
+    fn reset(&mut self) {
+        self.remaining = Cow::Borrowed(candidate_answers());
     }
 }
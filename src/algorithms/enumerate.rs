@@ -1,11 +1,14 @@
-use crate::{Correctness, Guess, Guesser, DICTIONARY, MAX_MASK_ENUM};
-use once_cell::sync::OnceCell;
+use super::first_guess;
+use super::summation;
+use super::tiebreak::{Best, BreaksTies, Frequency, TieBreak};
+use crate::{candidate_answers, letter_count, Correctness, Guess, Guesser};
 use std::borrow::Cow;
+use std::sync::Arc;
 
-static INITIAL: OnceCell<Vec<(&'static str, usize)>> = OnceCell::new();
-
+#[derive(Clone)]
 pub struct Enumerate {
-    remaining: Cow<'static, Vec<(&'static str, usize)>>,
+    remaining: Cow<'static, [(&'static str, usize)]>,
+    tiebreak: Arc<dyn TieBreak>,
 }
 
 impl Default for Enumerate {
@@ -17,19 +20,20 @@ impl Default for Enumerate {
 impl Enumerate {
     pub fn new() -> Self {
         Self {
-            remaining: Cow::Borrowed(INITIAL.get_or_init(|| DICTIONARY.to_vec())),
+            remaining: Cow::Borrowed(candidate_answers()),
+            tiebreak: Arc::new(Frequency),
         }
     }
 }
 
-#[derive(Debug, Copy, Clone)]
-struct Candidate {
-    word: &'static str,
-    goodness: f64,
+impl BreaksTies for Enumerate {
+    fn tiebreak_mut(&mut self) -> &mut Arc<dyn TieBreak> {
+        &mut self.tiebreak
+    }
 }
 
 impl Guesser for Enumerate {
-    fn guess(&mut self, history: &[Guess]) -> String {
+    fn guess(&mut self, history: &[Guess]) -> Cow<'static, str> {
         if let Some(last) = history.last() {
             if matches!(self.remaining, Cow::Owned(_)) {
                 self.remaining
@@ -45,13 +49,13 @@ impl Guesser for Enumerate {
                 );
             }
         }
-        if history.is_empty() {
-            return "tares".to_string();
+        if let Some(opener) = first_guess().filter(|_| history.is_empty()) {
+            return Cow::Borrowed(opener);
         }
 
         let remaining_count: usize = self.remaining.iter().map(|&(_, c)| c).sum();
 
-        let mut best: Option<Candidate> = None;
+        let mut best = Best::new(&*self.tiebreak);
         for &(word, count) in &*self.remaining {
             // considering a world where we _did_ guess `word` and got `pattern` as the
             // correctness. now, compute what _then_ is left.
@@ -60,35 +64,28 @@ impl Guesser for Enumerate {
             // that result in that pattern, we can instead keep a running total for each pattern
             // simultaneously by storing them in an array. We can do this since each candidate-word
             // pair deterministically produces only one mask.
-            let mut totals = [0usize; MAX_MASK_ENUM];
+            let mut totals = vec![0usize; Correctness::count(letter_count(word))];
             for (candidate, count) in &*self.remaining {
-                let idx = Correctness::pack(&Correctness::compute(candidate, word));
-                totals[usize::from(idx)] += count;
+                let idx = Correctness::compute_packed(candidate, word);
+                totals[idx] += count;
             }
 
             assert_eq!(totals.iter().sum::<usize>(), remaining_count, "{}", word);
 
-            let sum: f64 = totals
-                .into_iter()
-                .filter(|t| *t != 0)
-                .map(|t| {
-                    // TODO: apply sigmoid
-                    let p_of_this_pattern = t as f64 / remaining_count as f64;
-                    p_of_this_pattern * p_of_this_pattern.log2()
-                })
-                .sum();
+            let sum = summation::sum(totals.into_iter().filter(|t| *t != 0).map(|t| {
+                // TODO: apply sigmoid
+                let p_of_this_pattern = t as f64 / remaining_count as f64;
+                p_of_this_pattern * p_of_this_pattern.log2()
+            }));
 
             let p_word = count as f64 / remaining_count as f64;
             let goodness = p_word * -sum;
-            if let Some(c) = best {
-                // Is this one better?
-                if goodness > c.goodness {
-                    best = Some(Candidate { word, goodness });
-                }
-            } else {
-                best = Some(Candidate { word, goodness });
-            }
+            best.consider(word, goodness);
         }
-        best.unwrap().word.to_string()
+        Cow::Borrowed(best.word().unwrap())
+    }
+
+    fn reset(&mut self) {
+        self.remaining = Cow::Borrowed(candidate_answers());
     }
 }
@@ -0,0 +1,314 @@
+use super::summation;
+use crate::Correctness;
+use std::collections::{BTreeMap, HashMap};
+
+/// Exact evaluation of the endgame, by playing out every feedback each guess could get.
+///
+/// This is exponential in the number of guesses looked ahead, so it is only feasible once few
+/// candidates are left. Results are memoized on the set of candidates, which recurs a lot since
+/// different guesses often split the candidates the same way, so each word must always be given
+/// with the same likelihood.
+#[derive(Default, Clone)]
+pub(crate) struct Exact {
+    solve: HashMap<(Vec<&'static str>, usize), f64>,
+    guesses: HashMap<Vec<&'static str>, f64>,
+    /// The fewest guesses that sets of candidates are known to need, from searches that were
+    /// given up on.
+    at_least: HashMap<Vec<&'static str>, f64>,
+}
+
+/// The packed feedback when the guess is the answer.
+const SOLVED: usize = 0;
+
+/// Splits `candidates` by the (packed) feedback they would give to `guess`.
+fn buckets(
+    guess: &str,
+    candidates: &[(&'static str, f64)],
+) -> BTreeMap<usize, Vec<(&'static str, f64)>> {
+    let mut buckets: BTreeMap<_, Vec<_>> = BTreeMap::new();
+    for &(candidate, p) in candidates {
+        buckets
+            .entry(Correctness::compute_packed(candidate, guess))
+            .or_default()
+            .push((candidate, p));
+    }
+    buckets
+}
+
+impl Exact {
+    /// The probability of guessing the answer within `guesses` guesses if the next one is `guess`
+    /// and every one after it is the best possible, when the answer is one of `candidates` with
+    /// the given (relative) likelihoods.
+    pub(crate) fn solve_probability_with(
+        &mut self,
+        guess: &str,
+        candidates: &[(&'static str, f64)],
+        guesses: usize,
+    ) -> f64 {
+        if guesses == 0 {
+            return 0.0;
+        }
+        let total = summation::sum(candidates.iter().map(|&(_, p)| p));
+        summation::sum(
+            buckets(guess, candidates)
+                .into_iter()
+                .map(|(mask, bucket)| {
+                    let p = summation::sum(bucket.iter().map(|&(_, p)| p)) / total;
+                    if mask == SOLVED {
+                        p
+                    } else {
+                        p * self.solve_probability(&bucket, guesses - 1)
+                    }
+                }),
+        )
+    }
+
+    /// The probability of guessing the answer within `guesses` guesses when only guessing
+    /// candidates, and always picking the best one.
+    pub(crate) fn solve_probability(
+        &mut self,
+        candidates: &[(&'static str, f64)],
+        guesses: usize,
+    ) -> f64 {
+        match (candidates.len(), guesses) {
+            (_, 0) | (0, _) => return 0.0,
+            (1, _) => return 1.0,
+            (_, 1) => {
+                let total = summation::sum(candidates.iter().map(|&(_, p)| p));
+                return candidates.iter().map(|&(_, p)| p).fold(0.0, f64::max) / total;
+            }
+            _ => {}
+        }
+        let key = (candidates.iter().map(|&(w, _)| w).collect(), guesses);
+        if let Some(&p) = self.solve.get(&key) {
+            return p;
+        }
+        let mut best = 0.0f64;
+        for &(guess, _) in candidates {
+            best = best.max(self.solve_probability_with(guess, candidates, guesses));
+            if best >= 1.0 {
+                break;
+            }
+        }
+        self.solve.insert(key, best);
+        best
+    }
+
+    /// The expected number of guesses needed, including `guess` itself, if the next one is
+    /// `guess` and every one after it is the best possible.
+    pub(crate) fn expected_guesses_with(
+        &mut self,
+        guess: &str,
+        candidates: &[(&'static str, f64)],
+    ) -> f64 {
+        let total = summation::sum(candidates.iter().map(|&(_, p)| p));
+        1.0 + summation::sum(
+            buckets(guess, candidates)
+                .into_iter()
+                .filter(|(mask, _)| *mask != SOLVED)
+                .map(|(_, bucket)| {
+                    let p = summation::sum(bucket.iter().map(|&(_, p)| p)) / total;
+                    p * self.expected_guesses(&bucket)
+                }),
+        )
+    }
+
+    /// The expected number of guesses needed when only guessing candidates, and always picking
+    /// the best one.
+    pub(crate) fn expected_guesses(&mut self, candidates: &[(&'static str, f64)]) -> f64 {
+        if candidates.len() <= 1 {
+            return candidates.len() as f64;
+        }
+        let key: Vec<_> = candidates.iter().map(|&(w, _)| w).collect();
+        if let Some(&guesses) = self.guesses.get(&key) {
+            return guesses;
+        }
+        let mut best = f64::INFINITY;
+        for &(guess, _) in candidates {
+            best = best.min(self.expected_guesses_with(guess, candidates));
+        }
+        self.guesses.insert(key, best);
+        best
+    }
+
+    /// The candidate to guess to need the fewest guesses on average, when only guessing
+    /// candidates, along with how many that is (including the guess itself).
+    ///
+    /// This gets the same result as trying every candidate with
+    /// [`expected_guesses_with`](Self::expected_guesses_with), but a guess is given up on as soon
+    /// as what is left of it can't make up for what it has already cost compared to the best
+    /// guess so far, which is what makes larger candidate sets feasible.
+    pub(crate) fn best_guess(
+        &mut self,
+        candidates: &[(&'static str, f64)],
+    ) -> Option<(&'static str, f64)> {
+        let mut best = None;
+        let mut bound = f64::INFINITY;
+        for &(guess, _) in candidates {
+            if let Some(guesses) = self.expected_guesses_with_below(guess, candidates, bound) {
+                if guesses < bound {
+                    best = Some((guess, guesses));
+                    bound = guesses;
+                }
+            }
+        }
+        best
+    }
+
+    /// [`expected_guesses_with`](Self::expected_guesses_with), or `None` once it is clear that it
+    /// is no less than `bound`.
+    fn expected_guesses_with_below(
+        &mut self,
+        guess: &str,
+        candidates: &[(&'static str, f64)],
+        bound: f64,
+    ) -> Option<f64> {
+        let total = summation::sum(candidates.iter().map(|&(_, p)| p));
+        let buckets: Vec<_> = buckets(guess, candidates)
+            .into_iter()
+            .filter(|(mask, _)| *mask != SOLVED)
+            .map(|(_, bucket)| {
+                let p = summation::sum(bucket.iter().map(|&(_, p)| p)) / total;
+                (p, p * self.at_least(&bucket), bucket)
+            })
+            .collect();
+        // Start from the fewest guesses each bucket could take, and work out the actual number one
+        // bucket at a time, each only as far as it could still make the guess beat `bound`.
+        let mut partial = 1.0 + summation::sum(buckets.iter().map(|&(_, least, _)| least));
+        let mut terms = Vec::with_capacity(buckets.len());
+        for (p, least, bucket) in &buckets {
+            if partial >= bound {
+                return None;
+            }
+            let others = partial - least;
+            let guesses = self.expected_guesses_below(bucket, (bound - others) / p)?;
+            terms.push(p * guesses);
+            partial = others + p * guesses;
+        }
+        (partial < bound).then(|| 1.0 + summation::sum(terms))
+    }
+
+    /// [`expected_guesses`](Self::expected_guesses), or `None` once it is clear that it is no
+    /// less than `bound`.
+    fn expected_guesses_below(
+        &mut self,
+        candidates: &[(&'static str, f64)],
+        bound: f64,
+    ) -> Option<f64> {
+        if candidates.len() <= 1 {
+            return Some(candidates.len() as f64);
+        }
+        let key: Vec<_> = candidates.iter().map(|&(w, _)| w).collect();
+        if let Some(&guesses) = self.guesses.get(&key) {
+            return Some(guesses);
+        }
+        if self.at_least(candidates) >= bound {
+            return None;
+        }
+        let mut best = f64::INFINITY;
+        for &(guess, _) in candidates {
+            if let Some(guesses) =
+                self.expected_guesses_with_below(guess, candidates, best.min(bound))
+            {
+                best = best.min(guesses);
+            }
+        }
+        // Every guess that was given up on was no better than the best one, unless none was
+        // better than `bound`, so this is only the exact answer when it is below `bound`.
+        if best < bound {
+            self.at_least.remove(&key);
+            self.guesses.insert(key, best);
+            Some(best)
+        } else {
+            // Searching the same candidates again only needs to be done against a higher bound.
+            self.at_least.insert(key, bound);
+            None
+        }
+    }
+
+    /// The fewest guesses `candidates` could need on average: as many as they are known to need,
+    /// or else one if the likeliest is guessed first and is the answer, and at least two
+    /// otherwise.
+    fn at_least(&self, candidates: &[(&'static str, f64)]) -> f64 {
+        if candidates.len() <= 1 {
+            return candidates.len() as f64;
+        }
+        let key: Vec<_> = candidates.iter().map(|&(w, _)| w).collect();
+        if let Some(&guesses) = self.guesses.get(&key) {
+            return guesses;
+        }
+        let total = summation::sum(candidates.iter().map(|&(_, p)| p));
+        let least = 2.0 - candidates.iter().map(|&(_, p)| p).fold(0.0, f64::max) / total;
+        self.at_least
+            .get(&key)
+            .map_or(least, |&known| known.max(least))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Exact;
+
+    #[test]
+    fn one_guess_left() {
+        let mut exact = Exact::default();
+        let candidates = [("right", 3.0), ("fight", 1.0)];
+        assert_eq!(exact.solve_probability(&candidates, 1), 0.75);
+        assert_eq!(exact.solve_probability_with("fight", &candidates, 1), 0.25);
+    }
+
+    #[test]
+    fn recurses_into_buckets() {
+        let mut exact = Exact::default();
+        // Whichever is guessed first, the other can be told apart and guessed next.
+        let candidates = [("right", 1.0), ("fight", 1.0)];
+        assert_eq!(exact.solve_probability(&candidates, 2), 1.0);
+        // Guessing one of these only tells the answer apart from the other three.
+        let candidates = [
+            ("right", 1.0),
+            ("fight", 1.0),
+            ("might", 1.0),
+            ("sight", 1.0),
+        ];
+        assert_eq!(exact.solve_probability(&candidates, 2), 0.5);
+        assert_eq!(exact.solve_probability(&candidates, 4), 1.0);
+    }
+
+    #[test]
+    fn expected_guesses() {
+        let mut exact = Exact::default();
+        assert_eq!(exact.expected_guesses(&[("right", 1.0)]), 1.0);
+        // Right away with probability 3/4, otherwise on the second guess.
+        let candidates = [("right", 3.0), ("fight", 1.0)];
+        assert_eq!(exact.expected_guesses(&candidates), 1.25);
+        assert_eq!(exact.expected_guesses_with("fight", &candidates), 1.75);
+        // Each guess only rules out itself: 1, 2, 3 or 4 guesses, equally likely.
+        let mut exact = Exact::default();
+        let candidates = [
+            ("right", 1.0),
+            ("fight", 1.0),
+            ("might", 1.0),
+            ("sight", 1.0),
+        ];
+        assert_eq!(exact.expected_guesses(&candidates), 2.5);
+    }
+
+    #[test]
+    fn best_guess_prunes_to_the_same_answer() {
+        let candidates: Vec<_> = crate::algorithms::words::weighted()
+            .iter()
+            .filter(|&&(word, _)| word.ends_with("ight"))
+            .take(12)
+            .copied()
+            .collect();
+        let (guess, guesses) = Exact::default().best_guess(&candidates).unwrap();
+        let mut exact = Exact::default();
+        assert!((guesses - exact.expected_guesses(&candidates)).abs() < 1e-9);
+        assert!((guesses - exact.expected_guesses_with(guess, &candidates)).abs() < 1e-9);
+        assert_eq!(Exact::default().best_guess(&[]), None);
+        assert_eq!(
+            Exact::default().best_guess(&[("right", 1.0)]),
+            Some(("right", 1.0))
+        );
+    }
+}
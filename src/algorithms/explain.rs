@@ -0,0 +1,160 @@
+//! Why the scoring algorithms guessed what they did: the guesses that came closest each turn, with
+//! the numbers they were scored on, handed to a callback rather than printed so that whoever is
+//! driving the guesser decides where they go.
+
+use super::words::weighted;
+use crate::analysis::opener_shortlist;
+use std::sync::Arc;
+
+/// How a guesser chose one of its guesses.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GuessExplanation {
+    /// Which guess of the game this is, starting from 1.
+    pub round: usize,
+    /// How many candidates could still be the answer before guessing.
+    pub remaining: usize,
+    /// The word that was guessed.
+    pub guess: &'static str,
+    /// The best guesses the guesser scored, best first.
+    pub top: Vec<ScoredGuess>,
+}
+
+/// One of the guesses a guesser scored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoredGuess {
+    pub word: &'static str,
+    /// How many bits the feedback to it is expected to give.
+    pub information: f64,
+    /// The chance of it being the answer, going by how common the candidates left are.
+    pub probability: f64,
+    /// How many guesses the game is expected to take in all after guessing it, for the algorithms
+    /// that estimate it.
+    pub expected_score: Option<f64>,
+}
+
+/// Where a guesser reports its explanations, and how many guesses it lists in each.
+#[derive(Clone)]
+pub(crate) struct Explanations {
+    top: usize,
+    report: Arc<dyn Fn(&GuessExplanation) + Send + Sync>,
+}
+
+impl Explanations {
+    pub(crate) fn new(
+        top: usize,
+        report: impl Fn(&GuessExplanation) + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            top,
+            report: Arc::new(report),
+        }
+    }
+
+    /// Somewhere to keep the best guesses while scoring them.
+    pub(crate) fn shortlist(&self) -> Shortlist {
+        Shortlist {
+            top: self.top,
+            guesses: Vec::with_capacity(self.top + 1),
+        }
+    }
+
+    pub(crate) fn report(
+        &self,
+        round: usize,
+        remaining: usize,
+        guess: &'static str,
+        top: Shortlist,
+    ) {
+        (self.report)(&GuessExplanation {
+            round,
+            remaining,
+            guess,
+            top: top.guesses.into_iter().map(|(_, guess)| guess).collect(),
+        });
+    }
+
+    /// Reports the opener, which is looked up in the shortlist rather than scored, so there is no
+    /// expected score to give for it or the openers that came close.
+    pub(crate) fn report_opener(&self, opener: &'static str) {
+        let candidates = weighted();
+        let total: f64 = candidates.iter().map(|&(_, p)| p).sum();
+        let mut top = self.shortlist();
+        for &(word, information) in opener_shortlist() {
+            let p = candidates
+                .iter()
+                .find(|&&(w, _)| w == word)
+                .map_or(0.0, |&(_, p)| p);
+            top.consider(
+                information,
+                ScoredGuess {
+                    word,
+                    information,
+                    probability: p / total,
+                    expected_score: None,
+                },
+            );
+        }
+        self.report(1, candidates.len(), opener, top);
+    }
+}
+
+/// The best few guesses scored so far.
+pub(crate) struct Shortlist {
+    top: usize,
+    guesses: Vec<(f64, ScoredGuess)>,
+}
+
+impl Shortlist {
+    /// Keeps `guess` if it is among the best so far, going by `goodness` (higher is better).
+    /// Between guesses that are as good, the one considered first stays ahead.
+    pub(crate) fn consider(&mut self, goodness: f64, guess: ScoredGuess) {
+        let at = self.guesses.partition_point(|&(g, _)| g >= goodness);
+        if at < self.top {
+            self.guesses.insert(at, (goodness, guess));
+            self.guesses.truncate(self.top);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Explanations, ScoredGuess};
+    use std::sync::{Arc, Mutex};
+
+    fn scored(word: &'static str) -> ScoredGuess {
+        ScoredGuess {
+            word,
+            information: 0.0,
+            probability: 0.0,
+            expected_score: None,
+        }
+    }
+
+    #[test]
+    fn keeps_the_best() {
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let explanations = Explanations::new(3, {
+            let reported = Arc::clone(&reported);
+            move |explanation| reported.lock().unwrap().push(explanation.clone())
+        });
+        let mut top = explanations.shortlist();
+        for (word, goodness) in [
+            ("cigar", 1.0),
+            ("rebut", 3.0),
+            ("sissy", 2.0),
+            ("humph", 3.0),
+            ("awake", 0.5),
+        ] {
+            top.consider(goodness, scored(word));
+        }
+        explanations.report(2, 5, "rebut", top);
+
+        let reported = reported.lock().unwrap();
+        assert_eq!(reported.len(), 1);
+        assert_eq!(reported[0].round, 2);
+        assert_eq!(reported[0].remaining, 5);
+        assert_eq!(reported[0].guess, "rebut");
+        let words: Vec<_> = reported[0].top.iter().map(|g| g.word).collect();
+        assert_eq!(words, ["rebut", "humph", "sissy"]);
+    }
+}
@@ -0,0 +1,119 @@
+//! Entropy worked out with integers only, for strategies that want exactly the same guesses on
+//! every platform.
+//!
+//! Floating point logarithms aren't required to round the same way everywhere, and sums of them
+//! depend on the order of the terms, so two machines can disagree on which of two nearly equal
+//! guesses is better. Here every logarithm comes from a table built at compile time with integer
+//! arithmetic, and the entropy is added up in integers, so the result is the same bit for bit
+//! whatever the platform and whatever order the candidates are in.
+
+/// How many bits of the fixed-point numbers are after the point.
+const FRACTION_BITS: u32 = 32;
+const ONE: u64 = 1 << FRACTION_BITS;
+// The table has an entry for each of this many bits of the mantissa, and is interpolated between
+// them, which is accurate to well within a millionth of a bit.
+const TABLE_BITS: u32 = 10;
+const TABLE_SIZE: usize = 1 << TABLE_BITS;
+const INTERPOLATION_BITS: u32 = FRACTION_BITS - TABLE_BITS;
+
+/// `log2(1 + i / TABLE_SIZE)` for every `i` up to and including `TABLE_SIZE`, with
+/// `FRACTION_BITS` bits after the point.
+static LOG2_TABLE: [u64; TABLE_SIZE + 1] = log2_table();
+
+const fn log2_table() -> [u64; TABLE_SIZE + 1] {
+    let mut table = [0; TABLE_SIZE + 1];
+    let mut i = 0;
+    while i <= TABLE_SIZE {
+        table[i] = log2_mantissa(ONE + ((i as u64) << INTERPOLATION_BITS));
+        i += 1;
+    }
+    table
+}
+
+/// The logarithm of a number between 1 and 2 (both fixed-point), one bit at a time: squaring the
+/// number doubles its logarithm, so whether the square is 2 or more gives the next bit.
+const fn log2_mantissa(x: u64) -> u64 {
+    let mut x = x as u128;
+    let mut log = 0;
+    let mut bit = ONE >> 1;
+    while bit > 0 {
+        x = (x * x) >> FRACTION_BITS;
+        if x >= 2 * ONE as u128 {
+            x >>= 1;
+            log |= bit;
+        }
+        bit >>= 1;
+    }
+    log
+}
+
+/// `log2(n)` with `FRACTION_BITS` bits after the point.
+///
+/// # Panics
+///
+/// If `n` is 0.
+pub(crate) fn log2(n: u64) -> u64 {
+    assert!(n > 0, "the logarithm of 0 is undefined");
+    let whole = 63 - n.leading_zeros();
+    let mantissa = if whole <= FRACTION_BITS {
+        n << (FRACTION_BITS - whole)
+    } else {
+        n >> (whole - FRACTION_BITS)
+    } - ONE;
+    let i = (mantissa >> INTERPOLATION_BITS) as usize;
+    let between = mantissa & ((1 << INTERPOLATION_BITS) - 1);
+    let (low, high) = (LOG2_TABLE[i], LOG2_TABLE[i + 1]);
+    (u64::from(whole) << FRACTION_BITS) + low + (((high - low) * between) >> INTERPOLATION_BITS)
+}
+
+/// The entropy (in bits) of the feedback to a guess, given how many of the `total` candidates get
+/// each feedback.
+///
+/// This is `log2(total) - sum(t * log2(t)) / total`, which only needs the logarithms of whole
+/// numbers. The result is rounded to a multiple of `2^-32`, so converting it to `f64` is exact.
+pub(crate) fn entropy(totals: &[usize], total: usize) -> f64 {
+    let sum: u128 = totals
+        .iter()
+        .filter(|&&t| t != 0)
+        .map(|&t| t as u128 * u128::from(log2(t as u64)))
+        .sum();
+    let fixed = u128::from(log2(total as u64)).saturating_sub(sum / total as u128);
+    fixed as f64 / ONE as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{entropy, log2, ONE};
+
+    #[test]
+    fn powers_of_two_are_exact() {
+        for k in 0..40 {
+            assert_eq!(log2(1 << k), k * ONE);
+        }
+    }
+
+    #[test]
+    fn log2_is_close() {
+        for n in (1..100_000).chain((1..1000).map(|n| n * 1_234_567_891)) {
+            let fixed = log2(n) as f64 / ONE as f64;
+            assert!((fixed - (n as f64).log2()).abs() < 1e-6, "log2({})", n);
+        }
+    }
+
+    #[test]
+    fn entropy_is_close() {
+        let totals = [0, 35, 12025, 1, 4700, 3, 975];
+        let total: usize = totals.iter().sum();
+        let float = -totals
+            .iter()
+            .filter(|&&t| t != 0)
+            .map(|&t| {
+                let p = t as f64 / total as f64;
+                p * p.log2()
+            })
+            .sum::<f64>();
+        assert!((entropy(&totals, total) - float).abs() < 1e-6);
+        assert_eq!(entropy(&[0, 8, 0], 8), 0.0);
+        assert_eq!(entropy(&[4, 4, 4, 4], 16), 2.0);
+    }
+}
@@ -0,0 +1,374 @@
+//! Working out the feedback to a guess from many candidates at once, with the fastest
+//! instructions the CPU running the solver has.
+//!
+//! The escore, sigmoid and cutoff implementations, the opener table and the precomputed masks of
+//! the cache implementation spend nearly all of their time working out the feedback of one guess
+//! against every candidate left. Laid out as [`Columns`], that can be done for many candidates
+//! side by side, with loops over the lanes that have no branches, which the compiler turns into
+//! vector instructions by itself.
+//!
+//! There are no hand-written vector instructions here: every kernel is the same portable code, and
+//! all that differs between them is which instructions the compiler is allowed to use for it. The
+//! widest ones (AVX2 on x86-64) can't be assumed to be there unless the solver is built with
+//! `target-cpu=native`, which a binary handed to someone else can't be, so the code is compiled
+//! once more with them enabled and that copy is picked the first time the kernel is used if the
+//! CPU supports it. `std::simd` wouldn't help with that, since it fixes the instructions at compile
+//! time too (and isn't stable). How much faster that is depends on how well the compiler
+//! vectorises the loops, which `benches/kernel.rs` keeps an eye on: with the AVX2 copy, the table
+//! of every word as an opener takes about three quarters of the time it takes with the portable
+//! one.
+//!
+//! The cache implementation itself finds over 99% of the masks it needs already worked out, and
+//! works out the rest one at a time as they come up, so it has nothing to batch.
+
+use crate::profile::{duplicates, Duplicates};
+use crate::Correctness;
+use itertools::Either;
+use once_cell::sync::OnceCell;
+use std::fmt;
+use std::str::FromStr;
+
+/// How many candidates the kernel works on at once, which fills an AVX2 register.
+const LANES: usize = 32;
+/// Feedback packed into a `u8` only fits words of up to five letters.
+const MAX_KERNEL_WORD_LEN: usize = 5;
+
+/// Which build of the kernel works out feedback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kernel {
+    /// Compiled with the 256-bit AVX2 instructions enabled, on x86-64 CPUs that have them.
+    Avx2,
+    /// Compiled with the 128-bit NEON instructions enabled, on 64-bit ARM CPUs.
+    Neon,
+    /// Compiled for whatever instructions every CPU of the target has.
+    Portable,
+}
+
+impl Kernel {
+    /// The fastest kernel the CPU running this supports.
+    pub fn detect() -> Self {
+        [Self::Avx2, Self::Neon]
+            .into_iter()
+            .find(|kernel| kernel.is_supported())
+            .unwrap_or(Self::Portable)
+    }
+
+    /// Whether the CPU running this can use this kernel.
+    pub fn is_supported(self) -> bool {
+        match self {
+            #[cfg(target_arch = "x86_64")]
+            Self::Avx2 => is_x86_feature_detected!("avx2"),
+            #[cfg(target_arch = "aarch64")]
+            Self::Neon => std::arch::is_aarch64_feature_detected!("neon"),
+            Self::Portable => true,
+            #[allow(unreachable_patterns)]
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for Kernel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "avx2" => Ok(Self::Avx2),
+            "neon" => Ok(Self::Neon),
+            "portable" => Ok(Self::Portable),
+            _ => Err(format!(
+                "unknown kernel '{}', expected avx2, neon or portable",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Kernel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Avx2 => "avx2",
+            Self::Neon => "neon",
+            Self::Portable => "portable",
+        })
+    }
+}
+
+static KERNEL: OnceCell<Kernel> = OnceCell::new();
+
+/// Picks the kernel that works out feedback from now on. Fails if the CPU doesn't support it, or
+/// if a different kernel has already been used.
+pub fn set_kernel(kernel: Kernel) -> Result<(), String> {
+    if !kernel.is_supported() {
+        return Err(format!("this CPU can't use the {} kernel", kernel));
+    }
+    match KERNEL.try_insert(kernel) {
+        Ok(_) => Ok(()),
+        Err((current, kernel)) if *current == kernel => Ok(()),
+        Err(_) => Err("a different kernel is already in use".to_string()),
+    }
+}
+
+/// The kernel in use, which is the [fastest one](Kernel::detect) the CPU supports unless another
+/// was picked before any feedback was worked out.
+pub fn kernel() -> Kernel {
+    *KERNEL.get_or_init(Kernel::detect)
+}
+
+/// Candidates laid out for the kernel: in chunks of `LANES` words, with letter `i` of every word
+/// in a chunk next to each other. The last chunk is padded with zero bytes, which no guess has.
+pub(crate) struct Columns {
+    len: usize,
+    words: usize,
+    chunks: Vec<[u8; LANES]>,
+}
+
+impl Columns {
+    /// Lays out `words`, which all have `len` letters. This is `None` if the words are too long
+    /// for the kernel, in which case [`Correctness::compute_packed`] has to be used instead.
+    pub(crate) fn new<'a>(words: impl IntoIterator<Item = &'a str>, len: usize) -> Option<Self> {
+        if len > MAX_KERNEL_WORD_LEN {
+            return None;
+        }
+        let mut columns = Self {
+            len,
+            words: 0,
+            chunks: Vec::new(),
+        };
+        for word in words {
+            let lane = columns.words % LANES;
+            if lane == 0 {
+                columns.chunks.extend(std::iter::repeat_n([0; LANES], len));
+            }
+            let chunk = columns.chunks.len() - len;
+            for (i, b) in word.bytes().enumerate() {
+                columns.chunks[chunk + i][lane] = b;
+            }
+            columns.words += 1;
+        }
+        Some(columns)
+    }
+}
+
+/// The feedback to any guess from a fixed set of candidates, worked out by the kernel when the
+/// words are short enough for it, and one candidate at a time otherwise.
+pub(crate) struct Feedback<'a> {
+    words: Vec<&'a str>,
+    columns: Option<Columns>,
+    masks: Vec<u8>,
+}
+
+impl<'a> Feedback<'a> {
+    /// Lays out `words`, which all have the same number of letters, for the kernel. The kernel
+    /// compares letters a byte at a time, so words with letters outside ASCII are left to
+    /// [`Correctness::compute_packed`].
+    pub(crate) fn new(words: Vec<&'a str>) -> Self {
+        let len = words.first().map_or(0, |word| word.len());
+        let columns = if words.iter().all(|word| word.is_ascii()) {
+            Columns::new(words.iter().copied(), len)
+        } else {
+            None
+        };
+        Self {
+            columns,
+            words,
+            masks: Vec::new(),
+        }
+    }
+
+    /// The [packed](Correctness::pack) feedback to `guess` from each of the candidates, in order.
+    pub(crate) fn packed<'s>(&'s mut self, guess: &'s str) -> impl Iterator<Item = usize> + 's {
+        match &self.columns {
+            Some(columns) if guess.is_ascii() => {
+                masks(guess, columns, &mut self.masks);
+                Either::Left(self.masks.iter().map(|&mask| usize::from(mask)))
+            }
+            _ => Either::Right(
+                self.words
+                    .iter()
+                    .map(move |answer| Correctness::compute_packed(answer, guess)),
+            ),
+        }
+    }
+}
+
+/// Sets `masks` to the [packed](Correctness::pack) feedback to `guess` from each of the words in
+/// `columns`, in order, under the duplicate-letter rule in use.
+pub(crate) fn masks(guess: &str, columns: &Columns, masks: &mut Vec<u8>) {
+    masks_with(kernel(), duplicates(), guess, columns, masks)
+}
+
+fn masks_with(
+    kernel: Kernel,
+    rule: Duplicates,
+    guess: &str,
+    columns: &Columns,
+    masks: &mut Vec<u8>,
+) {
+    assert_eq!(guess.len(), columns.len);
+    masks.clear();
+    masks.resize(columns.chunks.len() / columns.len.max(1) * LANES, 0);
+    match kernel {
+        // SAFETY: the AVX2 kernel is only picked when the CPU supports it.
+        #[cfg(target_arch = "x86_64")]
+        Kernel::Avx2 if kernel.is_supported() => unsafe {
+            masks_avx2(guess.as_bytes(), columns, rule, masks)
+        },
+        // SAFETY: the NEON kernel is only picked when the CPU supports it.
+        #[cfg(target_arch = "aarch64")]
+        Kernel::Neon if kernel.is_supported() => unsafe {
+            masks_neon(guess.as_bytes(), columns, rule, masks)
+        },
+        _ => masks_portable(guess.as_bytes(), columns, rule, masks),
+    }
+    masks.truncate(columns.words);
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn masks_avx2(guess: &[u8], columns: &Columns, rule: Duplicates, masks: &mut [u8]) {
+    masks_portable(guess, columns, rule, masks)
+}
+
+#[cfg(target_arch = "aarch64")]
+#[target_feature(enable = "neon")]
+unsafe fn masks_neon(guess: &[u8], columns: &Columns, rule: Duplicates, masks: &mut [u8]) {
+    masks_portable(guess, columns, rule, masks)
+}
+
+// Always inlined, so that each of the kernels above gets its own copy, compiled for its own
+// instructions.
+#[inline(always)]
+fn masks_portable(guess: &[u8], columns: &Columns, rule: Duplicates, masks: &mut [u8]) {
+    let len = guess.len();
+    if len == 0 {
+        return;
+    }
+    for (answer, masks) in columns
+        .chunks
+        .chunks_exact(len)
+        .zip(masks.chunks_exact_mut(LANES))
+    {
+        chunk_masks(guess, answer, rule, masks.try_into().unwrap());
+    }
+}
+
+/// The feedback to `guess` from each of one chunk of candidates, lane by lane, with no branches
+/// that depend on the lane so that each loop over the lanes becomes a few vector instructions.
+///
+/// A guessed letter that isn't green is yellow if the answer has more copies of it that could be
+/// yellow than the guess has copies of it before this one that aren't green, which is the same
+/// as what [`Correctness::compute_with`] works out one letter at a time.
+#[inline(always)]
+fn chunk_masks(guess: &[u8], answer: &[[u8; LANES]], rule: Duplicates, masks: &mut [u8; LANES]) {
+    let mut green = [[0u8; LANES]; MAX_KERNEL_WORD_LEN];
+    for ((green, answer), &g) in green.iter_mut().zip(answer).zip(guess) {
+        for lane in 0..LANES {
+            green[lane] = u8::from(answer[lane] == g);
+        }
+    }
+    // Under the official rule, the copies of a letter in the right place can't also make another
+    // copy yellow.
+    let greens_count = u8::from(rule != Duplicates::Official);
+
+    *masks = [0; LANES];
+    for (i, &g) in guess.iter().enumerate() {
+        let mut copies = [0u8; LANES];
+        for (answer, green) in answer.iter().zip(&green) {
+            for lane in 0..LANES {
+                copies[lane] += u8::from(answer[lane] == g) & (greens_count | (1 - green[lane]));
+            }
+        }
+        // Under the per-letter rule, however many copies were guessed before, they don't use
+        // any up.
+        let mut earlier = [0u8; LANES];
+        if rule != Duplicates::PerLetter {
+            for (_, green) in guess[..i].iter().zip(&green).filter(|&(&e, _)| e == g) {
+                for lane in 0..LANES {
+                    earlier[lane] += 1 - green[lane];
+                }
+            }
+        }
+        for lane in 0..LANES {
+            let yellow = u8::from(copies[lane] > earlier[lane]);
+            // 0 for green, 1 for yellow and 2 for gray, as in `Correctness::pack`.
+            let feedback = (1 - green[i][lane]) * (2 - yellow);
+            masks[lane] = masks[lane] * 3 + feedback;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{masks_with, set_kernel, Columns, Feedback, Kernel};
+    use crate::{dictionary, profile::Duplicates, Correctness};
+
+    #[test]
+    fn kernels_agree_with_compute() {
+        // More words than fit in one chunk, with plenty of repeated letters.
+        let mut words: Vec<_> = dictionary().iter().take(70).map(|&(w, _)| w).collect();
+        words.extend([
+            "speed", "erase", "sissy", "eerie", "abbey", "geese", "llama",
+        ]);
+        let columns = Columns::new(words.iter().copied(), 5).unwrap();
+        let mut masks = Vec::new();
+        assert!(Kernel::Portable.is_supported());
+        for kernel in [Kernel::Avx2, Kernel::Neon, Kernel::Portable] {
+            if !kernel.is_supported() {
+                assert!(set_kernel(kernel).is_err());
+                continue;
+            }
+            for rule in [
+                Duplicates::Official,
+                Duplicates::PerLetter,
+                Duplicates::CountCapped,
+            ] {
+                for guess in &words {
+                    masks_with(kernel, rule, guess, &columns, &mut masks);
+                    let expected: Vec<_> = words
+                        .iter()
+                        .map(|answer| {
+                            let mask = Correctness::compute_with(answer, guess, rule);
+                            Correctness::pack(&mask[..5]) as u8
+                        })
+                        .collect();
+                    assert_eq!(masks, expected, "{} kernel, {}, {}", kernel, rule, guess);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn feedback_of_long_words() {
+        let words = vec!["abcdef", "fedcba", "abcabc"];
+        let packed: Vec<_> = Feedback::new(words.clone()).packed("abcdef").collect();
+        let expected: Vec<_> = words
+            .iter()
+            .map(|answer| Correctness::compute_packed(answer, "abcdef"))
+            .collect();
+        assert_eq!(packed, expected);
+    }
+
+    #[test]
+    fn feedback_of_other_alphabets() {
+        // Byte for byte, "niños" is six letters long, so the kernel can't be used.
+        let words = vec!["niños", "señor", "ninos"];
+        for guess in ["señor", "tares"] {
+            let packed: Vec<_> = Feedback::new(words.clone()).packed(guess).collect();
+            let expected: Vec<_> = words
+                .iter()
+                .map(|answer| Correctness::compute_packed(answer, guess))
+                .collect();
+            assert_eq!(packed, expected);
+        }
+        // Nor for a guess like that against words it could be used for.
+        let words = vec!["tares", "ninos"];
+        let packed: Vec<_> = Feedback::new(words.clone()).packed("niños").collect();
+        assert_eq!(packed[1], Correctness::compute_packed("ninos", "niños"));
+    }
+
+    #[test]
+    fn too_long() {
+        assert!(Columns::new(["abcdef"], 6).is_none());
+        assert_eq!(Columns::new(Vec::new(), 5).unwrap().words, 0);
+    }
+}
@@ -0,0 +1,262 @@
+//! The mask of every guess against every answer, worked out up front and saved to a file, so that
+//! the cache of [`Cached`](super::Cached) starts out full instead of filling in as games are
+//! played.
+//!
+//! The file is laid out just like the cache: a header as long as a cache line, and then a row for
+//! each word in the dictionary, padded out to a whole number of cache lines, where each byte is
+//! the packed mask + 1. That way a run can map it into memory as it is, and only the rows it uses
+//! are ever read from disk.
+
+use super::cache::{CacheLayout, CACHE_LINE};
+use super::kernel::Feedback;
+use crate::profile::{default_cache_dir, duplicates, Duplicates};
+use crate::{dictionary, dictionary_info};
+use memmap2::{MmapMut, MmapOptions};
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+const MAGIC: &[u8; 8] = b"WSMASKS\0";
+const VERSION: u32 = 1;
+
+/// The mask of every word in the dictionary in use against every other, laid out like the cache
+/// of [`Cached`](super::Cached).
+pub struct PatternMatrix {
+    layout: CacheLayout,
+    num_words: usize,
+    row_bytes: usize,
+    cells: Vec<u8>,
+}
+
+impl PatternMatrix {
+    /// Works out every mask for the dictionary in use on `workers` threads, which takes about a
+    /// second on one thread for the official dictionary.
+    pub fn compute(layout: CacheLayout, workers: usize) -> Self {
+        let words: Vec<_> = dictionary().iter().map(|&(word, _)| word).collect();
+        Self::compute_for(&words, layout, workers)
+    }
+
+    fn compute_for(words: &[&str], layout: CacheLayout, workers: usize) -> Self {
+        let num_words = words.len();
+        let row_bytes = num_words.div_ceil(CACHE_LINE) * CACHE_LINE;
+        let mut cells = vec![0u8; num_words * row_bytes];
+        // Each worker works out a block of rows, which takes about as long as any other.
+        let rows_per_worker = num_words.div_ceil(workers.max(1)).max(1);
+        std::thread::scope(|s| {
+            for (block, cells) in cells
+                .chunks_mut((rows_per_worker * row_bytes).max(1))
+                .enumerate()
+            {
+                // The kernel works out the masks of one guess against many answers at once, so a
+                // row of guesses is filled in a column at a time, for all of the block's answers.
+                s.spawn(move || match layout {
+                    CacheLayout::GuessMajor => {
+                        let mut feedback = Feedback::new(words.to_vec());
+                        for (i, cells) in cells.chunks_mut(row_bytes).enumerate() {
+                            let guess = words[block * rows_per_worker + i];
+                            for (cell, mask) in cells.iter_mut().zip(feedback.packed(guess)) {
+                                *cell = mask as u8 + 1;
+                            }
+                        }
+                    }
+                    CacheLayout::CandidateMajor => {
+                        let first = block * rows_per_worker;
+                        let rows = cells.len() / row_bytes;
+                        let mut feedback = Feedback::new(words[first..first + rows].to_vec());
+                        for (column, &guess) in words.iter().enumerate() {
+                            for (row, mask) in feedback.packed(guess).enumerate() {
+                                cells[row * row_bytes + column] = mask as u8 + 1;
+                            }
+                        }
+                    }
+                });
+            }
+        });
+        Self {
+            layout,
+            num_words,
+            row_bytes,
+            cells,
+        }
+    }
+
+    pub fn layout(&self) -> CacheLayout {
+        self.layout
+    }
+
+    /// How many words there are masks for.
+    pub fn len(&self) -> usize {
+        self.num_words
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.num_words == 0
+    }
+
+    /// The packed mask of the `guess_idx`th word in the dictionary against the `answer_idx`th.
+    pub fn get(&self, guess_idx: usize, answer_idx: usize) -> usize {
+        let (row, column) = match self.layout {
+            CacheLayout::GuessMajor => (guess_idx, answer_idx),
+            CacheLayout::CandidateMajor => (answer_idx, guess_idx),
+        };
+        assert!(column < self.num_words);
+        usize::from(self.cells[row * self.row_bytes + column] - 1)
+    }
+
+    /// Where the masks for the dictionary, duplicate-letter rule and `layout` in use are kept by
+    /// default, which is where [`Cached`](super::Cached) looks for them.
+    pub fn default_path(layout: CacheLayout) -> Option<PathBuf> {
+        Some(default_cache_dir()?.join(format!(
+            "masks-{}-{}-{}.bin",
+            dictionary_info().version(),
+            duplicates(),
+            layout
+        )))
+    }
+
+    /// Writes the masks to `path`, for the dictionary and duplicate-letter rule in use.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let header = header(
+            self.layout,
+            self.num_words,
+            dictionary_info().checksum,
+            duplicates(),
+        );
+        self.save_with_header(path, &header)
+    }
+
+    fn save_with_header(&self, path: &Path, header: &[u8; CACHE_LINE]) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        // Write it somewhere else first, so that another run never maps half of it.
+        let partial = path.with_extension(format!("{}.tmp", std::process::id()));
+        let mut bytes = Vec::with_capacity(CACHE_LINE + self.cells.len());
+        bytes.extend_from_slice(header);
+        bytes.extend_from_slice(&self.cells);
+        fs::write(&partial, bytes)?;
+        fs::rename(&partial, path)
+    }
+}
+
+/// The header of a file of masks for `num_words` words of the dictionary with `checksum`, scored
+/// with `duplicates`.
+fn header(
+    layout: CacheLayout,
+    num_words: usize,
+    checksum: u64,
+    duplicates: Duplicates,
+) -> [u8; CACHE_LINE] {
+    let mut header = [0; CACHE_LINE];
+    header[..8].copy_from_slice(MAGIC);
+    header[8..12].copy_from_slice(&VERSION.to_le_bytes());
+    header[12] = match layout {
+        CacheLayout::GuessMajor => 0,
+        CacheLayout::CandidateMajor => 1,
+    };
+    header[13] = match duplicates {
+        Duplicates::Official => 0,
+        Duplicates::PerLetter => 1,
+        Duplicates::CountCapped => 2,
+    };
+    header[16..24].copy_from_slice(&(num_words as u64).to_le_bytes());
+    header[24..32].copy_from_slice(&checksum.to_le_bytes());
+    header
+}
+
+/// Maps the masks in `path` into memory, laid out like the cache's cells, failing unless they were
+/// worked out for the dictionary, duplicate-letter rule and `layout` in use.
+///
+/// The mapping is private, so nothing written to it ever reaches the file.
+pub(super) fn map(path: &Path, layout: CacheLayout) -> Result<MmapMut, String> {
+    let num_words = dictionary().len();
+    let expected = header(layout, num_words, dictionary_info().checksum, duplicates());
+    map_with_header(path, num_words, &expected)
+}
+
+fn map_with_header(
+    path: &Path,
+    num_words: usize,
+    expected: &[u8; CACHE_LINE],
+) -> Result<MmapMut, String> {
+    let describe = |e: io::Error| format!("could not read '{}': {}", path.display(), e);
+    let mut file = fs::File::open(path).map_err(describe)?;
+    let mut header = [0; CACHE_LINE];
+    file.read_exact(&mut header).map_err(describe)?;
+    if header[..12] != expected[..12] {
+        return Err(format!(
+            "'{}' is not a file of masks this version can read",
+            path.display()
+        ));
+    }
+    if header != *expected {
+        return Err(format!(
+            "'{}' was worked out for a different dictionary, duplicate-letter rule or layout",
+            path.display()
+        ));
+    }
+    let len = num_words * num_words.div_ceil(CACHE_LINE) * CACHE_LINE;
+    let file_len = file.metadata().map_err(describe)?.len();
+    if file_len != (CACHE_LINE + len) as u64 {
+        return Err(format!("'{}' is cut short", path.display()));
+    }
+    // SAFETY: the mapping is private, so other processes writing to the file can at worst change
+    // masks that are then checked against nothing, rather than break memory safety.
+    unsafe {
+        MmapOptions::new()
+            .offset(CACHE_LINE as u64)
+            .len(len)
+            .map_copy(&file)
+    }
+    .map_err(describe)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{header, map_with_header, PatternMatrix};
+    use crate::algorithms::CacheLayout;
+    use crate::profile::Duplicates;
+    use crate::Correctness;
+
+    const WORDS: [&str; 5] = ["cigar", "rebut", "sissy", "humph", "awake"];
+
+    #[test]
+    fn computes_every_mask() {
+        for layout in [CacheLayout::GuessMajor, CacheLayout::CandidateMajor] {
+            let matrix = PatternMatrix::compute_for(&WORDS, layout, 3);
+            assert_eq!(matrix.len(), WORDS.len());
+            for (g, guess) in WORDS.iter().enumerate() {
+                for (a, answer) in WORDS.iter().enumerate() {
+                    assert_eq!(matrix.get(g, a), Correctness::compute_packed(answer, guess));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn saves_and_maps() {
+        let dir = std::env::temp_dir().join(format!("wordle-masks-test-{}", std::process::id()));
+        let path = dir.join("masks.bin");
+        let layout = CacheLayout::GuessMajor;
+        let matrix = PatternMatrix::compute_for(&WORDS, layout, 2);
+        let expected = header(layout, WORDS.len(), 42, Duplicates::Official);
+        matrix.save_with_header(&path, &expected).unwrap();
+
+        let map = map_with_header(&path, WORDS.len(), &expected).unwrap();
+        assert_eq!(map.len(), matrix.cells.len());
+        assert_eq!(&map[..], &matrix.cells[..]);
+
+        let other = header(layout, WORDS.len(), 43, Duplicates::Official);
+        assert!(map_with_header(&path, WORDS.len(), &other).is_err());
+        let other = header(
+            CacheLayout::CandidateMajor,
+            WORDS.len(),
+            42,
+            Duplicates::Official,
+        );
+        assert!(map_with_header(&path, WORDS.len(), &other).is_err());
+        std::fs::write(&path, b"not masks").unwrap();
+        assert!(map_with_header(&path, WORDS.len(), &expected).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
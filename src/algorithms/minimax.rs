@@ -0,0 +1,155 @@
+use super::kernel::Feedback;
+use super::tiebreak::{Best, BreaksTies, Frequency, TieBreak};
+use crate::{candidate_answers, dictionary, letter_count, Correctness, Guess, Guesser};
+use once_cell::sync::OnceCell;
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Knuth's method for Mastermind: guesses the word whose feedback leaves the fewest candidates in
+/// the worst case, out of every word in the dictionary, rather than the one expected to give the
+/// most information. Between guesses that are equally good in the worst case, it prefers one that
+/// could be the answer.
+///
+/// It ignores how likely each candidate is, so it is a baseline for the scorers that don't rather
+/// than a contender.
+#[derive(Clone)]
+pub struct Minimax {
+    remaining: Cow<'static, [(&'static str, usize)]>,
+    tiebreak: Arc<dyn TieBreak>,
+}
+
+impl Default for Minimax {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Minimax {
+    pub fn new() -> Self {
+        Self {
+            remaining: Cow::Borrowed(candidate_answers()),
+            tiebreak: Arc::new(Frequency),
+        }
+    }
+}
+
+/// The guess out of `guesses` whose feedback leaves the fewest of `candidates` in the worst case,
+/// preferring one of the candidates, and then as `tiebreak` does.
+fn best_guess(
+    guesses: impl IntoIterator<Item = &'static str>,
+    candidates: &[&'static str],
+    tiebreak: &dyn TieBreak,
+) -> Option<&'static str> {
+    let is_candidate: HashSet<_> = candidates.iter().copied().collect();
+    let mut feedback = Feedback::new(candidates.to_vec());
+    let mut best = Best::new(tiebreak);
+    for word in guesses {
+        let mut buckets = vec![0usize; Correctness::count(letter_count(word))];
+        for idx in feedback.packed(word) {
+            buckets[idx] += 1;
+        }
+        let worst = buckets.into_iter().max().unwrap_or(0) as f64;
+        // Bucket sizes are whole numbers, so the bonus only ever decides between equals.
+        let bonus = if is_candidate.contains(word) {
+            0.5
+        } else {
+            0.0
+        };
+        best.consider(word, bonus - worst);
+    }
+    best.word()
+}
+
+impl BreaksTies for Minimax {
+    fn tiebreak_mut(&mut self) -> &mut Arc<dyn TieBreak> {
+        &mut self.tiebreak
+    }
+}
+
+impl Guesser for Minimax {
+    fn guess(&mut self, history: &[Guess]) -> Cow<'static, str> {
+        if let Some(last) = history.last() {
+            if matches!(self.remaining, Cow::Owned(_)) {
+                self.remaining
+                    .to_mut()
+                    .retain(|(word, _)| last.matches(word));
+            } else {
+                self.remaining = Cow::Owned(
+                    self.remaining
+                        .iter()
+                        .filter(|(word, _)| last.matches(word))
+                        .copied()
+                        .collect(),
+                );
+            }
+        }
+        if self.remaining.len() == 1 {
+            return Cow::Borrowed(self.remaining[0].0);
+        }
+        let guesses = dictionary().iter().map(|&(word, _)| word);
+        if history.is_empty() {
+            // Going through every pair of words in the dictionary takes a while, and always gives
+            // the same opener, so it is only done once.
+            static OPENER: OnceCell<&'static str> = OnceCell::new();
+            let opener = *OPENER.get_or_init(|| {
+                let candidates: Vec<_> = candidate_answers().iter().map(|&(w, _)| w).collect();
+                best_guess(guesses, &candidates, &Frequency)
+                    .expect("the dictionary has words in it")
+            });
+            return Cow::Borrowed(opener);
+        }
+        let candidates: Vec<_> = self.remaining.iter().map(|&(word, _)| word).collect();
+        Cow::Borrowed(
+            best_guess(guesses, &candidates, &*self.tiebreak)
+                .expect("the dictionary has words in it"),
+        )
+    }
+
+    fn reset(&mut self) {
+        self.remaining = Cow::Borrowed(candidate_answers());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{best_guess, Minimax};
+    use crate::algorithms::tiebreak::Alphabetical;
+    use crate::{Correctness, Guess, Guesser};
+    use std::borrow::Cow;
+
+    #[test]
+    fn minimises_the_worst_case() {
+        // Guessing one of the "-ight" words leaves the others all getting the same feedback, so
+        // a word testing several of their first letters is better even though it can't be the
+        // answer.
+        let candidates = [
+            "fight", "light", "might", "night", "right", "sight", "tight", "wight",
+        ];
+        let guess = best_guess(["fight", "tawny"], &candidates, &Alphabetical);
+        assert_eq!(guess, Some("tawny"));
+        // With as good a worst case, a candidate wins.
+        let guess = best_guess(["flock", "light"], &candidates[..2], &Alphabetical);
+        assert_eq!(guess, Some("light"));
+    }
+
+    #[test]
+    fn plays() {
+        // Working out the opener takes a while without optimisations, so start from another one.
+        for answer in ["cigar", "rebut", "sissy"] {
+            let mut guesser = Minimax::new();
+            let mut history = vec![Guess {
+                word: Cow::Borrowed("tares"),
+                mask: Correctness::compute(answer, "tares"),
+            }];
+            while history.last().unwrap().word != answer {
+                let word = guesser.guess(&history);
+                history.push(Guess {
+                    mask: Correctness::compute(answer, &word),
+                    word,
+                });
+            }
+            assert!(history.len() <= 6, "{:?}", history);
+        }
+    }
+}
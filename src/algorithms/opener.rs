@@ -0,0 +1,56 @@
+use crate::{Guess, Guesser};
+use std::borrow::Cow;
+
+/// Makes another guesser start with a different first guess, leaving the rest of the game to it.
+#[derive(Clone)]
+pub struct Opener<G> {
+    opener: Cow<'static, str>,
+    inner: G,
+}
+
+impl<G> Opener<G> {
+    pub fn new(opener: impl Into<Cow<'static, str>>, inner: G) -> Self {
+        Self {
+            opener: opener.into(),
+            inner,
+        }
+    }
+}
+
+impl<G: Guesser> Guesser for Opener<G> {
+    fn guess(&mut self, history: &[Guess]) -> Cow<'static, str> {
+        if history.is_empty() {
+            // Still ask, in case the guesser sets itself up on its first guess.
+            self.inner.guess(history);
+            return self.opener.clone();
+        }
+        self.inner.guess(history)
+    }
+
+    fn finish(&self, guesses: usize) {
+        self.inner.finish(guesses)
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Opener;
+    use crate::algorithms::Popular;
+    use crate::Wordle;
+
+    #[test]
+    fn opener() {
+        let w = Wordle::new();
+        assert_eq!(
+            w.play("crane", Opener::new("crane", Popular::new())),
+            Some(1)
+        );
+        assert!(w
+            .play("cigar", Opener::new("crane", Popular::new()))
+            .is_some());
+    }
+}
@@ -0,0 +1,115 @@
+use super::escore::Escore;
+use super::exact::Exact;
+use super::words::weighted;
+use crate::{Guess, Guesser};
+use std::borrow::Cow;
+
+/// Guesses the candidate that needs the fewest guesses on average, worked out exactly by playing
+/// out every feedback each guess could get, once few enough candidates are left.
+///
+/// Every candidate is tried as a guess at every step, which takes exponential time, so while more
+/// than [`max_candidates`](Self::with_max_candidates) are left it guesses like [`Escore`] instead.
+/// Only candidates are ever guessed, so a guess that can't be the answer but would split the rest
+/// better is never found.
+#[derive(Clone)]
+pub struct Optimal {
+    remaining: Cow<'static, [(&'static str, f64)]>,
+    max_candidates: usize,
+    fallback: Escore,
+}
+
+impl Default for Optimal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How many candidates are searched exactly by default, which takes a few tens of milliseconds at
+/// worst.
+const MAX_CANDIDATES: usize = 128;
+
+impl Optimal {
+    pub fn new() -> Self {
+        Self {
+            remaining: Cow::Borrowed(weighted()),
+            max_candidates: MAX_CANDIDATES,
+            fallback: Escore::new(),
+        }
+    }
+
+    /// Only works out the best guess exactly once at most `max` candidates are left, guessing
+    /// like `fallback` until then.
+    pub fn with_max_candidates(mut self, max: usize) -> Self {
+        self.max_candidates = max;
+        self
+    }
+
+    /// Guesses like `fallback` while there are too many candidates to search exactly.
+    pub fn with_fallback(mut self, fallback: Escore) -> Self {
+        self.fallback = fallback;
+        self
+    }
+}
+
+impl Guesser for Optimal {
+    fn guess(&mut self, history: &[Guess]) -> Cow<'static, str> {
+        if let Some(last) = history.last() {
+            if matches!(self.remaining, Cow::Owned(_)) {
+                self.remaining
+                    .to_mut()
+                    .retain(|(word, _)| last.matches(word));
+            } else {
+                self.remaining = Cow::Owned(
+                    self.remaining
+                        .iter()
+                        .filter(|(word, _)| last.matches(word))
+                        .copied()
+                        .collect(),
+                );
+            }
+        }
+        // The fallback keeps track of the candidates itself, so it has to see every guess until
+        // it is no longer needed.
+        if self.remaining.len() > self.max_candidates {
+            return self.fallback.guess(history);
+        }
+        match Exact::default().best_guess(&self.remaining) {
+            Some((word, _)) => Cow::Borrowed(word),
+            None => self.fallback.guess(history),
+        }
+    }
+
+    fn reset(&mut self) {
+        self.remaining = Cow::Borrowed(weighted());
+        self.fallback.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Optimal;
+    use crate::algorithms::Escore;
+    use crate::Wordle;
+
+    #[test]
+    fn no_worse_than_fallback() {
+        let w = Wordle::new();
+        let answers = [
+            "cigar", "rebut", "sissy", "humph", "awake", "blush", "focal",
+        ];
+        let score = |guesser: &dyn Fn() -> Optimal| -> usize {
+            answers
+                .iter()
+                .map(|answer| w.play(answer, guesser()).unwrap())
+                .sum()
+        };
+        let optimal = score(&Optimal::new);
+        let fallback: usize = answers
+            .iter()
+            .map(|answer| w.play(answer, Escore::new()).unwrap())
+            .sum();
+        assert!(optimal <= fallback + 2, "{} vs {}", optimal, fallback);
+        // Without searching exactly it plays just like its fallback.
+        assert_eq!(score(&|| Optimal::new().with_max_candidates(0)), fallback);
+    }
+}
@@ -1,13 +1,12 @@
-use crate::{Guess, Guesser, DICTIONARY};
-use once_cell::sync::OnceCell;
+use super::first_guess;
+use crate::{candidate_answers, Guess, Guesser};
 use std::borrow::Cow;
 
-static INITIAL: OnceCell<Vec<(&'static str, usize)>> = OnceCell::new();
-
 /// A strawman algorithm which simply chooses the most popular word of the
 /// words remaining which match the most recent mask
+#[derive(Clone)]
 pub struct Popular {
-    remaining: Cow<'static, Vec<(&'static str, usize)>>,
+    remaining: Cow<'static, [(&'static str, usize)]>,
 }
 
 impl Default for Popular {
@@ -19,13 +18,13 @@ impl Default for Popular {
 impl Popular {
     pub fn new() -> Self {
         Self {
-            remaining: Cow::Borrowed(INITIAL.get_or_init(|| DICTIONARY.to_vec())),
+            remaining: Cow::Borrowed(candidate_answers()),
         }
     }
 }
 
 impl Guesser for Popular {
-    fn guess(&mut self, history: &[Guess]) -> String {
+    fn guess(&mut self, history: &[Guess]) -> Cow<'static, str> {
         if let Some(last) = history.last() {
             if matches!(self.remaining, Cow::Owned(_)) {
                 self.remaining
@@ -41,10 +40,13 @@ impl Guesser for Popular {
                 );
             }
         }
-        if history.is_empty() {
-            "tares".to_string()
-        } else {
-            self.remaining.first().unwrap().0.to_string()
+        match first_guess().filter(|_| history.is_empty()) {
+            Some(opener) => Cow::Borrowed(opener),
+            None => Cow::Borrowed(self.remaining.first().unwrap().0),
         }
     }
+
+    fn reset(&mut self) {
+        self.remaining = Cow::Borrowed(candidate_answers());
+    }
 }
@@ -1,14 +1,17 @@
-use crate::{Correctness, Guess, Guesser, DICTIONARY};
+use super::first_guess;
+use super::tiebreak::{Best, BreaksTies, Frequency, TieBreak};
+use crate::{candidate_answers, dictionary, word_len, Correctness, Guess, Guesser, Mask};
 use once_cell::sync::OnceCell;
 use std::borrow::Cow;
 use std::collections::BTreeMap;
+use std::sync::Arc;
 
-static INITIAL: OnceCell<Vec<(&'static str, usize)>> = OnceCell::new();
-static MATCH: OnceCell<BTreeMap<(&'static str, &'static str, [Correctness; 5]), bool>> =
-    OnceCell::new();
+static MATCH: OnceCell<BTreeMap<(&'static str, &'static str, Mask), bool>> = OnceCell::new();
 
+#[derive(Clone)]
 pub struct Precalc {
-    remaining: Cow<'static, Vec<(&'static str, usize)>>,
+    remaining: Cow<'static, [(&'static str, usize)]>,
+    tiebreak: Arc<dyn TieBreak>,
 }
 
 impl Default for Precalc {
@@ -20,19 +23,20 @@ impl Default for Precalc {
 impl Precalc {
     pub fn new() -> Self {
         Self {
-            remaining: Cow::Borrowed(INITIAL.get_or_init(|| DICTIONARY.to_vec())),
+            remaining: Cow::Borrowed(candidate_answers()),
+            tiebreak: Arc::new(Frequency),
         }
     }
 }
 
-#[derive(Debug, Copy, Clone)]
-struct Candidate {
-    word: &'static str,
-    goodness: f64,
+impl BreaksTies for Precalc {
+    fn tiebreak_mut(&mut self) -> &mut Arc<dyn TieBreak> {
+        &mut self.tiebreak
+    }
 }
 
 impl Guesser for Precalc {
-    fn guess(&mut self, history: &[Guess]) -> String {
+    fn guess(&mut self, history: &[Guess]) -> Cow<'static, str> {
         if let Some(last) = history.last() {
             if matches!(self.remaining, Cow::Owned(_)) {
                 self.remaining
@@ -48,31 +52,31 @@ impl Guesser for Precalc {
                 );
             }
         }
-        if history.is_empty() {
-            return "tares".to_string();
+        if let Some(opener) = first_guess().filter(|_| history.is_empty()) {
+            return Cow::Borrowed(opener);
         }
 
         let remaining_count: usize = self.remaining.iter().map(|&(_, c)| c).sum();
 
-        let mut best: Option<Candidate> = None;
+        let mut best = Best::new(&*self.tiebreak);
         for &(word, _) in &*self.remaining {
             let mut sum = 0.0;
             // TODO: don't consider correctness patterns that had no candidates in the previous
             // iteration
-            for pattern in Correctness::patterns() {
+            for &pattern in Correctness::possible_patterns(word_len()) {
                 // considering a world where we _did_ guess `word` and got `pattern` as the
                 // correctness. now, compute what _then_ is left.
                 let mut in_pattern_total = 0;
                 for (candidate, count) in &*self.remaining {
                     let matches = MATCH.get_or_init(|| {
-                        let words = &INITIAL.get().unwrap()[..512];
+                        let words = &dictionary()[..512];
                         let mut out = BTreeMap::new();
                         for &(word1, _) in words {
                             for &(word2, _) in words {
                                 if word2 < word1 {
                                     break;
                                 }
-                                for pattern in Correctness::patterns() {
+                                for &pattern in Correctness::possible_patterns(word_len()) {
                                     let g = Guess {
                                         word: Cow::Borrowed(word1),
                                         mask: pattern,
@@ -84,10 +88,10 @@ impl Guesser for Precalc {
                         out
                     });
 
-                    let key = if word < candidate {
-                        (&*word, *candidate, pattern)
+                    let key = if word < *candidate {
+                        (word, *candidate, pattern)
                     } else {
-                        (*candidate, &*word, pattern)
+                        (*candidate, word, pattern)
                     };
                     if matches.get(&key).copied().unwrap_or_else(|| {
                         let g = Guess {
@@ -108,15 +112,12 @@ impl Guesser for Precalc {
             }
             // TODO: weight this by p_word
             let goodness = -sum;
-            if let Some(c) = best {
-                // Is this one better?
-                if goodness > c.goodness {
-                    best = Some(Candidate { word, goodness });
-                }
-            } else {
-                best = Some(Candidate { word, goodness });
-            }
+            best.consider(word, goodness);
         }
-        best.unwrap().word.to_string()
+        Cow::Borrowed(best.word().unwrap())
+    }
+
+    fn reset(&mut self) {
+        self.remaining = Cow::Borrowed(candidate_answers());
     }
 }
@@ -0,0 +1,93 @@
+//! How precisely the guessers work out how much information a guess gives.
+//!
+//! For every guess they consider, the escore, sigmoid and cache implementations add up how likely
+//! each feedback is over all of the candidates left, and then how much information that gives.
+//! On large candidate sets that is where nearly all of the time goes, and doing it in single
+//! precision halves the memory it touches and makes the logarithms cheaper. The guesses only
+//! change when two of them are within a rounding error of each other, which hardly ever affects
+//! the score; the `compare-precision` command shows by how much on a sample of answers.
+
+use super::summation;
+use std::fmt;
+use std::str::FromStr;
+
+/// Which floating point type the probabilities of the feedback are worked out in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Precision {
+    /// Double precision, which gives the same guesses as the published scores.
+    #[default]
+    F64,
+    /// Single precision, which is faster but may pick a different guess when two are about as
+    /// good as each other.
+    F32,
+}
+
+impl FromStr for Precision {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "f64" => Ok(Self::F64),
+            "f32" => Ok(Self::F32),
+            _ => Err(format!("unknown precision '{}', expected f64 or f32", s)),
+        }
+    }
+}
+
+impl fmt::Display for Precision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::F64 => "f64",
+            Self::F32 => "f32",
+        })
+    }
+}
+
+/// The information (in bits) expected from a guess, given how much of the candidates' weight,
+/// out of `remaining_p` in total, gets each feedback, worked out in single precision.
+pub(crate) fn information_f32(totals: &[f32], remaining_p: f64) -> f64 {
+    let remaining_p = remaining_p as f32;
+    // The least likely answers are less likely than the smallest f32, so check for zero after
+    // dividing rather than before, or 0 * log2(0) would make the result NaN.
+    -summation::sum(
+        totals
+            .iter()
+            .map(|&p| p / remaining_p)
+            .filter(|&p| p != 0.0)
+            .map(|p_of_this_pattern| f64::from(p_of_this_pattern * p_of_this_pattern.log2())),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{information_f32, Precision};
+
+    #[test]
+    fn f32_information_is_close() {
+        let totals = [0.0, 3.5, 120.25, 0.001, 47.0, 1e-6, 9.75];
+        let remaining_p: f64 = totals.iter().sum();
+        let exact = -totals
+            .iter()
+            .filter(|&&t| t != 0.0)
+            .map(|&p| {
+                let p = p / remaining_p;
+                p * p.log2()
+            })
+            .sum::<f64>();
+        let single: Vec<_> = totals.iter().map(|&t| t as f32).collect();
+        assert!((information_f32(&single, remaining_p) - exact).abs() < 1e-5);
+    }
+
+    #[test]
+    fn f32_information_ignores_underflow() {
+        assert_eq!(information_f32(&[1e10, 1e-40], 1e10), 0.0);
+    }
+
+    #[test]
+    fn parse() {
+        for precision in [Precision::F64, Precision::F32] {
+            assert_eq!(precision.to_string().parse(), Ok(precision));
+        }
+        assert!("f16".parse::<Precision>().is_err());
+    }
+}
@@ -0,0 +1,182 @@
+use super::summation;
+use super::words::WordIndex;
+use crate::{candidate_answers, dictionary, letter_count, Correctness, Guess, Guesser};
+use rhai::{Array, Dynamic, Engine, Map, Scope, AST};
+use std::borrow::Cow;
+use std::rc::Rc;
+
+/// A guesser whose strategy is a [rhai](https://rhai.rs) script, for quickly trying out ideas
+/// without recompiling.
+///
+/// The script must define `guess(candidates, history)`, which is given the words still consistent
+/// with the history (most frequent first) and the history itself as an array of
+/// `#{ word: "tares", mask: "XYYXX" }` maps, and returns the word to guess. It can use these
+/// helpers:
+///
+/// - `entropy(word, candidates)`: the expected information, in bits, from guessing `word` when
+///   each of `candidates` is equally likely to be the answer.
+/// - `mask(answer, guess)`: the feedback for `guess`, e.g. `"GYXXY"`.
+/// - `frequency(word)`: how common `word` is, or 0 if it is not in the dictionary.
+///
+/// ```rhai
+/// fn guess(candidates, history) {
+///     if history.is_empty() {
+///         return "tares";
+///     }
+///     let best = candidates[0];
+///     for word in candidates {
+///         if entropy(word, candidates) > entropy(best, candidates) {
+///             best = word;
+///         }
+///     }
+///     best
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Scripted {
+    script: Rc<(Engine, AST)>,
+    remaining: Vec<(&'static str, usize)>,
+}
+
+impl Scripted {
+    /// Compiles `source`, reporting syntax errors as strings.
+    pub fn new(source: &str) -> Result<Self, String> {
+        let mut engine = Engine::new();
+        engine
+            .register_fn("entropy", entropy)
+            .register_fn("mask", |answer: &str, guess: &str| {
+                Correctness::format_mask(
+                    &Correctness::compute(answer, guess)[..letter_count(guess)],
+                )
+            })
+            .register_fn("frequency", |word: &str| {
+                WordIndex::of(word).map_or(0, |idx| dictionary()[usize::from(idx)].1 as i64)
+            });
+        let ast = engine.compile(source).map_err(|e| e.to_string())?;
+        Ok(Self {
+            script: Rc::new((engine, ast)),
+            remaining: candidate_answers().to_vec(),
+        })
+    }
+
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self, String> {
+        let source = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Self::new(&source)
+    }
+}
+
+fn entropy(word: &str, candidates: Array) -> f64 {
+    let mut totals = vec![0usize; Correctness::count(letter_count(word))];
+    let mut n = 0;
+    for candidate in candidates {
+        if let Ok(candidate) = candidate.into_immutable_string() {
+            let idx = Correctness::compute_packed(&candidate, word);
+            totals[idx] += 1;
+            n += 1;
+        }
+    }
+    -summation::sum(totals.into_iter().filter(|t| *t != 0).map(|t| {
+        let p_of_this_pattern = t as f64 / n as f64;
+        p_of_this_pattern * p_of_this_pattern.log2()
+    }))
+}
+
+impl Guesser for Scripted {
+    fn guess(&mut self, history: &[Guess]) -> Cow<'static, str> {
+        if let Some(last) = history.last() {
+            self.remaining.retain(|(word, _)| last.matches(word));
+        }
+
+        let candidates: Array = self
+            .remaining
+            .iter()
+            .map(|&(word, _)| Dynamic::from(word.to_string()))
+            .collect();
+        let history: Array = history
+            .iter()
+            .map(|guess| {
+                let mut map = Map::new();
+                map.insert("word".into(), Dynamic::from(guess.word.to_string()));
+                map.insert(
+                    "mask".into(),
+                    Dynamic::from(Correctness::format_mask(guess.feedback())),
+                );
+                Dynamic::from_map(map)
+            })
+            .collect();
+
+        let (engine, ast) = &*self.script;
+        engine
+            .call_fn::<String>(&mut Scope::new(), ast, "guess", (candidates, history))
+            .unwrap_or_else(|e| panic!("script failed to make a guess: {}", e))
+            .into()
+    }
+
+    fn reset(&mut self) {
+        self.remaining.clear();
+        self.remaining.extend_from_slice(candidate_answers());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Scripted;
+    use crate::Wordle;
+
+    const ENTROPY: &str = r#"
+        fn guess(candidates, history) {
+            if history.is_empty() {
+                return "tares";
+            }
+            let best = candidates[0];
+            let best_entropy = entropy(best, candidates);
+            for word in candidates {
+                let e = entropy(word, candidates);
+                if e > best_entropy {
+                    best = word;
+                    best_entropy = e;
+                }
+            }
+            best
+        }
+    "#;
+
+    #[test]
+    fn plays_a_game() {
+        let w = Wordle::new();
+        let guesser = Scripted::new(ENTROPY).unwrap();
+        assert!(w.play("cigar", guesser).is_some());
+    }
+
+    #[test]
+    fn helpers() {
+        let w = Wordle::new();
+        let guesser = Scripted::new(
+            r#"
+            fn guess(candidates, history) {
+                if history.is_empty() {
+                    return "tares";
+                }
+                if history[0].word == "tares"
+                    && history[0].mask == mask("right", "tares")
+                    && mask("abcde", "eabcd") == "YYYYY"
+                    && frequency("which") > frequency("tares")
+                    && entropy("right", ["right", "fight"]) == 1.0
+                    && candidates.contains("right")
+                {
+                    "right"
+                } else {
+                    "wrong"
+                }
+            }
+        "#,
+        )
+        .unwrap();
+        assert_eq!(w.play("right", guesser), Some(2));
+    }
+
+    #[test]
+    fn syntax_error() {
+        assert!(Scripted::new("fn guess(").is_err());
+    }
+}
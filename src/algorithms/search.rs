@@ -0,0 +1,368 @@
+//! How many of the candidates left the cutoff and cache implementations (and strategies) consider
+//! as guesses.
+//!
+//! Both of them only score the likeliest candidates, since the best guess is nearly always among
+//! them, and scoring every candidate takes quadratic time. How many are enough is a trade-off
+//! between speed and how often the best guess is missed, which [`Calibration`] can work out from
+//! the games being played. It also depends on the game: the second and third guesses decide
+//! most of the score, while once little is left to find out the likeliest candidates are the
+//! only ones worth guessing, which a [`SearchSchedule`] can spell out.
+
+use super::telemetry;
+use super::tiebreak::Best;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+/// How many of the candidates left to consider as guesses.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SearchWidth {
+    /// The share of the candidates to consider, likeliest first.
+    pub fraction: f64,
+    /// The fewest candidates to consider, however few that share is.
+    pub min: usize,
+}
+
+impl Default for SearchWidth {
+    /// A third of the candidates, and at least 20.
+    fn default() -> Self {
+        Self {
+            fraction: 1.0 / 3.0,
+            min: 20,
+        }
+    }
+}
+
+impl SearchWidth {
+    /// How many of `remaining` candidates to consider.
+    pub(crate) fn limit(&self, remaining: usize) -> usize {
+        // A third of 63 would otherwise come out as 20.999..., since a third can't be represented
+        // exactly.
+        ((remaining as f64 * self.fraction + 1e-9) as usize).max(self.min)
+    }
+}
+
+impl FromStr for SearchWidth {
+    type Err = String;
+
+    /// Parses a share of the candidates (or `all`), optionally followed by `min` and the fewest
+    /// candidates to consider, e.g. `0.5 min 10`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words: Vec<_> = s.split_whitespace().collect();
+        let (fraction, min) = match words[..] {
+            [fraction] => (fraction, None),
+            [fraction, "min", min] => (fraction, Some(min)),
+            _ => return Err(format!("'{}' is not a search width like 0.5 min 10", s)),
+        };
+        let fraction = match fraction {
+            "all" => 1.0,
+            _ => fraction
+                .parse()
+                .ok()
+                .filter(|f| (0.0..=1.0).contains(f))
+                .ok_or_else(|| format!("'{}' is not a share between 0 and 1", fraction))?,
+        };
+        let min = match min {
+            Some(min) => min
+                .parse()
+                .map_err(|_| format!("'{}' is not a number", min))?,
+            None => Self::default().min,
+        };
+        Ok(Self { fraction, min })
+    }
+}
+
+/// How wide to search for each guess, depending on how far into the game it is and how much is
+/// left to find out.
+///
+/// Written as clauses separated by commas, each of which is one of
+///
+/// - `round <n> <width>`: search `width` wide for the `n`th guess (the opener is the first).
+/// - `below <bits> bits <width>`: search `width` wide once the candidates left have less than
+///   `bits` of entropy, whatever the round.
+/// - `<width>`: search `width` wide for any other guess (by default a third, and at least 20).
+///
+/// where a width is a [`SearchWidth`], such as `round 2 all, round 3 0.5, below 3 bits 0.1 min 5`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SearchSchedule {
+    /// How wide to search when nothing else says.
+    pub default: SearchWidth,
+    /// How wide to search for particular guesses, by their number.
+    pub rounds: Vec<(usize, SearchWidth)>,
+    /// How wide to search once the candidates left have less than this many bits of entropy.
+    pub low_entropy: Option<(f64, SearchWidth)>,
+}
+
+impl From<SearchWidth> for SearchSchedule {
+    /// Searches `width` wide for every guess.
+    fn from(width: SearchWidth) -> Self {
+        Self {
+            default: width,
+            ..Default::default()
+        }
+    }
+}
+
+impl SearchSchedule {
+    /// Adds one clause to the schedule, as its whitespace-separated words, replacing any earlier
+    /// one for the same round (or the same default or entropy).
+    pub fn add_clause(&mut self, words: &[&str]) -> Result<(), String> {
+        match words {
+            ["round", round, width @ ..] => {
+                let round = round
+                    .parse()
+                    .map_err(|_| format!("'{}' is not a number", round))?;
+                let width = width.join(" ").parse()?;
+                self.rounds.retain(|&(r, _)| r != round);
+                self.rounds.push((round, width));
+            }
+            ["below", bits, "bits", width @ ..] => {
+                let bits = bits
+                    .parse()
+                    .map_err(|_| format!("'{}' is not a number", bits))?;
+                self.low_entropy = Some((bits, width.join(" ").parse()?));
+            }
+            width => self.default = width.join(" ").parse()?,
+        }
+        Ok(())
+    }
+
+    /// How wide to search for the `round`th guess, when the candidates left have `entropy` bits
+    /// of it (which is only worked out if the schedule depends on it).
+    pub(crate) fn width(&self, round: usize, entropy: impl FnOnce() -> f64) -> SearchWidth {
+        if let Some((bits, width)) = self.low_entropy {
+            if entropy() < bits {
+                return width;
+            }
+        }
+        self.rounds
+            .iter()
+            .find(|&&(r, _)| r == round)
+            .map_or(self.default, |&(_, width)| width)
+    }
+}
+
+impl FromStr for SearchSchedule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut schedule = Self::default();
+        for clause in s.split(',').map(str::trim).filter(|c| !c.is_empty()) {
+            let words: Vec<_> = clause.split_whitespace().collect();
+            schedule.add_clause(&words)?;
+        }
+        Ok(schedule)
+    }
+}
+
+/// The entropy (in bits) of candidates that are as likely as their `counts`.
+pub(crate) fn entropy_of_counts(counts: impl Iterator<Item = usize> + Clone) -> f64 {
+    let total: usize = counts.clone().sum();
+    -counts
+        .filter(|&c| c != 0)
+        .map(|c| {
+            let p = c as f64 / total as f64;
+            p * p.log2()
+        })
+        .sum::<f64>()
+}
+
+/// Tunes the [`SearchWidth`] to miss the best guess as often as a target, by now and then
+/// considering every candidate to see whether the best one was within the width.
+///
+/// Each check that finds the best guess was missed widens the search a little, and each check
+/// that finds it wasn't narrows it by `target / (1 - target)` as much, so that the width settles
+/// where a share of `target` of the checks miss. A `Calibration` is shared by every game played,
+/// so that it keeps tuning the same width from one game to the next.
+#[derive(Debug)]
+pub struct Calibration {
+    target: f64,
+    check_every: usize,
+    state: Mutex<CalibrationState>,
+}
+
+#[derive(Debug)]
+struct CalibrationState {
+    width: SearchWidth,
+    guesses: usize,
+    checks: usize,
+    misses: usize,
+}
+
+/// How much a miss widens the search by.
+const STEP: f64 = 0.1;
+
+impl Calibration {
+    /// Starts from `width`, checking one in `check_every` guesses, to miss the best guess in a
+    /// share of `target` of them.
+    pub fn new(width: SearchWidth, target: f64, check_every: usize) -> Self {
+        Self {
+            target: target.clamp(0.0, 0.5),
+            check_every: check_every.max(1),
+            state: Mutex::new(CalibrationState {
+                width,
+                guesses: 0,
+                checks: 0,
+                misses: 0,
+            }),
+        }
+    }
+
+    /// The width tuned so far.
+    pub fn width(&self) -> SearchWidth {
+        self.state.lock().unwrap().width
+    }
+
+    /// How many guesses were checked, and how many of them missed the best guess.
+    pub fn checks(&self) -> (usize, usize) {
+        let state = self.state.lock().unwrap();
+        (state.checks, state.misses)
+    }
+
+    /// The width to use for the next guess, and whether to check it by considering every
+    /// candidate.
+    pub(crate) fn next_guess(&self) -> (SearchWidth, bool) {
+        let mut state = self.state.lock().unwrap();
+        state.guesses += 1;
+        (state.width, state.guesses.is_multiple_of(self.check_every))
+    }
+
+    /// Records whether a checked guess missed the best guess, and tunes the width to match.
+    pub(crate) fn record(&self, missed: bool) {
+        let mut state = self.state.lock().unwrap();
+        state.checks += 1;
+        let factor = if missed {
+            state.misses += 1;
+            1.0 + STEP
+        } else {
+            1.0 - STEP * self.target / (1.0 - self.target)
+        };
+        state.width.fraction = (state.width.fraction * factor).clamp(f64::EPSILON, 1.0);
+    }
+}
+
+/// Keeps track of one guess's search, to tell when to cut it short and, when it is being checked,
+/// which guess it would have been cut short at.
+pub(crate) struct Search<'a> {
+    limit: usize,
+    remaining: usize,
+    considered: usize,
+    buckets: usize,
+    calibration: Option<&'a Calibration>,
+    check: bool,
+    truncated: Option<(&'static str, f64)>,
+}
+
+impl<'a> Search<'a> {
+    /// Starts searching `remaining` candidates `width` wide, or as wide as `calibration` says.
+    pub(crate) fn new(
+        width: SearchWidth,
+        calibration: Option<&'a Calibration>,
+        remaining: usize,
+    ) -> Self {
+        let (width, check) = match calibration {
+            Some(calibration) => calibration.next_guess(),
+            None => (width, false),
+        };
+        Self {
+            limit: width.limit(remaining),
+            remaining,
+            considered: 0,
+            buckets: 0,
+            calibration,
+            check,
+            truncated: None,
+        }
+    }
+
+    /// Records that one more guess was considered, returning whether to stop searching.
+    pub(crate) fn considered(&mut self, best: &Best) -> bool {
+        self.considered += 1;
+        if self.considered == self.limit {
+            self.truncated = best.best();
+            return !self.check;
+        }
+        false
+    }
+
+    /// Records how many different feedbacks the guess just considered could get, for the
+    /// [telemetry](super::telemetry) (which is the only time `buckets` is called).
+    pub(crate) fn count_buckets(&mut self, buckets: impl FnOnce() -> usize) {
+        if telemetry::enabled() {
+            self.buckets += buckets();
+        }
+    }
+
+    /// The guess the search settled on, which is the one it would have been cut short at even
+    /// if it was checked against every candidate.
+    pub(crate) fn finish(self, best: &Best) -> &'static str {
+        telemetry::record_search(
+            self.considered as u64,
+            self.buckets as u64,
+            self.truncated.is_some() && self.limit < self.remaining,
+        );
+        match self.truncated {
+            Some((word, goodness)) => {
+                if let (Some(calibration), true) = (self.calibration, self.check) {
+                    calibration.record(best.beats(goodness));
+                }
+                word
+            }
+            None => best.word().unwrap(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Calibration, SearchSchedule, SearchWidth};
+
+    #[test]
+    fn default_is_a_third() {
+        let width = SearchWidth::default();
+        for remaining in 0..20_000 {
+            assert_eq!(
+                width.limit(remaining),
+                (remaining / 3).max(20),
+                "{}",
+                remaining
+            );
+        }
+    }
+
+    #[test]
+    fn schedule() {
+        let schedule: SearchSchedule = "round 2 all, round 3 0.5 min 10, below 3 bits 0.1, 0.25"
+            .parse()
+            .unwrap();
+        let width = |fraction, min| SearchWidth { fraction, min };
+        assert_eq!(schedule.width(2, || 8.0), width(1.0, 20));
+        assert_eq!(schedule.width(3, || 8.0), width(0.5, 10));
+        assert_eq!(schedule.width(3, || 2.0), width(0.1, 20));
+        assert_eq!(schedule.width(4, || 8.0), width(0.25, 20));
+        assert_eq!(
+            "".parse::<SearchSchedule>()
+                .unwrap()
+                .width(2, || unreachable!()),
+            SearchWidth::default()
+        );
+        assert!("round two 0.5".parse::<SearchSchedule>().is_err());
+        assert!("below 3 bits 2".parse::<SearchSchedule>().is_err());
+        assert!("0.5 max 10".parse::<SearchSchedule>().is_err());
+    }
+
+    #[test]
+    fn calibration_settles_on_target() {
+        let calibration = Calibration::new(SearchWidth::default(), 0.1, 1);
+        // Pretend every guess misses when the search is narrower than a half.
+        for _ in 0..1000 {
+            let (width, check) = calibration.next_guess();
+            assert!(check);
+            calibration.record(width.fraction < 0.5);
+        }
+        let fraction = calibration.width().fraction;
+        assert!((0.45..0.6).contains(&fraction), "{}", fraction);
+        let (checks, misses) = calibration.checks();
+        assert_eq!(checks, 1000);
+        assert!(misses > 0 && misses < 200, "{}", misses);
+    }
+}
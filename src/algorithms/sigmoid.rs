@@ -1,13 +1,24 @@
-use crate::{Correctness, Guess, Guesser, DICTIONARY, MAX_MASK_ENUM};
-use once_cell::sync::OnceCell;
+use super::budget::{out_of_time, TimeBudget};
+use super::explain::{Explanations, GuessExplanation, ScoredGuess};
+use super::first_guess;
+use super::kernel::Feedback;
+use super::precision::{information_f32, Precision};
+use super::summation;
+use super::tiebreak::{Best, BreaksTies, Frequency, TieBreak};
+use super::words::weighted;
+use crate::{letter_count, Correctness, Guess, Guesser};
 use std::borrow::Cow;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-static INITIAL: OnceCell<Vec<(&'static str, f64)>> = OnceCell::new();
-static PATTERNS: OnceCell<Vec<[Correctness; 5]>> = OnceCell::new();
-
+#[derive(Clone)]
 pub struct Sigmoid {
-    remaining: Cow<'static, Vec<(&'static str, f64)>>,
-    patterns: Cow<'static, Vec<[Correctness; 5]>>,
+    remaining: Cow<'static, [(&'static str, f64)]>,
+    tiebreak: Arc<dyn TieBreak>,
+    lambda: f64,
+    budget: Option<TimeBudget>,
+    precision: Precision,
+    explanations: Option<Explanations>,
 }
 
 impl Default for Sigmoid {
@@ -16,77 +27,62 @@ impl Default for Sigmoid {
     }
 }
 
-const L: f64 = 1.0;
-// How steep is the cut-off?
-const K: f64 = 30000000.0;
-// Where is the cut-off?
-const X0: f64 = 0.00000497;
-// This combination of settings leads to a fairly sharp cut-off around:
-//
-//  word  "raw" p      sigmoid p   count
-// vying 0.000491% -> 15.999853% (1560905)
-// rheum 0.000492% -> 16.735476% (1561474)
-// lamas 0.000492% -> 16.827817% (1561544)
-// kraal 0.000492% -> 17.389054% (1561963)
-// gents 0.000493% -> 25.448008% (1567081)
-// hails 0.000494% -> 29.575062% (1569275)
-// atria 0.000494% -> 30.602258% (1569792)
-// wooed 0.000495% -> 32.304510% (1570628)
-// kinks 0.000495% -> 32.904357% (1570917)
-// sushi 0.000495% -> 34.261053% (1571561)
-// filly 0.000496% -> 39.634216% (1574006)
-// lazar 0.000496% -> 43.073524% (1575508)
-// lapel 0.000497% -> 48.215265% (1577704)
-// cecum 0.000497% -> 48.505414% (1577827)
-// kayak 0.000497% -> 49.307915% (1578167)
-// fundy 0.000498% -> 55.755065% (1580908)
-// haply 0.000498% -> 56.508662% (1581232)
-// bigot 0.000498% -> 57.402526% (1581618)
-// wisps 0.000498% -> 57.407146% (1581620)
-// foals 0.000498% -> 57.875326% (1581823)
-fn sigmoid(p: f64) -> f64 {
-    L / (1.0 + (-K * (p - X0)).exp())
-}
-const PRINT_SIGMOID: bool = false;
-
 impl Sigmoid {
     pub fn new() -> Self {
         Self {
-            remaining: Cow::Borrowed(INITIAL.get_or_init(|| {
-                let sum: usize = DICTIONARY.iter().map(|(_, count)| count).sum();
+            remaining: Cow::Borrowed(weighted()),
+            tiebreak: Arc::new(Frequency),
+            lambda: 1.0,
+            budget: None,
+            precision: Precision::F64,
+            explanations: None,
+        }
+    }
 
-                if PRINT_SIGMOID {
-                    for (word, count) in DICTIONARY.iter().rev() {
-                        let p = *count as f64 / sum as f64;
-                        println!(
-                            "{} {:.6}% -> {:.6}% ({})",
-                            word,
-                            100.0 * p,
-                            100.0 * sigmoid(p),
-                            count
-                        );
-                    }
-                }
+    /// How much to favour guesses that are likely to be the answer over ones that give more
+    /// information: the chance of being the answer is raised to the power `lambda` before it is
+    /// multiplied with the information. 0 ignores it entirely, and the default is 1.
+    pub fn with_lambda(mut self, lambda: f64) -> Self {
+        self.lambda = lambda;
+        self
+    }
 
-                DICTIONARY
-                    .iter()
-                    .copied()
-                    .map(|(word, count)| (word, sigmoid(count as f64 / sum as f64)))
-                    .collect()
-            })),
-            patterns: Cow::Borrowed(PATTERNS.get_or_init(|| Correctness::patterns().collect())),
-        }
+    /// Limits how long a whole game may take, by considering fewer candidates for a guess once
+    /// its share of the time is used up. This makes the guesses (slightly) worse, and no longer
+    /// the same from one run to the next.
+    pub fn with_time_budget(mut self, per_game: Duration) -> Self {
+        self.budget = Some(TimeBudget::new(per_game));
+        self
+    }
+
+    /// Works out how likely each feedback is in `precision`, which is faster in single precision
+    /// on large candidate sets, but may pick a different guess when two are about as good.
+    pub fn with_precision(mut self, precision: Precision) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Hands `report` the `top` guesses it scored best every turn, along with what they scored.
+    /// It doesn't estimate how many guesses are left, so there is no expected score for them.
+    pub fn with_explanations(
+        mut self,
+        top: usize,
+        report: impl Fn(&GuessExplanation) + Send + Sync + 'static,
+    ) -> Self {
+        self.explanations = Some(Explanations::new(top, report));
+        self
     }
 }
 
-#[derive(Debug, Copy, Clone)]
-struct Candidate {
-    word: &'static str,
-    goodness: f64,
+impl BreaksTies for Sigmoid {
+    fn tiebreak_mut(&mut self) -> &mut Arc<dyn TieBreak> {
+        &mut self.tiebreak
+    }
 }
 
 impl Guesser for Sigmoid {
-    fn guess(&mut self, history: &[Guess]) -> String {
+    fn guess(&mut self, history: &[Guess]) -> Cow<'static, str> {
+        let start = Instant::now();
         if let Some(last) = history.last() {
             if matches!(self.remaining, Cow::Owned(_)) {
                 self.remaining
@@ -103,15 +99,20 @@ impl Guesser for Sigmoid {
             }
         }
         if history.is_empty() {
-            self.patterns = Cow::Borrowed(PATTERNS.get().unwrap());
-            return "tares".to_string();
-        } else {
-            assert!(!self.patterns.is_empty());
+            if let Some(opener) = first_guess() {
+                if let Some(explanations) = &self.explanations {
+                    explanations.report_opener(opener);
+                }
+                return Cow::Borrowed(opener);
+            }
         }
 
-        let remaining_p: f64 = self.remaining.iter().map(|&(_, p)| p).sum();
+        let remaining_p = summation::sum(self.remaining.iter().map(|&(_, p)| p));
 
-        let mut best: Option<Candidate> = None;
+        let mut feedback = Feedback::new(self.remaining.iter().map(|&(word, _)| word).collect());
+        let deadline = self.budget.map(|b| b.deadline(start, history.len()));
+        let mut best = Best::new(&*self.tiebreak);
+        let mut top = self.explanations.as_ref().map(Explanations::shortlist);
         let mut i = 0;
         let stop = (self.remaining.len() / 3).max(20);
         for &(word, count) in &*self.remaining {
@@ -122,42 +123,64 @@ impl Guesser for Sigmoid {
             // that result in that pattern, we can instead keep a running total for each pattern
             // simultaneously by storing them in an array. We can do this since each candidate-word
             // pair deterministically produces only one mask.
-            let mut totals = [0.0f64; MAX_MASK_ENUM];
-            for (candidate, count) in &*self.remaining {
-                let idx = Correctness::pack(&Correctness::compute(candidate, word));
-                totals[usize::from(idx)] += count;
-            }
-
-            let sum: f64 = totals
-                .into_iter()
-                .filter(|t| *t != 0.0)
-                .map(|p| {
-                    let p_of_this_pattern = p as f64 / remaining_p as f64;
-                    p_of_this_pattern * p_of_this_pattern.log2()
-                })
-                .sum();
+            let entropy = match self.precision {
+                Precision::F64 => {
+                    let mut totals = vec![0.0f64; Correctness::count(letter_count(word))];
+                    for (idx, (_, count)) in feedback.packed(word).zip(&*self.remaining) {
+                        totals[idx] += count;
+                    }
+                    -summation::sum(totals.into_iter().filter(|t| *t != 0.0).map(|p| {
+                        let p_of_this_pattern = p / remaining_p;
+                        p_of_this_pattern * p_of_this_pattern.log2()
+                    }))
+                }
+                Precision::F32 => {
+                    let mut totals = vec![0.0f32; Correctness::count(letter_count(word))];
+                    for (idx, (_, count)) in feedback.packed(word).zip(&*self.remaining) {
+                        totals[idx] += *count as f32;
+                    }
+                    information_f32(&totals, remaining_p)
+                }
+            };
 
-            let p_word = count as f64 / remaining_p as f64;
-            let entropy = -sum;
+            let p_word = count / remaining_p;
             // TODO: this should be (minimizing):
             // (p_word * (history.len() + 1)) + ((1 - p_word) * estimate_remaining_guesses(remaining_entropy))
             // where remaining_entropy is the existing entropy - entropy
             // and restimate_remaining_guesses is computed by regression over historical data
-            let goodness = p_word * entropy;
-            if let Some(c) = best {
-                // Is this one better?
-                if goodness > c.goodness {
-                    best = Some(Candidate { word, goodness });
-                }
-            } else {
-                best = Some(Candidate { word, goodness });
+            let goodness = p_word.powf(self.lambda) * entropy;
+            best.consider(word, goodness);
+            if let Some(top) = &mut top {
+                top.consider(
+                    goodness,
+                    ScoredGuess {
+                        word,
+                        information: entropy,
+                        probability: p_word,
+                        expected_score: None,
+                    },
+                );
             }
 
             i += 1;
-            if i >= stop {
+            if i >= stop || out_of_time(deadline) {
                 break;
             }
         }
-        best.unwrap().word.to_string()
+        if let Some(budget) = &mut self.budget {
+            budget.spend(start);
+        }
+        let guess = best.word().unwrap();
+        if let (Some(explanations), Some(top)) = (&self.explanations, top) {
+            explanations.report(history.len() + 1, self.remaining.len(), guess, top);
+        }
+        Cow::Borrowed(guess)
+    }
+
+    fn reset(&mut self) {
+        self.remaining = Cow::Borrowed(weighted());
+        if let Some(budget) = &mut self.budget {
+            budget.reset();
+        }
     }
 }
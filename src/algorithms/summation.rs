@@ -0,0 +1,115 @@
+//! How the guessers add up the probabilities and entropy terms of the candidates.
+//!
+//! Adding up thousands of floating point terms one after another loses a little precision with
+//! every addition, and how much depends on the order of the terms. That is usually harmless, but
+//! when two guesses are within a rounding error of each other, it decides which one is picked, so
+//! a change that only reorders the candidates (or a platform that rounds differently) can change
+//! the guesses. Compensated summation keeps track of what each addition lost, so that the totals
+//! come out the same (to within the last bit or so) whatever the order.
+
+use once_cell::sync::OnceCell;
+use std::fmt;
+use std::str::FromStr;
+
+/// How sums of floating point terms are worked out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Summation {
+    /// Adds each term to a running total, which is fastest.
+    #[default]
+    Naive,
+    /// Adds each term to a running total while keeping track of the rounding error (Neumaier's
+    /// variant of Kahan summation), so that the result hardly depends on the order of the terms.
+    Compensated,
+}
+
+impl FromStr for Summation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "naive" => Ok(Self::Naive),
+            "compensated" => Ok(Self::Compensated),
+            _ => Err(format!(
+                "unknown summation '{}', expected naive or compensated",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Summation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Naive => "naive",
+            Self::Compensated => "compensated",
+        })
+    }
+}
+
+static SUMMATION: OnceCell<Summation> = OnceCell::new();
+
+/// Picks how the guessers add up their terms from now on. Fails if a guesser has already used a
+/// different way.
+pub fn set_summation(summation: Summation) -> Result<(), String> {
+    match SUMMATION.try_insert(summation) {
+        Ok(_) => Ok(()),
+        Err((current, summation)) if *current == summation => Ok(()),
+        Err(_) => Err("a different summation is already in use".to_string()),
+    }
+}
+
+/// How the guessers add up their terms, which is [`Summation::Naive`] unless another way was
+/// picked before any guesser used it.
+pub fn summation() -> Summation {
+    *SUMMATION.get_or_init(Summation::default)
+}
+
+/// Adds up `terms` in the way [in use](summation).
+pub(crate) fn sum(terms: impl IntoIterator<Item = f64>) -> f64 {
+    sum_with(summation(), terms)
+}
+
+fn sum_with(summation: Summation, terms: impl IntoIterator<Item = f64>) -> f64 {
+    match summation {
+        Summation::Naive => terms.into_iter().sum(),
+        Summation::Compensated => {
+            let mut total = 0.0f64;
+            let mut lost = 0.0;
+            for term in terms {
+                let t = total + term;
+                // Whichever of the two is smaller in magnitude is the one whose low bits were lost.
+                lost += if total.abs() >= term.abs() {
+                    (total - t) + term
+                } else {
+                    (term - t) + total
+                };
+                total = t;
+            }
+            total + lost
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sum_with, Summation};
+
+    #[test]
+    fn compensated_is_independent_of_order() {
+        let terms = [1.0, 1e100, 1.0, -1e100];
+        assert_eq!(sum_with(Summation::Naive, terms), 0.0);
+        assert_eq!(sum_with(Summation::Compensated, terms), 2.0);
+
+        let tenths = vec![0.1; 10];
+        assert_ne!(sum_with(Summation::Naive, tenths.iter().copied()), 1.0);
+        assert_eq!(sum_with(Summation::Compensated, tenths), 1.0);
+    }
+
+    #[test]
+    fn parse() {
+        for summation in [Summation::Naive, Summation::Compensated] {
+            assert_eq!(summation.to_string().parse(), Ok(summation));
+        }
+        assert!("pairwise".parse::<Summation>().is_err());
+    }
+}
@@ -0,0 +1,171 @@
+//! Counts of what the guessers did to come up with their guesses, added up over a whole run, to
+//! tell where their time goes and what is worth optimising next.
+//!
+//! Only the guessers that [search](super::search) the candidates (cutoff, cache and strategies)
+//! count what they scored, and only the cache implementation has a cache to count lookups in.
+//! Nothing is added up unless [`enable_telemetry`] was called first, and each guess adds its
+//! counts once, when it is done, so that parallel games don't fight over the counters.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static SEARCHES: AtomicU64 = AtomicU64::new(0);
+static TRUNCATED: AtomicU64 = AtomicU64::new(0);
+static SCORED: AtomicU64 = AtomicU64::new(0);
+static BUCKETS: AtomicU64 = AtomicU64::new(0);
+static LOOKUPS: AtomicU64 = AtomicU64::new(0);
+static HITS: AtomicU64 = AtomicU64::new(0);
+
+/// Starts adding up the [`Telemetry`] of every guess made from now on.
+pub fn enable_telemetry() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub(crate) fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// What has been added up since [`enable_telemetry`] was called.
+pub fn telemetry() -> Telemetry {
+    let get = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+    Telemetry {
+        searches: get(&SEARCHES),
+        truncated: get(&TRUNCATED),
+        scored: get(&SCORED),
+        buckets: get(&BUCKETS),
+        lookups: get(&LOOKUPS),
+        hits: get(&HITS),
+    }
+}
+
+/// Counts of what the guessers did over a run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Telemetry {
+    /// How many guesses were searched for (which leaves out openers, which are known up front).
+    pub searches: u64,
+    /// How many of those searches were cut short before scoring every candidate.
+    pub truncated: u64,
+    /// How many candidates were scored as guesses, over all the searches.
+    pub scored: u64,
+    /// How many different feedbacks the candidates scored could get, added up (only for the
+    /// guessers that score them by entropy).
+    pub buckets: u64,
+    /// How many masks were looked up in the cache.
+    pub lookups: u64,
+    /// How many of those had already been worked out.
+    pub hits: u64,
+}
+
+impl Telemetry {
+    /// The share of the lookups in the cache that found the mask already worked out.
+    pub fn hit_rate(&self) -> Option<f64> {
+        ratio(self.hits, self.lookups)
+    }
+
+    /// How many candidates were scored for each guess searched for.
+    pub fn scored_per_search(&self) -> Option<f64> {
+        ratio(self.scored, self.searches)
+    }
+
+    /// How many different feedbacks each candidate scored could get, on average.
+    pub fn buckets_per_scored(&self) -> Option<f64> {
+        ratio(self.buckets, self.scored)
+    }
+
+    /// The share of the searches that were cut short.
+    pub fn truncation_rate(&self) -> Option<f64> {
+        ratio(self.truncated, self.searches)
+    }
+}
+
+fn ratio(n: u64, d: u64) -> Option<f64> {
+    (d != 0).then(|| n as f64 / d as f64)
+}
+
+impl fmt::Display for Telemetry {
+    /// A few lines summing the counts up, leaving out the ones nothing was counted for.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(rate) = self.hit_rate() {
+            writeln!(
+                f,
+                "cache hit rate {:.2}% of {} lookups",
+                rate * 100.0,
+                self.lookups
+            )?;
+        }
+        match (self.scored_per_search(), self.buckets_per_scored()) {
+            (None, _) => writeln!(f, "no guesses were searched for")?,
+            // Only scoring by entropy counts feedbacks.
+            (Some(scored), Some(buckets)) if self.buckets != 0 => writeln!(
+                f,
+                "{:.1} candidates scored per guess, with {:.1} feedbacks each",
+                scored, buckets
+            )?,
+            (Some(scored), _) => writeln!(f, "{:.1} candidates scored per guess", scored)?,
+        }
+        if let Some(rate) = self.truncation_rate() {
+            writeln!(
+                f,
+                "{:.2}% of {} searches cut short",
+                rate * 100.0,
+                self.searches
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The lookups one guess made in the cache.
+#[derive(Debug, Default)]
+pub(crate) struct Lookups {
+    pub(crate) lookups: u64,
+    pub(crate) hits: u64,
+}
+
+impl Lookups {
+    /// Adds these lookups to the run's, if it is being counted.
+    pub(crate) fn record(self) {
+        if enabled() {
+            LOOKUPS.fetch_add(self.lookups, Ordering::Relaxed);
+            HITS.fetch_add(self.hits, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Adds one search to the run's, if it is being counted.
+pub(crate) fn record_search(scored: u64, buckets: u64, truncated: bool) {
+    if enabled() {
+        SEARCHES.fetch_add(1, Ordering::Relaxed);
+        TRUNCATED.fetch_add(u64::from(truncated), Ordering::Relaxed);
+        SCORED.fetch_add(scored, Ordering::Relaxed);
+        BUCKETS.fetch_add(buckets, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Telemetry;
+
+    #[test]
+    fn summary() {
+        let telemetry = Telemetry {
+            searches: 4,
+            truncated: 1,
+            scored: 100,
+            buckets: 2500,
+            lookups: 1000,
+            hits: 900,
+        };
+        assert_eq!(
+            telemetry.to_string(),
+            "cache hit rate 90.00% of 1000 lookups\n\
+             25.0 candidates scored per guess, with 25.0 feedbacks each\n\
+             25.00% of 4 searches cut short\n"
+        );
+        assert_eq!(
+            Telemetry::default().to_string(),
+            "no guesses were searched for\n"
+        );
+    }
+}
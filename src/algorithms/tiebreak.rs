@@ -0,0 +1,207 @@
+use super::words::WordIndex;
+use crate::splitmix64;
+use std::sync::Arc;
+
+/// How close two scores have to be for the guesses to be considered equally good.
+const EPSILON: f64 = 1e-9;
+
+/// Decides between two guesses that the scoring algorithms consider equally good.
+///
+/// Without this, ties would be won by whichever word the algorithm happened to look at first (or
+/// last), which isn't even stable for the algorithms that keep their candidates in a `HashMap`.
+pub trait TieBreak: Send + Sync {
+    /// Whether `challenger` should be guessed rather than `incumbent`.
+    fn prefer(&self, challenger: &str, incumbent: &str) -> bool;
+}
+
+/// Prefer the more common word, since it is more likely to be the answer.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Frequency;
+
+impl TieBreak for Frequency {
+    fn prefer(&self, challenger: &str, incumbent: &str) -> bool {
+        let rank = |word| WordIndex::of(word).map_or(usize::MAX, usize::from);
+        rank(challenger) < rank(incumbent)
+    }
+}
+
+/// Prefer the word that comes first in the alphabet.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Alphabetical;
+
+impl TieBreak for Alphabetical {
+    fn prefer(&self, challenger: &str, incumbent: &str) -> bool {
+        challenger < incumbent
+    }
+}
+
+/// Prefer the word with the most distinct letters, since it tests more of the alphabet, falling
+/// back to [`Frequency`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FewestRepeats;
+
+impl TieBreak for FewestRepeats {
+    fn prefer(&self, challenger: &str, incumbent: &str) -> bool {
+        let distinct = |word: &str| {
+            let mut letters: Vec<char> = word.chars().collect();
+            letters.sort_unstable();
+            letters.dedup();
+            letters.len()
+        };
+        match distinct(challenger).cmp(&distinct(incumbent)) {
+            std::cmp::Ordering::Equal => Frequency.prefer(challenger, incumbent),
+            ordering => ordering.is_gt(),
+        }
+    }
+}
+
+/// Break ties uniformly at random, but reproducibly for a given seed.
+#[derive(Debug, Clone, Copy)]
+pub struct Random {
+    seed: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    fn hash(&self, word: &str) -> u64 {
+        let mut state = self.seed;
+        for b in word.bytes() {
+            state ^= u64::from(b);
+            splitmix64(&mut state);
+        }
+        splitmix64(&mut state)
+    }
+}
+
+impl TieBreak for Random {
+    fn prefer(&self, challenger: &str, incumbent: &str) -> bool {
+        // Ordering by a seeded hash rather than flipping a coin for every comparison makes every
+        // tied word equally likely to win, no matter the order they are considered in.
+        self.hash(challenger) < self.hash(incumbent)
+    }
+}
+
+/// A guesser that chooses between equally good guesses with a [`TieBreak`], which can be swapped
+/// for another one.
+pub trait BreaksTies: Sized {
+    /// The tiebreak the guesser uses.
+    fn tiebreak_mut(&mut self) -> &mut Arc<dyn TieBreak>;
+
+    /// Uses `tiebreak` to choose between guesses that are equally good.
+    fn with_tiebreak(mut self, tiebreak: impl TieBreak + 'static) -> Self {
+        *self.tiebreak_mut() = Arc::new(tiebreak);
+        self
+    }
+}
+
+/// Keeps track of the best guess seen so far.
+pub(crate) struct Best<'a> {
+    tiebreak: &'a dyn TieBreak,
+    best: Option<(&'static str, f64)>,
+}
+
+impl<'a> Best<'a> {
+    pub(crate) fn new(tiebreak: &'a dyn TieBreak) -> Self {
+        Self {
+            tiebreak,
+            best: None,
+        }
+    }
+
+    /// Considers guessing `word`, which scores `goodness` (higher is better).
+    pub(crate) fn consider(&mut self, word: &'static str, goodness: f64) {
+        let better = match self.best {
+            None => true,
+            Some((_, best)) if goodness > best + EPSILON => true,
+            Some((incumbent, best)) if goodness > best - EPSILON => {
+                self.tiebreak.prefer(word, incumbent)
+            }
+            Some(_) => false,
+        };
+        if better {
+            self.best = Some((word, goodness));
+        }
+    }
+
+    pub(crate) fn word(&self) -> Option<&'static str> {
+        self.best.map(|(word, _)| word)
+    }
+
+    /// The best guess so far, along with its goodness.
+    pub(crate) fn best(&self) -> Option<(&'static str, f64)> {
+        self.best
+    }
+
+    /// Whether the best guess so far is better than one that scores `goodness`, by more than a
+    /// tie.
+    pub(crate) fn beats(&self, goodness: f64) -> bool {
+        matches!(self.best, Some((_, best)) if best > goodness + EPSILON)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Alphabetical, Best, BreaksTies, FewestRepeats, Frequency, Random, TieBreak};
+
+    #[test]
+    fn frequency() {
+        assert!(Frequency.prefer("which", "tares"));
+        assert!(!Frequency.prefer("tares", "which"));
+    }
+
+    #[test]
+    fn alphabetical() {
+        assert!(Alphabetical.prefer("abbey", "tares"));
+        assert!(!Alphabetical.prefer("tares", "abbey"));
+    }
+
+    #[test]
+    fn fewest_repeats() {
+        assert!(FewestRepeats.prefer("tares", "sissy"));
+        assert!(!FewestRepeats.prefer("sissy", "tares"));
+        // Same number of distinct letters, so the more common word wins.
+        assert!(FewestRepeats.prefer("which", "there"));
+    }
+
+    #[test]
+    fn random_is_reproducible() {
+        let words = ["tares", "which", "sissy", "abbey", "crane"];
+        let winner = |seed| {
+            let random = Random::new(seed);
+            let mut best = Best::new(&random);
+            for word in words {
+                best.consider(word, 1.0);
+            }
+            best.word().unwrap()
+        };
+        assert_eq!(winner(1), winner(1));
+        let winners: std::collections::HashSet<_> = (0..32).map(winner).collect();
+        assert!(winners.len() > 1);
+    }
+
+    #[test]
+    fn best_prefers_higher_goodness() {
+        let mut best = Best::new(&Alphabetical);
+        best.consider("zesty", 2.0);
+        best.consider("abbey", 1.0);
+        assert_eq!(best.word(), Some("zesty"));
+    }
+
+    #[test]
+    fn best_breaks_near_ties() {
+        let mut best = Best::new(&Alphabetical);
+        best.consider("zesty", 1.0 + 1e-12);
+        best.consider("abbey", 1.0);
+        assert_eq!(best.word(), Some("abbey"));
+    }
+
+    #[test]
+    fn algorithms_use_it() {
+        let w = crate::Wordle::new();
+        let guesser = crate::algorithms::Escore::new().with_tiebreak(Random::new(7));
+        assert!(w.play("cigar", guesser).is_some());
+    }
+}
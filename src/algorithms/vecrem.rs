@@ -1,8 +1,13 @@
-use crate::{Correctness, Guess, Guesser, DICTIONARY};
+use super::first_guess;
+use super::tiebreak::{Best, BreaksTies, Frequency, TieBreak};
+use crate::{candidate_answers, word_len, Correctness, Guess, Guesser};
 use std::borrow::Cow;
+use std::sync::Arc;
 
+#[derive(Clone)]
 pub struct Vecrem {
     remaining: Vec<(&'static str, usize)>,
+    tiebreak: Arc<dyn TieBreak>,
 }
 
 impl Default for Vecrem {
@@ -14,32 +19,33 @@ impl Default for Vecrem {
 impl Vecrem {
     pub fn new() -> Self {
         Self {
-            remaining: DICTIONARY.to_vec(),
+            remaining: candidate_answers().to_vec(),
+            tiebreak: Arc::new(Frequency),
         }
     }
 }
 
-#[derive(Debug, Copy, Clone)]
-struct Candidate {
-    word: &'static str,
-    goodness: f64,
+impl BreaksTies for Vecrem {
+    fn tiebreak_mut(&mut self) -> &mut Arc<dyn TieBreak> {
+        &mut self.tiebreak
+    }
 }
 
 impl Guesser for Vecrem {
-    fn guess(&mut self, history: &[Guess]) -> String {
+    fn guess(&mut self, history: &[Guess]) -> Cow<'static, str> {
         if let Some(last) = history.last() {
             self.remaining.retain(|(word, _)| last.matches(word));
         }
-        if history.is_empty() {
-            return "tares".to_string();
+        if let Some(opener) = first_guess().filter(|_| history.is_empty()) {
+            return Cow::Borrowed(opener);
         }
 
         let remaining_count: usize = self.remaining.iter().map(|&(_, c)| c).sum();
 
-        let mut best: Option<Candidate> = None;
+        let mut best = Best::new(&*self.tiebreak);
         for &(word, _) in &self.remaining {
             let mut sum = 0.0;
-            for pattern in Correctness::patterns() {
+            for &pattern in Correctness::possible_patterns(word_len()) {
                 // considering a world where we _did_ guess `word` and got `pattern` as the
                 // correctness. now, compute what _then_ is left.
                 let mut in_pattern_total = 0;
@@ -60,15 +66,13 @@ impl Guesser for Vecrem {
                 sum += p_of_this_pattern * p_of_this_pattern.log2();
             }
             let goodness = -sum;
-            if let Some(c) = best {
-                // Is this one better?
-                if goodness > c.goodness {
-                    best = Some(Candidate { word, goodness });
-                }
-            } else {
-                best = Some(Candidate { word, goodness });
-            }
+            best.consider(word, goodness);
         }
-        best.unwrap().word.to_string()
+        Cow::Borrowed(best.word().unwrap())
+    }
+
+    fn reset(&mut self) {
+        self.remaining.clear();
+        self.remaining.extend_from_slice(candidate_answers());
     }
 }
@@ -1,11 +1,13 @@
-use crate::{Correctness, Guess, Guesser, DICTIONARY};
-use once_cell::sync::OnceCell;
+use super::first_guess;
+use super::tiebreak::{Best, BreaksTies, Frequency, TieBreak};
+use crate::{candidate_answers, word_len, Correctness, Guess, Guesser};
 use std::borrow::Cow;
+use std::sync::Arc;
 
-static INITIAL: OnceCell<Vec<(&'static str, usize)>> = OnceCell::new();
-
+#[derive(Clone)]
 pub struct Weight {
-    remaining: Cow<'static, Vec<(&'static str, usize)>>,
+    remaining: Cow<'static, [(&'static str, usize)]>,
+    tiebreak: Arc<dyn TieBreak>,
 }
 
 impl Default for Weight {
@@ -17,19 +19,20 @@ impl Default for Weight {
 impl Weight {
     pub fn new() -> Self {
         Self {
-            remaining: Cow::Borrowed(INITIAL.get_or_init(|| DICTIONARY.to_vec())),
+            remaining: Cow::Borrowed(candidate_answers()),
+            tiebreak: Arc::new(Frequency),
         }
     }
 }
 
-#[derive(Debug, Copy, Clone)]
-struct Candidate {
-    word: &'static str,
-    goodness: f64,
+impl BreaksTies for Weight {
+    fn tiebreak_mut(&mut self) -> &mut Arc<dyn TieBreak> {
+        &mut self.tiebreak
+    }
 }
 
 impl Guesser for Weight {
-    fn guess(&mut self, history: &[Guess]) -> String {
+    fn guess(&mut self, history: &[Guess]) -> Cow<'static, str> {
         if let Some(last) = history.last() {
             if matches!(self.remaining, Cow::Owned(_)) {
                 self.remaining
@@ -45,17 +48,17 @@ impl Guesser for Weight {
                 );
             }
         }
-        if history.is_empty() {
-            return "tares".to_string();
+        if let Some(opener) = first_guess().filter(|_| history.is_empty()) {
+            return Cow::Borrowed(opener);
         }
 
         let remaining_count: usize = self.remaining.iter().map(|&(_, c)| c).sum();
 
-        let mut best: Option<Candidate> = None;
+        let mut best = Best::new(&*self.tiebreak);
         for &(word, count) in &*self.remaining {
             let mut sum = 0.0;
             let mut self_total_count = 0usize;
-            for pattern in Correctness::patterns() {
+            for &pattern in Correctness::possible_patterns(word_len()) {
                 // considering a world where we _did_ guess `word` and got `pattern` as the
                 // correctness. now, compute what _then_ is left.
                 let mut in_pattern_total = 0;
@@ -82,15 +85,12 @@ impl Guesser for Weight {
 
             let p_word = count as f64 / remaining_count as f64;
             let goodness = p_word * -sum;
-            if let Some(c) = best {
-                // Is this one better?
-                if goodness > c.goodness {
-                    best = Some(Candidate { word, goodness });
-                }
-            } else {
-                best = Some(Candidate { word, goodness });
-            }
+            best.consider(word, goodness);
         }
-        best.unwrap().word.to_string()
+        Cow::Borrowed(best.word().unwrap())
+    }
+
+    fn reset(&mut self) {
+        self.remaining = Cow::Borrowed(candidate_answers());
     }
 }
@@ -0,0 +1,207 @@
+//! The dictionary in the forms the guessers start from, worked out once and shared by all of them.
+
+use crate::profile::profile;
+use crate::{candidate_answers, dictionary};
+use once_cell::sync::OnceCell;
+use std::collections::{HashMap, HashSet};
+
+const L: f64 = 1.0;
+// How steep is the cut-off?
+const K: f64 = 30000000.0;
+// Where is the cut-off?
+const X0: f64 = 0.00000497;
+// This combination of settings leads to a fairly sharp cut-off around:
+//
+//  word  "raw" p      sigmoid p   count
+// vying 0.000491% -> 15.999853% (1560905)
+// rheum 0.000492% -> 16.735476% (1561474)
+// lamas 0.000492% -> 16.827817% (1561544)
+// kraal 0.000492% -> 17.389054% (1561963)
+// gents 0.000493% -> 25.448008% (1567081)
+// hails 0.000494% -> 29.575062% (1569275)
+// atria 0.000494% -> 30.602258% (1569792)
+// wooed 0.000495% -> 32.304510% (1570628)
+// kinks 0.000495% -> 32.904357% (1570917)
+// sushi 0.000495% -> 34.261053% (1571561)
+// filly 0.000496% -> 39.634216% (1574006)
+// lazar 0.000496% -> 43.073524% (1575508)
+// lapel 0.000497% -> 48.215265% (1577704)
+// cecum 0.000497% -> 48.505414% (1577827)
+// kayak 0.000497% -> 49.307915% (1578167)
+// fundy 0.000498% -> 55.755065% (1580908)
+// haply 0.000498% -> 56.508662% (1581232)
+// bigot 0.000498% -> 57.402526% (1581618)
+// wisps 0.000498% -> 57.407146% (1581620)
+// foals 0.000498% -> 57.875326% (1581823)
+pub(super) fn sigmoid(p: f64) -> f64 {
+    L / (1.0 + (-K * (p - X0)).exp())
+}
+const PRINT_SIGMOID: bool = false;
+
+/// Every word that can be the answer along with its chance of being it, as estimated by
+/// [`sigmoid`] from how common it is, in the same order as the dictionary.
+///
+/// The chances are relative to the whole dictionary, so they are the same whether or not the
+/// profile only allows some of its words as answers.
+pub(super) fn weighted() -> &'static [(&'static str, f64)] {
+    static WEIGHTED: OnceCell<Vec<(&'static str, f64)>> = OnceCell::new();
+    WEIGHTED.get_or_init(|| {
+        let sum: usize = dictionary().iter().map(|(_, count)| count).sum();
+
+        if PRINT_SIGMOID {
+            for &(word, count) in dictionary().iter().rev() {
+                let p = count as f64 / sum as f64;
+                println!(
+                    "{} {:.6}% -> {:.6}% ({})",
+                    word,
+                    100.0 * p,
+                    100.0 * sigmoid(p),
+                    count
+                );
+            }
+        }
+
+        candidate_answers()
+            .iter()
+            .map(|&(word, count)| (word, sigmoid(count as f64 / sum as f64)))
+            .collect()
+    })
+}
+
+/// Every word in the dictionary that can't be the answer, but can still be guessed to narrow
+/// down the ones that can. There are none unless the profile only allows some of them as answers.
+pub(super) fn guesses_only() -> &'static [&'static str] {
+    static GUESSES: OnceCell<Vec<&'static str>> = OnceCell::new();
+    GUESSES.get_or_init(|| {
+        if !profile().answers_only {
+            return Vec::new();
+        }
+        let candidates: HashSet<_> = candidate_answers().iter().map(|&(word, _)| word).collect();
+        dictionary()
+            .iter()
+            .map(|&(word, _)| word)
+            .filter(|word| !candidates.contains(word))
+            .collect()
+    })
+}
+
+/// Where a word is in the dictionary in use, which stands in for the word wherever the guessers
+/// only need to tell words apart or look up what was worked out for them, since comparing and
+/// copying it is cheaper than the word. The dictionary is sorted by frequency, so a lower index
+/// means a more common word.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WordIndex(u16);
+
+impl WordIndex {
+    /// The most words a dictionary can have for all of them to have an index.
+    pub const MAX_WORDS: usize = u16::MAX as usize + 1;
+
+    /// Where `word` is in the dictionary in use, if it is in it.
+    pub fn of(word: &str) -> Option<Self> {
+        words().index(word)
+    }
+
+    /// The word at this index in the dictionary in use.
+    ///
+    /// # Panics
+    ///
+    /// If the dictionary doesn't have that many words.
+    pub fn word(self) -> &'static str {
+        words().word(self)
+    }
+}
+
+impl From<WordIndex> for usize {
+    fn from(idx: WordIndex) -> usize {
+        usize::from(idx.0)
+    }
+}
+
+/// A list of words along with where each one is in it, to go between words and their indices
+/// either way.
+pub(crate) struct Words {
+    words: Vec<&'static str>,
+    indices: HashMap<&'static str, WordIndex>,
+}
+
+impl Words {
+    /// # Panics
+    ///
+    /// If there are more than [`WordIndex::MAX_WORDS`] words.
+    pub(crate) fn new(words: impl IntoIterator<Item = &'static str>) -> Self {
+        let words: Vec<_> = words.into_iter().collect();
+        assert!(
+            words.len() <= WordIndex::MAX_WORDS,
+            "only {} words can be indexed",
+            WordIndex::MAX_WORDS
+        );
+        let indices = words
+            .iter()
+            .enumerate()
+            .map(|(idx, &word)| (word, WordIndex(idx as u16)))
+            .collect();
+        Self { words, indices }
+    }
+
+    pub(crate) fn index(&self, word: &str) -> Option<WordIndex> {
+        self.indices.get(word).copied()
+    }
+
+    #[inline]
+    pub(crate) fn word(&self, idx: WordIndex) -> &'static str {
+        self.words[usize::from(idx)]
+    }
+}
+
+/// Every word in the dictionary in use, indexed.
+pub(crate) fn words() -> &'static Words {
+    static WORDS: OnceCell<Words> = OnceCell::new();
+    WORDS.get_or_init(|| Words::new(dictionary().iter().map(|&(word, _)| word)))
+}
+
+/// [`weighted`], with each word given by its index in the dictionary.
+pub(super) fn weighted_indexed() -> &'static [(WordIndex, f64)] {
+    static INDEXED: OnceCell<Vec<(WordIndex, f64)>> = OnceCell::new();
+    INDEXED.get_or_init(|| {
+        weighted()
+            .iter()
+            .map(|&(word, p)| {
+                let idx = WordIndex::of(word).expect("answers are in the dictionary");
+                (idx, p)
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{guesses_only, weighted, weighted_indexed, WordIndex, Words};
+    use crate::dictionary;
+
+    #[test]
+    fn same_order_as_dictionary() {
+        for (idx, (&(word, _), &(weighted_idx, p))) in
+            dictionary().iter().zip(weighted_indexed()).enumerate()
+        {
+            assert_eq!(weighted_idx.word(), word);
+            assert_eq!(weighted()[idx], (word, p));
+            assert_eq!(usize::from(weighted_idx), idx);
+            assert_eq!(WordIndex::of(word), Some(weighted_idx));
+        }
+        // More common words are likelier answers.
+        assert!(weighted().windows(2).all(|w| w[0].1 >= w[1].1));
+        assert_eq!(WordIndex::of("zzzzz"), None);
+        // Every word can be the answer unless the profile says otherwise.
+        assert!(guesses_only().is_empty());
+    }
+
+    #[test]
+    fn indexes_both_ways() {
+        let words = Words::new(["cigar", "rebut", "sissy"]);
+        let idx = words.index("rebut").unwrap();
+        assert_eq!(usize::from(idx), 1);
+        assert_eq!(words.word(idx), "rebut");
+        assert_eq!(words.index("humph"), None);
+        assert!(words.index("cigar").unwrap() < idx);
+    }
+}
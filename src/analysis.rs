@@ -0,0 +1,661 @@
+//! Numbers about guesses that don't depend on any one guesser's strategy.
+
+use crate::{
+    algorithms::{est_steps_left, kernel::Feedback, summation},
+    dictionary,
+    json::json_string,
+    letter_count,
+    profile::{profile, Duplicates},
+    repeats_letter, Correctness, Guess, Mask,
+};
+use once_cell::sync::OnceCell;
+use std::collections::HashSet;
+
+// The best openers over the official dictionary, as worked out by `build.rs`.
+include!(concat!(env!("OUT_DIR"), "/openers.rs"));
+
+/// How good a guess is as an opener, when every candidate is equally likely to be the answer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenerStats {
+    pub word: &'static str,
+    /// The expected information from the feedback, in bits.
+    pub entropy: f64,
+    /// The most candidates any single feedback can leave.
+    pub worst_case: usize,
+    /// The number of candidates left on average.
+    pub expected_remaining: f64,
+}
+
+/// How many of `candidates` would give each (packed) feedback if `guess` were played.
+pub fn buckets(guess: &str, candidates: &[&str]) -> Vec<usize> {
+    let mut totals = vec![0; Correctness::count(letter_count(guess))];
+    for candidate in candidates {
+        totals[Correctness::compute_packed(candidate, guess)] += 1;
+    }
+    totals
+}
+
+/// Scores every one of `guesses` as an opener against `candidates`, best (highest entropy) first.
+pub fn opener_table(guesses: &[&'static str], candidates: &[&str]) -> Vec<OpenerStats> {
+    rank_openers(guesses, candidates, 1)
+}
+
+/// Scores every one of `guesses` as an opener against `candidates` like [`opener_table`], on
+/// `workers` threads, each taking an even share of the guesses.
+///
+/// Going through every word in a dictionary as an opener takes seconds, so this is the way to
+/// find the best openers for a dictionary given at runtime, which has no
+/// [shortlist](opener_shortlist).
+pub fn rank_openers(
+    guesses: &[&'static str],
+    candidates: &[&str],
+    workers: usize,
+) -> Vec<OpenerStats> {
+    let per_worker = guesses.len().div_ceil(workers.max(1)).max(1);
+    let mut table: Vec<_> = std::thread::scope(|s| {
+        let handles: Vec<_> = guesses
+            .chunks(per_worker)
+            .map(|guesses| {
+                s.spawn(move || {
+                    let mut feedback = Feedback::new(candidates.to_vec());
+                    guesses
+                        .iter()
+                        .map(|&word| {
+                            let mut buckets = vec![0; Correctness::count(letter_count(word))];
+                            for idx in feedback.packed(word) {
+                                buckets[idx] += 1;
+                            }
+                            opener_stats(word, buckets, candidates.len())
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("a worker panicked"))
+            .collect()
+    });
+    sort_openers(&mut table);
+    table
+}
+
+/// Scores `word` as an opener, given its [`buckets`] over `n` candidates.
+pub(crate) fn opener_stats(word: &'static str, buckets: Vec<usize>, n: usize) -> OpenerStats {
+    let n = n as f64;
+    let mut entropy = 0.0;
+    let mut expected_remaining = 0.0;
+    for &b in buckets.iter().filter(|&&b| b != 0) {
+        let p = b as f64 / n;
+        entropy -= p * p.log2();
+        expected_remaining += p * b as f64;
+    }
+    OpenerStats {
+        word,
+        entropy,
+        worst_case: buckets.into_iter().max().unwrap_or(0),
+        expected_remaining,
+    }
+}
+
+/// Sorts an [`opener_table`] best (highest entropy) first.
+pub(crate) fn sort_openers(table: &mut [OpenerStats]) {
+    table.sort_by(|a, b| b.entropy.total_cmp(&a.entropy).then(a.word.cmp(b.word)));
+}
+
+/// The 100 best openers by [entropy](OpenerStats::entropy) over the official dictionary, best
+/// first, along with their entropy. They are worked out when the solver is built, since it takes
+/// seconds even when optimised.
+///
+/// Words that the dictionary in use leaves out are left out here too (and the entropy of the rest
+/// is still the one over the whole official dictionary). A dictionary given at runtime has no
+/// shortlist, so this is empty for one. Under a [duplicate-letter rule](Duplicates) other than the
+/// official one, only the words without repeated letters are left, since the entropy of the others
+/// is different.
+pub fn opener_shortlist() -> &'static [(&'static str, f64)] {
+    static SHORTLIST: OnceCell<Vec<(&'static str, f64)>> = OnceCell::new();
+    SHORTLIST.get_or_init(|| {
+        if !profile().words.is_empty() {
+            return Vec::new();
+        }
+        let words: HashSet<_> = dictionary().iter().map(|&(word, _)| word).collect();
+        // Only guesses without repeated letters get the same feedback under every rule, so only
+        // their entropy is still right under another one.
+        let official = profile().duplicates == Duplicates::Official;
+        OPENERS
+            .iter()
+            .copied()
+            .filter(|(word, _)| words.contains(word) && (official || !repeats_letter(word)))
+            .collect()
+    })
+}
+
+/// The candidates that would give a guess the same feedback.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bucket {
+    pub mask: Mask,
+    /// The chance of getting this feedback, going by how common each candidate is.
+    pub probability: f64,
+    /// The candidates that give it, most common first.
+    pub candidates: Vec<&'static str>,
+}
+
+/// The chance that `guess` gets `mask` as feedback, if the answer is one of `candidates`, each
+/// as likely as how common it is (as in the [`dictionary`]).
+///
+/// This is the probability of one of the buckets in a [`distribution`], without working out the
+/// others, and 0 if no candidate gives that feedback (or there are no candidates).
+pub fn pattern_probability(guess: &str, mask: &Mask, candidates: &[(&str, usize)]) -> f64 {
+    let total: usize = candidates.iter().map(|&(_, count)| count).sum();
+    if total == 0 {
+        return 0.0;
+    }
+    let matching: usize = candidates
+        .iter()
+        .filter(|&&(candidate, _)| Correctness::compute(candidate, guess) == *mask)
+        .map(|&(_, count)| count)
+        .sum();
+    matching as f64 / total as f64
+}
+
+/// The words in the dictionary that the guesses in `history` still allow, along with how common
+/// each is, most common first.
+fn candidates(history: &[Guess]) -> Vec<(&'static str, usize)> {
+    dictionary()
+        .iter()
+        .filter(|(word, _)| history.iter().all(|g| g.matches(word)))
+        .copied()
+        .collect()
+}
+
+/// How the candidates left after `history` would be split up by the feedback to `guess`, from
+/// the likeliest feedback to the least likely. Feedback that no candidate gives is left out.
+pub fn distribution(history: &[Guess], guess: &str) -> Vec<Bucket> {
+    let candidates = candidates(history);
+    let total: usize = candidates.iter().map(|&(_, count)| count).sum();
+    let mut buckets: Vec<(usize, Vec<&'static str>)> =
+        vec![(0, Vec::new()); Correctness::count(letter_count(guess))];
+    for &(candidate, count) in &candidates {
+        let bucket = &mut buckets[Correctness::compute_packed(candidate, guess)];
+        bucket.0 += count;
+        bucket.1.push(candidate);
+    }
+    let mut distribution: Vec<_> = Correctness::patterns(letter_count(guess))
+        .zip(buckets)
+        .filter(|(_, (_, words))| !words.is_empty())
+        .map(|(mask, (count, candidates))| Bucket {
+            mask,
+            probability: count as f64 / total as f64,
+            candidates,
+        })
+        .collect();
+    distribution.sort_by(|a, b| b.probability.total_cmp(&a.probability));
+    distribution
+}
+
+/// Draws `buckets` (for a guess of `len` letters) as a bar chart, one line per feedback: the
+/// feedback as emoji, a bar up to `width` characters long for how likely it is, the probability
+/// and how many candidates give it. The likeliest feedback gets the full width.
+pub fn chart(buckets: &[Bucket], len: usize, width: usize) -> String {
+    let max = buckets.iter().map(|b| b.probability).fold(0.0, f64::max);
+    let mut out = String::new();
+    for bucket in buckets {
+        let pattern = Correctness::emoji_row(&bucket.mask[..len]);
+        let filled = if max > 0.0 {
+            (bucket.probability / max * width as f64).round() as usize
+        } else {
+            0
+        };
+        // Every feedback that can happen gets at least a sliver, to tell it from one that can't.
+        let filled = filled.max(usize::from(bucket.probability > 0.0));
+        out += &format!(
+            "{} {}{} {:>6.2}% {}\n",
+            pattern,
+            "#".repeat(filled),
+            " ".repeat(width.saturating_sub(filled)),
+            100.0 * bucket.probability,
+            bucket.candidates.len()
+        );
+    }
+    out
+}
+
+/// Writes `buckets` (for a guess of `len` letters) as a JSON array of objects with the
+/// `pattern` (e.g. `"GYXXY"`), its `probability` and how many `candidates` give it.
+pub fn distribution_json(buckets: &[Bucket], len: usize) -> String {
+    let buckets: Vec<_> = buckets
+        .iter()
+        .map(|b| {
+            format!(
+                "{{\"pattern\":{},\"probability\":{},\"candidates\":{}}}",
+                json_string(&Correctness::format_mask(&b.mask[..len])),
+                b.probability,
+                b.candidates.len()
+            )
+        })
+        .collect();
+    format!("[{}]", buckets.join(","))
+}
+
+/// How good a guess would be, whichever guesser is playing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GuessEvaluation {
+    /// The expected information from the feedback, in bits.
+    pub entropy: f64,
+    /// The most candidates any single feedback can leave.
+    pub worst_case: usize,
+    /// The number of candidates left on average.
+    pub expected_remaining: f64,
+    /// How many guesses (counting this one) the game is likely to take from here, going by the
+    /// same estimate of how many guesses the information left takes as the expected-score
+    /// guessers use.
+    pub expected_score: f64,
+}
+
+/// Evaluates playing `word` after `history`, going by how common each candidate is.
+///
+/// This is what [`distribution`] sums up, and what explaining a guess or comparing it with
+/// another builds on.
+pub fn evaluate_guess(history: &[Guess], word: &str) -> GuessEvaluation {
+    let candidates = candidates(history);
+    let total: usize = candidates.iter().map(|&(_, count)| count).sum();
+    let left = entropy(
+        candidates
+            .iter()
+            .map(|&(_, count)| count as f64 / total as f64),
+    );
+    let p_word = candidates
+        .iter()
+        .find(|&&(candidate, _)| candidate == word)
+        .map_or(0.0, |&(_, count)| count as f64 / total as f64);
+
+    let buckets = distribution(history, word);
+    let information = entropy(buckets.iter().map(|b| b.probability));
+    GuessEvaluation {
+        entropy: information,
+        worst_case: buckets
+            .iter()
+            .map(|b| b.candidates.len())
+            .max()
+            .unwrap_or(0),
+        expected_remaining: buckets
+            .iter()
+            .map(|b| b.probability * b.candidates.len() as f64)
+            .sum(),
+        expected_score: p_word + (1.0 - p_word) * (1.0 + est_steps_left(left - information)),
+    }
+}
+
+fn entropy(probabilities: impl Iterator<Item = f64>) -> f64 {
+    -summation::sum(probabilities.filter(|&p| p != 0.0).map(|p| p * p.log2()))
+}
+
+/// How well testing a letter would split the candidates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LetterSplit {
+    pub letter: char,
+    /// The chance that the answer has the letter, going by how common each candidate is.
+    pub probability: f64,
+    /// The information from learning whether the answer has the letter, in bits. It is highest
+    /// (1) when half the candidates have it.
+    pub entropy: f64,
+}
+
+/// The letters that none of the guesses in `history` have tested yet and that some but not all
+/// of the candidates left have, from the one that splits the candidates most evenly to the one
+/// that splits them least evenly. These are the letters worth getting into the next guess.
+pub fn informative_letters(history: &[Guess]) -> Vec<LetterSplit> {
+    let candidates = candidates(history);
+    let total: usize = candidates.iter().map(|&(_, count)| count).sum();
+    // Going by the letters the candidates have, rather than a to z, also covers alphabets with
+    // other letters in them.
+    let mut letters: Vec<char> = candidates
+        .iter()
+        .flat_map(|(word, _)| word.chars())
+        .collect();
+    letters.sort_unstable();
+    letters.dedup();
+    let mut splits: Vec<_> = letters
+        .into_iter()
+        .filter(|&letter| !history.iter().any(|g| g.word.contains(letter)))
+        .map(|letter| {
+            let containing: usize = candidates
+                .iter()
+                .filter(|(word, _)| word.contains(letter))
+                .map(|&(_, count)| count)
+                .sum();
+            let probability = containing as f64 / total as f64;
+            LetterSplit {
+                letter,
+                probability,
+                entropy: entropy([probability, 1.0 - probability].into_iter()),
+            }
+        })
+        .filter(|split| split.entropy > 0.0)
+        .collect();
+    splits.sort_by(|a, b| {
+        b.entropy
+            .total_cmp(&a.entropy)
+            .then(a.letter.cmp(&b.letter))
+    });
+    splits
+}
+
+/// A guess played to tell the candidates apart rather than to win.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Probe {
+    pub word: &'static str,
+    /// The [informative letters](informative_letters) it tests, most informative first.
+    pub letters: Vec<char>,
+}
+
+/// The allowed guess that tests as many of the [informative letters](informative_letters) after
+/// `history` as possible, such as 'forbs' to tell found, sound, round and bound apart. Between
+/// guesses that test as many, the one whose letters give the most information wins, and then the
+/// more common word.
+///
+/// This is `None` if no untested letter tells the candidates apart.
+pub fn probe_word(history: &[Guess]) -> Option<Probe> {
+    let splits = informative_letters(history);
+    let mut best: Option<(usize, f64, &'static str)> = None;
+    for &(word, _) in dictionary() {
+        let covered = splits
+            .iter()
+            .filter(|split| word.contains(split.letter))
+            .fold((0, 0.0), |(n, bits), split| (n + 1, bits + split.entropy));
+        if covered.0 > 0
+            && best.is_none_or(|(n, bits, _)| covered.0 > n || (covered.0 == n && covered.1 > bits))
+        {
+            best = Some((covered.0, covered.1, word));
+        }
+    }
+    let (_, _, word) = best?;
+    Some(Probe {
+        word,
+        letters: splits
+            .iter()
+            .map(|split| split.letter)
+            .filter(|&letter| word.contains(letter))
+            .collect(),
+    })
+}
+
+/// A guess that no other guess beats on both information and chance of being the answer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParetoPoint {
+    pub word: &'static str,
+    /// The expected information from the feedback, in bits.
+    pub entropy: f64,
+    /// The chance that this guess is the answer, going by how common each candidate is.
+    pub probability: f64,
+}
+
+/// The Pareto frontier of guesses trading off expected information against the chance of being
+/// the answer, after the guesses in `history`, from most to least likely to be the answer.
+///
+/// A guesser that scores guesses by some mix of the two will always pick one of these.
+pub fn pareto(history: &[Guess]) -> Vec<ParetoPoint> {
+    let candidates = candidates(history);
+    let total: usize = candidates.iter().map(|&(_, count)| count).sum();
+
+    let mut points: Vec<_> = dictionary()
+        .iter()
+        .map(|&(word, _)| {
+            let mut totals = vec![0; Correctness::count(letter_count(word))];
+            let mut count = 0;
+            for &(candidate, c) in &candidates {
+                totals[Correctness::compute_packed(candidate, word)] += c;
+                if candidate == word {
+                    count = c;
+                }
+            }
+            let entropy = -summation::sum(totals.into_iter().filter(|&t| t != 0).map(|t| {
+                let p = t as f64 / total as f64;
+                p * p.log2()
+            }));
+            ParetoPoint {
+                word,
+                entropy,
+                probability: count as f64 / total as f64,
+            }
+        })
+        .collect();
+
+    // Going from most to least likely, a guess is only on the frontier if it gives more
+    // information than every more likely one.
+    points.sort_by(|a, b| {
+        b.probability
+            .total_cmp(&a.probability)
+            .then(b.entropy.total_cmp(&a.entropy))
+    });
+    let mut frontier: Vec<ParetoPoint> = Vec::new();
+    for point in points {
+        if frontier
+            .last()
+            .is_none_or(|last| point.entropy > last.entropy)
+        {
+            frontier.push(point);
+        }
+    }
+    frontier
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        buckets, candidates, chart, distribution, distribution_json, evaluate_guess,
+        informative_letters, opener_shortlist, opener_table, pareto, pattern_probability,
+        probe_word, rank_openers, Bucket,
+    };
+    use crate::{dictionary, Correctness, Guess};
+    use std::borrow::Cow;
+
+    #[test]
+    fn bucket_counts() {
+        let b = buckets("right", &["right", "fight", "might", "abbey"]);
+        assert_eq!(b[Correctness::pack(&[Correctness::Correct; 5])], 1);
+        assert_eq!(b.iter().sum::<usize>(), 4);
+        assert_eq!(b.into_iter().max(), Some(2));
+    }
+
+    #[test]
+    fn table() {
+        let candidates = ["right", "fight", "might", "sight"];
+        let table = opener_table(&["right", "fimsx"], &candidates);
+        // 'fimsx' tells every candidate apart, 'right' only itself from the others.
+        assert_eq!(table[0].word, "fimsx");
+        assert_eq!(table[0].entropy, 2.0);
+        assert_eq!(table[0].worst_case, 1);
+        assert_eq!(table[0].expected_remaining, 1.0);
+        assert_eq!(table[1].worst_case, 3);
+        assert_eq!(table[1].expected_remaining, 2.5);
+    }
+
+    #[test]
+    fn ranks_in_parallel() {
+        let words: Vec<_> = dictionary().iter().take(200).map(|&(w, _)| w).collect();
+        let table = opener_table(&words, &words);
+        for workers in [2, 7, 500] {
+            assert_eq!(rank_openers(&words, &words, workers), table);
+        }
+    }
+
+    #[test]
+    fn shortlist() {
+        let shortlist = opener_shortlist();
+        assert_eq!(shortlist.len(), 100);
+        assert_eq!(shortlist[0].0, "tares");
+        for pair in shortlist.windows(2) {
+            assert!(pair[0].1 >= pair[1].1);
+        }
+        // The build script adds up the entropy the same way as `opener_table`.
+        let words: Vec<_> = dictionary().iter().map(|&(word, _)| word).collect();
+        let openers: Vec<_> = shortlist[..3].iter().map(|&(word, _)| word).collect();
+        for (stats, &(word, entropy)) in opener_table(&openers, &words).iter().zip(shortlist) {
+            assert_eq!(stats.word, word);
+            assert_eq!(stats.entropy, entropy);
+        }
+    }
+
+    #[test]
+    fn explain() {
+        let history = [Guess {
+            word: Cow::Borrowed("tares"),
+            mask: Correctness::parse_mask("XYYXX", 5).unwrap(),
+        }];
+        let buckets = distribution(&history, "clown");
+        let total: f64 = buckets.iter().map(|b| b.probability).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+        for pair in buckets.windows(2) {
+            assert!(pair[0].probability >= pair[1].probability);
+        }
+        for bucket in &buckets {
+            for candidate in &bucket.candidates {
+                assert!(history[0].matches(candidate));
+                assert_eq!(Correctness::compute(candidate, "clown"), bucket.mask);
+            }
+        }
+        // Guessing a candidate only solves it for that one.
+        let buckets = distribution(&history, "board");
+        let solved = buckets.iter().find(|b| b.mask == Correctness::SOLVED);
+        assert_eq!(solved.unwrap().candidates, ["board"]);
+    }
+
+    #[test]
+    fn probability_of_feedback() {
+        let history = [Guess {
+            word: Cow::Borrowed("tares"),
+            mask: Correctness::parse_mask("XYYXX", 5).unwrap(),
+        }];
+        let candidates = candidates(&history);
+        for bucket in distribution(&history, "clown") {
+            let p = pattern_probability("clown", &bucket.mask, &candidates);
+            assert!((p - bucket.probability).abs() < 1e-12);
+        }
+        let impossible = Correctness::parse_mask("GGGGG", 5).unwrap();
+        assert_eq!(pattern_probability("clown", &impossible, &candidates), 0.0);
+        assert_eq!(pattern_probability("clown", &impossible, &[]), 0.0);
+
+        let even = [("cigar", 1), ("rebut", 1), ("sissy", 2)];
+        let solved = Correctness::compute("cigar", "cigar");
+        assert_eq!(pattern_probability("cigar", &solved, &even), 0.25);
+    }
+
+    #[test]
+    fn letters_to_test() {
+        // Leaves the words ending in 'ound' but not starting with 'l', such as mound, pound and
+        // round.
+        let history = [Guess {
+            word: Cow::Borrowed("lound"),
+            mask: Correctness::parse_mask("XGGGG", 5).unwrap(),
+        }];
+        let splits = informative_letters(&history);
+        assert!(!splits.is_empty());
+        for split in &splits {
+            assert!(!"lound".contains(split.letter));
+            assert!(split.probability > 0.0 && split.probability < 1.0);
+        }
+        for pair in splits.windows(2) {
+            assert!(pair[0].entropy >= pair[1].entropy);
+        }
+        // Nothing in common with any of them, so there's nothing to learn.
+        assert!(!splits.iter().any(|split| split.letter == 'x'));
+        assert!(splits.iter().any(|split| split.letter == 'p'));
+    }
+
+    #[test]
+    fn probe() {
+        let history = [Guess {
+            word: Cow::Borrowed("lound"),
+            mask: Correctness::parse_mask("XGGGG", 5).unwrap(),
+        }];
+        let probe = probe_word(&history).unwrap();
+        assert!(dictionary().iter().any(|&(word, _)| word == probe.word));
+        let splits = informative_letters(&history);
+        // No allowed guess tests more of the letters.
+        for &(word, _) in dictionary() {
+            let tested = splits.iter().filter(|s| word.contains(s.letter)).count();
+            assert!(tested <= probe.letters.len(), "{}", word);
+        }
+        for letter in &probe.letters {
+            assert!(probe.word.contains(*letter));
+        }
+
+        let solved = [Guess {
+            word: Cow::Borrowed("cigar"),
+            mask: Correctness::SOLVED,
+        }];
+        assert_eq!(probe_word(&solved), None);
+    }
+
+    #[test]
+    fn bars() {
+        let bucket = |mask, probability, candidates| Bucket {
+            mask: Correctness::parse_mask(mask, 5).unwrap(),
+            probability,
+            candidates,
+        };
+        let buckets = [
+            bucket("GGGGG", 0.5, vec!["cigar"]),
+            bucket("XYXXX", 0.4, vec!["rebut", "humph"]),
+            bucket("XXXXX", 0.1, vec!["awake"]),
+        ];
+        assert_eq!(
+            chart(&buckets, 5, 10),
+            "🟩🟩🟩🟩🟩 ##########  50.00% 1\n\
+             ⬛🟨⬛⬛⬛ ########    40.00% 2\n\
+             ⬛⬛⬛⬛⬛ ##          10.00% 1\n"
+        );
+        assert_eq!(
+            distribution_json(&buckets[1..], 5),
+            "[{\"pattern\":\"XYXXX\",\"probability\":0.4,\"candidates\":2},\
+             {\"pattern\":\"XXXXX\",\"probability\":0.1,\"candidates\":1}]"
+        );
+    }
+
+    #[test]
+    fn what_if() {
+        let history = [Guess {
+            word: Cow::Borrowed("tares"),
+            mask: Correctness::parse_mask("XYYXX", 5).unwrap(),
+        }];
+        let (tares, clown) = (
+            evaluate_guess(&history, "tares"),
+            evaluate_guess(&history, "clown"),
+        );
+        // Guessing a word that was already ruled out tells nothing new.
+        assert_eq!(tares.entropy, 0.0);
+        assert_eq!(
+            tares.worst_case,
+            distribution(&history, "clown")
+                .iter()
+                .map(|b| b.candidates.len())
+                .sum()
+        );
+        assert!(clown.entropy > 0.0);
+        assert!(clown.worst_case < tares.worst_case);
+        assert!(clown.expected_remaining < tares.expected_remaining);
+        assert!(clown.expected_score < tares.expected_score);
+        // No guess takes fewer than one guess, and one that can't win takes at least two.
+        assert!(evaluate_guess(&history, "board").expected_score > 1.0);
+        assert!(tares.expected_score > 2.0);
+    }
+
+    #[test]
+    fn frontier() {
+        let history = [Guess {
+            word: Cow::Borrowed("tares"),
+            mask: Correctness::parse_mask("XYYXX", 5).unwrap(),
+        }];
+        let frontier = pareto(&history);
+        assert!(!frontier.is_empty());
+        // The most likely answer comes first, and the best information with no chance of winning
+        // last.
+        assert!(frontier[0].probability > 0.0);
+        assert_eq!(frontier.last().unwrap().probability, 0.0);
+        for pair in frontier.windows(2) {
+            assert!(pair[0].probability > pair[1].probability);
+            assert!(pair[0].entropy < pair[1].entropy);
+        }
+    }
+}
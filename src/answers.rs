@@ -0,0 +1,241 @@
+use crate::{profile, splitmix64};
+use once_cell::sync::OnceCell;
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const ANSWERS: &str = include_str!("../answers.txt");
+
+static OFFICIAL: OnceCell<Answers> = OnceCell::new();
+static IN_USE: OnceCell<Answers> = OnceCell::new();
+
+/// The date of the first official puzzle, as days since 1970-01-01.
+const FIRST_PUZZLE_DAY: i64 = days_from_civil(2021, 6, 19);
+
+/// The answers to the puzzles, in the order they were (or will be) used.
+///
+/// Puzzles are numbered from 0, which was played on 2021-06-19. Answers that the dictionary
+/// [profile](crate::profile) leaves out keep their numbers, but are skipped over.
+pub struct Answers {
+    words: Vec<&'static str>,
+    days: HashMap<&'static str, usize>,
+}
+
+impl Answers {
+    pub fn official() -> &'static Self {
+        OFFICIAL.get_or_init(|| Self::new(ANSWERS.split_whitespace().collect()))
+    }
+
+    /// The answers of the [profile](crate::profile) in use, which are the official ones unless
+    /// it replaces them (or the dictionary).
+    pub fn in_use() -> &'static Self {
+        let profile = profile::profile();
+        if profile.answers.is_empty() && profile.words.is_empty() {
+            return Self::official();
+        }
+        IN_USE.get_or_init(|| {
+            if profile.answers.is_empty() {
+                Self::new(profile::dictionary().iter().map(|&(w, _)| w).collect())
+            } else {
+                Self::new(profile.answers.iter().map(String::as_str).collect())
+            }
+        })
+    }
+
+    fn new(words: Vec<&'static str>) -> Self {
+        let days = words.iter().enumerate().map(|(i, &w)| (w, i)).collect();
+        Self { words, days }
+    }
+
+    /// The answer to the puzzle with the given number.
+    pub fn answer_for(&self, day: usize) -> Option<&'static str> {
+        self.words
+            .get(day)
+            .copied()
+            .filter(|w| !profile::is_excluded(w))
+    }
+
+    /// The number of the puzzle whose answer was `word`.
+    pub fn day_of(&self, word: &str) -> Option<usize> {
+        self.days.get(word).copied()
+    }
+
+    /// The number of the puzzle played on the given date.
+    pub fn day_on(&self, year: i64, month: i64, day: i64) -> Option<usize> {
+        if !(1..=12).contains(&month) || !(1..=days_in_month(year, month)).contains(&day) {
+            return None;
+        }
+        let puzzle = usize::try_from(days_from_civil(year, month, day) - FIRST_PUZZLE_DAY).ok()?;
+        (puzzle < self.len()).then_some(puzzle)
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// A pseudo-random sample of `n` distinct answers, along with their puzzle numbers.
+    ///
+    /// The same `seed` always gives the same sample, so results on it can be compared over time.
+    pub fn sample(&self, n: usize, seed: u64) -> Vec<(usize, &'static str)> {
+        let mut state = seed;
+        let mut days: Vec<_> = self.iter().map(|(day, _)| day).collect();
+        let n = n.min(days.len());
+        for i in 0..n {
+            let j = i + (splitmix64(&mut state) % (days.len() - i) as u64) as usize;
+            days.swap(i, j);
+        }
+        days.truncate(n);
+        days.into_iter().map(|day| (day, self.words[day])).collect()
+    }
+
+    /// Every answer along with its puzzle number, in order.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &'static str)> + Clone + '_ {
+        self.words
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|(_, w)| !profile::is_excluded(w))
+    }
+}
+
+/// The date the puzzle with the given number is (or was) played on, as (year, month, day).
+pub fn date_of(puzzle: usize) -> (i64, i64, i64) {
+    civil_from_days(FIRST_PUZZLE_DAY + puzzle as i64)
+}
+
+/// The number of today's puzzle, going by the date in UTC. Wordle moves on to the next puzzle at
+/// midnight local time, so this is a day off for some of the day away from UTC.
+pub fn todays_puzzle() -> usize {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("the clock is after 1970");
+    let today = (now.as_secs() / (24 * 60 * 60)) as i64;
+    usize::try_from(today - FIRST_PUZZLE_DAY).unwrap_or(0)
+}
+
+/// Number of days since 1970-01-01 of the given date in the proleptic Gregorian calendar.
+///
+/// See <http://howardhinnant.github.io/date_algorithms.html#days_from_civil>.
+const fn days_from_civil(y: i64, m: i64, d: i64) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// The number of days in the given month of the proleptic Gregorian calendar.
+const fn days_in_month(y: i64, m: i64) -> i64 {
+    match m {
+        2 if y % 4 == 0 && (y % 100 != 0 || y % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// The date in the proleptic Gregorian calendar that is the given number of days since
+/// 1970-01-01, the inverse of [`days_from_civil`].
+///
+/// See <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+const fn civil_from_days(z: i64) -> (i64, i64, i64) {
+    let z = z + 719468;
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    (
+        if m <= 2 {
+            yoe + era * 400 + 1
+        } else {
+            yoe + era * 400
+        },
+        m,
+        d,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{date_of, Answers};
+
+    #[test]
+    fn answer_for() {
+        let answers = Answers::official();
+        assert_eq!(answers.answer_for(0), Some("cigar"));
+        assert_eq!(answers.answer_for(1), Some("rebut"));
+        assert_eq!(answers.answer_for(answers.len()), None);
+    }
+
+    #[test]
+    fn day_of() {
+        let answers = Answers::official();
+        assert_eq!(answers.day_of("cigar"), Some(0));
+        assert_eq!(answers.day_of("rebut"), Some(1));
+        assert_eq!(answers.day_of("tares"), None);
+    }
+
+    #[test]
+    fn day_on() {
+        let answers = Answers::official();
+        assert_eq!(answers.day_on(2021, 6, 19), Some(0));
+        assert_eq!(answers.day_on(2021, 6, 18), None);
+        assert_eq!(answers.day_on(2022, 1, 1), Some(196));
+        assert_eq!(answers.day_on(2023, 12, 18), Some(912));
+        assert_eq!(answers.day_on(2023, 13, 1), None);
+        // Days past the end of the month aren't the first days of the next.
+        assert_eq!(answers.day_on(2023, 4, 31), None);
+        assert_eq!(answers.day_on(2023, 2, 29), None);
+        assert_eq!(answers.day_on(2024, 2, 29), Some(985));
+        assert_eq!(answers.day_on(2024, 2, 30), None);
+        assert_eq!(answers.day_on(2024, 2, 31), None);
+    }
+
+    #[test]
+    fn date_of_puzzle() {
+        assert_eq!(date_of(0), (2021, 6, 19));
+        assert_eq!(date_of(196), (2022, 1, 1));
+        assert_eq!(date_of(912), (2023, 12, 18));
+        // 2024 is a leap year.
+        assert_eq!(date_of(985), (2024, 2, 29));
+    }
+
+    #[test]
+    fn sample_is_deterministic() {
+        let answers = Answers::official();
+        let sample = answers.sample(50, 42);
+        assert_eq!(sample.len(), 50);
+        assert_eq!(sample, answers.sample(50, 42));
+        assert_ne!(sample, answers.sample(50, 43));
+        for &(day, word) in &sample {
+            assert_eq!(answers.answer_for(day), Some(word));
+        }
+    }
+
+    #[test]
+    fn sample_is_distinct() {
+        let answers = Answers::official();
+        let mut days: Vec<_> = answers
+            .sample(answers.len() + 10, 7)
+            .into_iter()
+            .map(|(d, _)| d)
+            .collect();
+        days.sort_unstable();
+        assert_eq!(days, (0..answers.len()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn order_is_preserved() {
+        let answers = Answers::official();
+        for (day, word) in answers.iter() {
+            assert_eq!(answers.day_of(word), Some(day));
+        }
+    }
+}
@@ -0,0 +1,177 @@
+//! Asking a separately installed solver for suggestions, through its batch command, rather than
+//! linking against this crate's guessers directly (e.g. to pick up a newer solver without
+//! rebuilding).
+//!
+//! The batch command reads one game per line, as each guess followed by its feedback, and writes
+//! one suggestion per line in the same order, with `-` where there is none. [`HintRequest`] and
+//! [`HintResponse`] read and write those lines, so that nothing else needs to know the format.
+
+use crate::{letter_count, Correctness, Guess};
+use std::borrow::Cow;
+use std::ffi::OsString;
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Command, Stdio};
+
+/// A game to suggest the next guess for.
+#[derive(Debug, Clone, Default)]
+pub struct HintRequest {
+    pub history: Vec<Guess<'static>>,
+}
+
+impl HintRequest {
+    pub fn new(history: Vec<Guess<'static>>) -> Self {
+        Self { history }
+    }
+
+    /// The line of the batch command's input for this game, e.g. `tares XYYXX drain XYYYX`.
+    pub fn to_line(&self) -> String {
+        self.history
+            .iter()
+            .map(|guess| {
+                format!(
+                    "{} {}",
+                    guess.word,
+                    Correctness::format_mask(guess.feedback())
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Reads a game written by [`to_line`](Self::to_line).
+    pub fn from_line(line: &str) -> Option<Self> {
+        let words: Vec<_> = line.split_whitespace().collect();
+        if !words.len().is_multiple_of(2) {
+            return None;
+        }
+        let history = words
+            .chunks(2)
+            .map(|pair| {
+                Some(Guess {
+                    word: Cow::Owned(pair[0].to_lowercase()),
+                    mask: Correctness::parse_mask(pair[1], letter_count(pair[0]))?,
+                })
+            })
+            .collect::<Option<_>>()?;
+        Some(Self { history })
+    }
+}
+
+/// The suggestion for a [`HintRequest`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HintResponse {
+    /// The word to guess next, or `None` if the game is solved or no word fits the feedback.
+    pub suggestion: Option<String>,
+}
+
+impl HintResponse {
+    /// The line of the batch command's output for this suggestion.
+    pub fn to_line(&self) -> String {
+        self.suggestion.clone().unwrap_or_else(|| "-".to_string())
+    }
+
+    /// Reads a suggestion written by [`to_line`](Self::to_line).
+    pub fn from_line(line: &str) -> Self {
+        let line = line.trim();
+        Self {
+            suggestion: (line != "-").then(|| line.to_string()),
+        }
+    }
+}
+
+/// Runs a solver's batch command to get suggestions.
+#[derive(Debug, Clone)]
+pub struct Client {
+    program: OsString,
+    args: Vec<OsString>,
+}
+
+impl Client {
+    /// A client for the solver installed as `program` (e.g. `wordle_solver`).
+    pub fn new(program: impl Into<OsString>) -> Self {
+        Self {
+            program: program.into(),
+            args: Vec::new(),
+        }
+    }
+
+    /// Passes another option to the solver, before the batch command (e.g. `--implementation`).
+    pub fn arg(mut self, arg: impl Into<OsString>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// The suggestion for each of `requests`, in the same order.
+    ///
+    /// Every call starts the solver afresh, so it is cheaper to ask for many suggestions at once.
+    pub fn hints(&self, requests: &[HintRequest]) -> io::Result<Vec<HintResponse>> {
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .arg("batch")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        // The solver reads every game before it answers any, so the input is written (and
+        // closed) before the output is read.
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        for request in requests {
+            writeln!(stdin, "{}", request.to_line())?;
+        }
+        drop(stdin);
+
+        let stdout = child.stdout.take().expect("stdout is piped");
+        let responses = BufReader::new(stdout)
+            .lines()
+            .map(|line| line.map(|line| HintResponse::from_line(&line)))
+            .collect::<io::Result<Vec<_>>>()?;
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(io::Error::other(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+        if responses.len() != requests.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "asked for {} suggestions, but got {}",
+                    requests.len(),
+                    responses.len()
+                ),
+            ));
+        }
+        Ok(responses)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Client, HintRequest, HintResponse};
+
+    #[test]
+    fn requests() {
+        let request = HintRequest::from_line("TARES xyyxx drain XYYYX").unwrap();
+        assert_eq!(request.history.len(), 2);
+        assert_eq!(request.to_line(), "tares XYYXX drain XYYYX");
+        assert_eq!(HintRequest::default().to_line(), "");
+        assert!(HintRequest::from_line("").unwrap().history.is_empty());
+        assert!(HintRequest::from_line("tares").is_none());
+        assert!(HintRequest::from_line("tares XYYX").is_none());
+    }
+
+    #[test]
+    fn responses() {
+        let response = HintResponse::from_line("drain\n");
+        assert_eq!(response.suggestion.as_deref(), Some("drain"));
+        assert_eq!(response.to_line(), "drain");
+        assert_eq!(HintResponse::from_line("-").suggestion, None);
+    }
+
+    #[test]
+    fn missing_solver() {
+        let client = Client::new("wordle-solver-that-does-not-exist");
+        assert!(client.hints(&[HintRequest::default()]).is_err());
+    }
+}
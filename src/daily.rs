@@ -0,0 +1,91 @@
+//! The puzzle of the day as the New York Times has it, fetched from the endpoint its own site
+//! uses, since its answers have drifted from the original list the built-in ones come from.
+//!
+//! None of the dependencies speak HTTPS, so the fetching is left to an installed `curl`, much as
+//! the client feature leaves suggesting guesses to an installed solver.
+
+use crate::answers::date_of;
+use crate::json::Json;
+use std::process::Command;
+
+/// Where the puzzle for a date (YYYY-MM-DD) is published.
+const URL: &str = "https://www.nytimes.com/svc/wordle/v2/";
+
+/// One day's puzzle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DailyPuzzle {
+    /// The puzzle's number, counting from 0 on 2021-06-19 like the built-in answers.
+    pub number: usize,
+    /// The day it is played on, as YYYY-MM-DD.
+    pub date: String,
+    pub solution: String,
+}
+
+impl DailyPuzzle {
+    /// Fetches the puzzle with the given number.
+    pub fn fetch(number: usize) -> Result<Self, String> {
+        let (y, m, d) = date_of(number);
+        let url = format!("{}{:04}-{:02}-{:02}.json", URL, y, m, d);
+        let output = Command::new("curl")
+            .args(["--silent", "--show-error", "--fail", "--max-time", "10"])
+            .arg(&url)
+            .output()
+            .map_err(|e| format!("could not run curl: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "could not fetch {}: {}",
+                url,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Self::parse(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    /// Reads the puzzle out of the JSON the site publishes, e.g.
+    /// `{"id":1,"solution":"cigar","print_date":"2021-06-19","days_since_launch":0}`.
+    fn parse(json: &str) -> Result<Self, String> {
+        let puzzle = Json::parse(json).map_err(|e| format!("the puzzle isn't JSON: {}", e))?;
+        let missing = |key| format!("the puzzle has no {}", key);
+        let string = |key| match puzzle.get(key) {
+            Some(Json::String(s)) => Ok(s),
+            _ => Err(missing(key)),
+        };
+        let number = match puzzle.get("days_since_launch") {
+            Some(&Json::Number(n)) if n >= 0.0 && n.fract() == 0.0 => n as usize,
+            _ => return Err(missing("days_since_launch")),
+        };
+        Ok(Self {
+            number,
+            date: string("print_date")?.clone(),
+            solution: string("solution")?.to_lowercase(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DailyPuzzle;
+
+    #[test]
+    fn parses_the_puzzle() {
+        let json =
+            r#"{"id":1234,"solution":"Cigar", "print_date": "2021-06-19","days_since_launch":0}"#;
+        assert_eq!(
+            DailyPuzzle::parse(json),
+            Ok(DailyPuzzle {
+                number: 0,
+                date: "2021-06-19".to_string(),
+                solution: "cigar".to_string(),
+            })
+        );
+        assert!(DailyPuzzle::parse(r#"{"status":"ERROR"}"#).is_err());
+
+        // Only the puzzle's own fields count, not ones nested in it or inside its strings.
+        let json = r#"{"editor":{"solution":"rebut"},"note":"\"solution\":\"sissy\"","#.to_string()
+            + r#""solution":"cigar","print_date":"2021-06-19","days_since_launch":0}"#;
+        assert_eq!(DailyPuzzle::parse(&json).unwrap().solution, "cigar");
+        let nested =
+            r#"{"puzzle":{"solution":"cigar","print_date":"2021-06-19","days_since_launch":0}}"#;
+        assert!(DailyPuzzle::parse(nested).is_err());
+    }
+}
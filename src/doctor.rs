@@ -0,0 +1,429 @@
+//! Checks for the setups that make the solver behave oddly, such as a dictionary file with a word
+//! of the wrong length in it, an answer that can't be guessed, or a tree built against another
+//! dictionary, each with what to do about it. These are what the `doctor` command runs.
+//!
+//! Unlike loading the same files to play, which stops at the first thing wrong with them, the
+//! checks go on to find everything wrong at once.
+
+use crate::algorithms::{kernel, Strategy};
+use crate::profile::{cached_dictionary_path, is_valid_cached, profile, read_words, Profile};
+use crate::tree::Tree;
+use crate::{dictionary, dictionary_info, is_lowercase_word, letter_count, word_len, MAX_WORD_LEN};
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// How bad a [`Finding`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Nothing is wrong.
+    Ok,
+    /// The solver works, but maybe not as expected.
+    Warning,
+    /// The solver refuses to start, or gets something wrong.
+    Problem,
+}
+
+/// Something a check found, and how to fix it if anything is wrong.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub severity: Severity,
+    pub message: String,
+    pub fix: Option<String>,
+}
+
+impl Finding {
+    pub fn ok(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Ok,
+            message: message.into(),
+            fix: None,
+        }
+    }
+
+    pub fn warning(message: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            message: message.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    pub fn problem(message: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Problem,
+            message: message.into(),
+            fix: Some(fix.into()),
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self.severity {
+            Severity::Ok => "ok",
+            Severity::Warning => "warning",
+            Severity::Problem => "problem",
+        };
+        write!(f, "{:<8} {}", label, self.message)?;
+        if let Some(fix) = &self.fix {
+            write!(f, "\n{:<8} fix: {}", "", fix)?;
+        }
+        Ok(())
+    }
+}
+
+/// A few of `words`, and how many more there are, to name the ones a check found without
+/// listing thousands of them.
+fn some_of(words: &[&str]) -> String {
+    const SHOWN: usize = 5;
+    let shown = words
+        .iter()
+        .take(SHOWN)
+        .map(|w| format!("'{}'", w))
+        .collect::<Vec<_>>()
+        .join(", ");
+    match words.len().checked_sub(SHOWN) {
+        Some(more) if more > 0 => format!("{} and {} more", shown, more),
+        _ => shown,
+    }
+}
+
+/// The words in `words` that come up more than once.
+fn repeated<'a>(words: impl IntoIterator<Item = &'a str>) -> Vec<&'a str> {
+    let mut seen = HashSet::new();
+    let mut repeated = Vec::new();
+    for word in words {
+        if !seen.insert(word) && !repeated.contains(&word) {
+            repeated.push(word);
+        }
+    }
+    repeated
+}
+
+/// Checks the contents of a dictionary file, returning what was found and, if it could be read,
+/// its words.
+pub fn check_dictionary(text: &str) -> (Vec<Finding>, Option<Vec<(String, usize)>>) {
+    let words = match read_words(text) {
+        Ok(words) => words,
+        Err(e) => {
+            return (
+                vec![Finding::problem(
+                format!("the dictionary file can't be read: {}", e),
+                "put one word on each line, optionally followed by a space and how common it is",
+            )],
+                None,
+            )
+        }
+    };
+    let Some((first, _)) = words.first() else {
+        return (
+            vec![Finding::problem(
+                "the dictionary file has no words in it",
+                "add the allowed guesses to it, one on each line",
+            )],
+            None,
+        );
+    };
+    let len = letter_count(first);
+    let mut findings = Vec::new();
+    if !(1..=MAX_WORD_LEN).contains(&len) {
+        findings.push(Finding::problem(
+            format!("the words are {} letters long", len),
+            format!("only words of 1 to {} letters can be played", MAX_WORD_LEN),
+        ));
+    }
+    let malformed: Vec<_> = words
+        .iter()
+        .map(|(w, _)| w.as_str())
+        .filter(|w| !is_lowercase_word(w, len))
+        .collect();
+    if !malformed.is_empty() {
+        findings.push(Finding::problem(
+            format!(
+                "{} of the words are not {} lowercase letters like the first one: {}",
+                malformed.len(),
+                len,
+                some_of(&malformed)
+            ),
+            "lowercase them, or remove the ones of other lengths",
+        ));
+    }
+    let repeated = repeated(words.iter().map(|(w, _)| w.as_str()));
+    if !repeated.is_empty() {
+        findings.push(Finding::warning(
+            format!(
+                "{} words are in the dictionary file more than once: {}",
+                repeated.len(),
+                some_of(&repeated)
+            ),
+            "keep only one line for each word, with its count",
+        ));
+    }
+    if findings.is_empty() {
+        findings.push(Finding::ok(format!(
+            "the dictionary file has {} words of {} letters",
+            words.len(),
+            len
+        )));
+    }
+    (findings, Some(words))
+}
+
+/// Checks that every one of `answers` can be guessed, since it is one of the `guesses`, which
+/// all have `len` letters.
+pub fn check_answers(answers: &[&str], guesses: &HashSet<&str>, len: usize) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let (malformed, unguessable): (Vec<_>, Vec<_>) = answers
+        .iter()
+        .filter(|a| !guesses.contains(*a))
+        .partition(|a| !is_lowercase_word(a, len));
+    if !malformed.is_empty() {
+        findings.push(Finding::problem(
+            format!(
+                "{} of the answers are not {} lowercase letters like the dictionary's words: {}",
+                malformed.len(),
+                len,
+                some_of(&malformed)
+            ),
+            "lowercase them, or remove the ones of other lengths from the answers file",
+        ));
+    }
+    if !unguessable.is_empty() {
+        findings.push(Finding::problem(
+            format!(
+                "{} of the answers are not in the dictionary, so can never be guessed: {}",
+                unguessable.len(),
+                some_of(&unguessable)
+            ),
+            "add them to the dictionary file, or remove them from the answers file",
+        ));
+    }
+    let repeated = repeated(answers.iter().copied());
+    if !repeated.is_empty() {
+        findings.push(Finding::warning(
+            format!(
+                "{} answers come up more than once: {}",
+                repeated.len(),
+                some_of(&repeated)
+            ),
+            "looking a puzzle up by its answer only finds the last of them, so remove the others",
+        ));
+    }
+    if findings.is_empty() {
+        findings.push(Finding::ok(format!(
+            "all {} answers are in the dictionary",
+            answers.len()
+        )));
+    }
+    findings
+}
+
+/// Checks the dictionaries cached in `dir` for the profile in use, and for anything left behind
+/// there.
+pub fn check_cache_dir(dir: &Path) -> Vec<Finding> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) if !dir.exists() => {
+            return vec![Finding::ok(format!(
+                "nothing is cached in '{}' yet",
+                dir.display()
+            ))]
+        }
+        Err(e) => {
+            return vec![Finding::warning(
+                format!(
+                    "the cache directory '{}' can't be read: {}",
+                    dir.display(),
+                    e
+                ),
+                "fix its permissions, or pass --no-dictionary-cache",
+            )]
+        }
+    };
+    let mut findings = Vec::new();
+    let mut invalid = Vec::new();
+    let mut leftovers = Vec::new();
+    for path in entries.filter_map(|e| Some(e.ok()?.path())) {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        if name.ends_with(".tmp") {
+            leftovers.push(name.into_owned());
+        } else if name.starts_with("dictionary-") && !is_valid_cached(&path) {
+            invalid.push(name.into_owned());
+        }
+    }
+    if !invalid.is_empty() {
+        let invalid: Vec<_> = invalid.iter().map(String::as_str).collect();
+        findings.push(Finding::warning(
+            format!(
+                "{} cached dictionaries are damaged, so would be built again rather than read: {}",
+                invalid.len(),
+                some_of(&invalid)
+            ),
+            format!("delete them from '{}'", dir.display()),
+        ));
+    }
+    if !leftovers.is_empty() {
+        let leftovers: Vec<_> = leftovers.iter().map(String::as_str).collect();
+        findings.push(Finding::warning(
+            format!(
+                "{} temporary files were left behind by runs that didn't finish: {}",
+                leftovers.len(),
+                some_of(&leftovers)
+            ),
+            format!(
+                "delete them from '{}' while the solver isn't running",
+                dir.display()
+            ),
+        ));
+    }
+    // The official dictionary doesn't need building, so it is never cached.
+    if *profile() == Profile::default() {
+        if findings.is_empty() {
+            findings.push(Finding::ok(format!(
+                "nothing is wrong with the cache in '{}'",
+                dir.display()
+            )));
+        }
+        return findings;
+    }
+    let cached = cached_dictionary_path(dir);
+    findings.push(Finding::ok(if cached.exists() {
+        format!(
+            "the dictionary for these options is cached in '{}'",
+            cached.display()
+        )
+    } else {
+        "the dictionary for these options isn't cached, which only matters if it is slow to build"
+            .to_string()
+    }));
+    findings
+}
+
+/// Checks that a strategy file can be loaded, and that its opener is in the dictionary in use.
+pub fn check_strategy(text: &str) -> Vec<Finding> {
+    let strategy: Strategy = match text.parse() {
+        Ok(strategy) => strategy,
+        Err(e) => {
+            return vec![Finding::problem(
+                format!("the strategy can't be loaded: {}", e),
+                "see the documentation of Strategy for the rules it can have",
+            )]
+        }
+    };
+    if letter_count(&strategy.opener) != word_len() {
+        return vec![Finding::problem(
+            format!(
+                "the strategy opens with '{}', but the dictionary's words have {} letters",
+                strategy.opener,
+                word_len()
+            ),
+            "strategies only play five-letter words, so use the official dictionary",
+        )];
+    }
+    if !dictionary().iter().any(|&(w, _)| w == strategy.opener) {
+        return vec![Finding::problem(
+            format!(
+                "the strategy opens with '{}', which is not in the dictionary",
+                strategy.opener
+            ),
+            "pick another opener with an `opener` rule",
+        )];
+    }
+    vec![Finding::ok("the strategy loads")]
+}
+
+/// Checks that a tree file was built against the dictionary in use, and solves every one of
+/// `answers`.
+pub fn check_tree(text: &str, answers: &[&'static str]) -> Vec<Finding> {
+    let tree: Tree = match text.parse() {
+        Ok(tree) => tree,
+        Err(e) => {
+            return vec![Finding::problem(
+                format!("the tree can't be loaded: {}", e),
+                "compile it again, with the same options as now",
+            )]
+        }
+    };
+    let verification = tree.verify(answers);
+    if verification.is_ok() {
+        return vec![Finding::ok(format!(
+            "the tree solves all {} answers, in at most {} guesses",
+            answers.len(),
+            verification.max_guesses
+        ))];
+    }
+    vec![Finding::problem(
+        format!(
+            "the tree has {} problems, such as: {}",
+            verification.problems.len(),
+            verification.problems[0]
+        ),
+        "compile it again, or see every problem with verify-strategy",
+    )]
+}
+
+/// Describes the dictionary in use, and what that means for which implementations can play it.
+pub fn check_in_use() -> Vec<Finding> {
+    let info = dictionary_info();
+    let mut findings = vec![Finding::ok(format!(
+        "playing dictionary {} of {} words of {} letters",
+        info.version(),
+        info.words,
+        word_len()
+    ))];
+    if word_len() > 5 {
+        findings.push(Finding::warning(
+            "the cache implementation (the default) only plays words of up to five letters",
+            "escore is played by default instead, which is slower",
+        ));
+    }
+    findings.push(Finding::ok(format!(
+        "working out feedback with the {} kernel",
+        kernel()
+    )));
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_answers, check_dictionary, Severity};
+    use std::collections::HashSet;
+
+    fn severities(findings: &[super::Finding]) -> Vec<Severity> {
+        findings.iter().map(|f| f.severity).collect()
+    }
+
+    #[test]
+    fn dictionary() {
+        let (findings, words) = check_dictionary("cigar 10\nrebut 5\nsissy\n");
+        assert_eq!(severities(&findings), [Severity::Ok]);
+        assert_eq!(words.unwrap().len(), 3);
+
+        let (findings, _) = check_dictionary("cigar\nRebut\ncat\ncigar\n");
+        assert_eq!(
+            severities(&findings),
+            [Severity::Problem, Severity::Warning]
+        );
+        assert!(findings[0].message.contains("'Rebut', 'cat'"));
+
+        let (findings, words) = check_dictionary("cigar lots\n");
+        assert_eq!(severities(&findings), [Severity::Problem]);
+        assert!(words.is_none());
+        assert_eq!(severities(&check_dictionary("\n\n").0), [Severity::Problem]);
+    }
+
+    #[test]
+    fn answers() {
+        let guesses: HashSet<_> = ["cigar", "rebut", "sissy"].into_iter().collect();
+        let ok = check_answers(&["cigar", "rebut"], &guesses, 5);
+        assert_eq!(severities(&ok), [Severity::Ok]);
+        let bad = check_answers(&["cigar", "humph", "cat", "cigar"], &guesses, 5);
+        assert_eq!(
+            severities(&bad),
+            [Severity::Problem, Severity::Problem, Severity::Warning]
+        );
+        assert!(bad[1].message.contains("'humph'"));
+    }
+}
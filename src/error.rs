@@ -0,0 +1,47 @@
+//! What can go wrong when embedding the solver, for the fallible versions of the functions that
+//! otherwise panic, such as [`Wordle::try_play`](crate::Wordle::try_play) and
+//! [`Correctness::try_compute`](crate::Correctness::try_compute).
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WordleError {
+    /// A word was guessed that isn't in the dictionary.
+    UnknownWord(String),
+    /// A word doesn't have as many letters as the words it is played against.
+    WrongLength { word: String, expected: usize },
+    /// A word has something other than lowercase letters (from any alphabet) in it.
+    NotLowercase(String),
+    /// A word was guessed that uses a letter more than once, when that isn't allowed.
+    RepeatsLetter(String),
+    /// A word was guessed in hard mode without reusing every letter revealed so far.
+    BreaksHardMode(String),
+    /// The answer still wasn't found after this many guesses.
+    TooManyGuesses(usize),
+    /// A dictionary couldn't be read, for this reason.
+    DictionaryParse(String),
+}
+
+impl fmt::Display for WordleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownWord(word) => write!(f, "guess '{}' is not in the dictionary", word),
+            Self::WrongLength { word, expected } => {
+                write!(f, "'{}' is not {} letters long", word, expected)
+            }
+            Self::NotLowercase(word) => write!(f, "'{}' is not made of lowercase letters", word),
+            Self::RepeatsLetter(word) => write!(f, "guess '{}' repeats a letter", word),
+            Self::BreaksHardMode(word) => write!(
+                f,
+                "guess '{}' doesn't reuse every letter revealed, as hard mode requires",
+                word
+            ),
+            Self::TooManyGuesses(guesses) => {
+                write!(f, "the answer wasn't found in {} guesses", guesses)
+            }
+            Self::DictionaryParse(reason) => f.write_str(reason),
+        }
+    }
+}
+
+impl std::error::Error for WordleError {}
@@ -0,0 +1,431 @@
+use crate::{Guesser, GuesserFactory, Wordle};
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// Settings for [`evaluate_all`].
+#[derive(Debug, Clone)]
+pub struct EvaluateOptions {
+    /// Games that take more guesses than this count as failures, even though they are played to
+    /// the end.
+    pub max_guesses: usize,
+}
+
+impl Default for EvaluateOptions {
+    fn default() -> Self {
+        // What the real game allows.
+        Self { max_guesses: 6 }
+    }
+}
+
+/// How a guesser did on a set of answers.
+#[derive(Debug, Clone)]
+pub struct EvaluationStats {
+    pub games: usize,
+    /// `histogram[i]` is the number of games solved in `i + 1` guesses.
+    pub histogram: Vec<usize>,
+    /// The answers that took more than [`max_guesses`](Self::max_guesses), or were never guessed.
+    pub failures: Vec<&'static str>,
+    /// The [`EvaluateOptions::max_guesses`] the games were played with.
+    pub max_guesses: usize,
+    pub elapsed: Duration,
+}
+
+impl Default for EvaluationStats {
+    fn default() -> Self {
+        Self::new(&EvaluateOptions::default())
+    }
+}
+
+impl EvaluationStats {
+    pub fn new(opts: &EvaluateOptions) -> Self {
+        Self {
+            games: 0,
+            histogram: Vec::new(),
+            failures: Vec::new(),
+            max_guesses: opts.max_guesses,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    /// The number of games that were eventually solved.
+    pub fn solved(&self) -> usize {
+        self.histogram.iter().sum()
+    }
+
+    pub fn total_guesses(&self) -> usize {
+        self.histogram
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (i + 1) * n)
+            .sum()
+    }
+
+    /// The average number of guesses over the games that were eventually solved.
+    pub fn average_score(&self) -> f64 {
+        self.total_guesses() as f64 / self.solved().max(1) as f64
+    }
+
+    pub fn failure_rate(&self) -> f64 {
+        self.failures.len() as f64 / self.games.max(1) as f64
+    }
+
+    /// The middle score of the games that were eventually solved.
+    pub fn median(&self) -> Option<usize> {
+        self.percentile(0.5)
+    }
+
+    /// The smallest score at least `fraction` of the games that were eventually solved did as
+    /// well as, e.g. 0.95 for the 95th percentile.
+    pub fn percentile(&self, fraction: f64) -> Option<usize> {
+        let rank = ((fraction * self.solved() as f64).ceil() as usize).max(1);
+        let mut seen = 0;
+        for (i, n) in self.histogram.iter().enumerate() {
+            seen += n;
+            if seen >= rank {
+                return Some(i + 1);
+            }
+        }
+        None
+    }
+
+    /// How many games were solved in each of one to [`max_guesses`](Self::max_guesses) guesses,
+    /// followed by how many [failed](Self::failures).
+    pub fn spread(&self) -> Vec<usize> {
+        let mut spread = vec![0; self.max_guesses + 1];
+        for (bar, &n) in spread.iter_mut().zip(&self.histogram) {
+            *bar = n;
+        }
+        spread[self.max_guesses] = self.failures.len();
+        spread
+    }
+
+    /// The [spread](Self::spread) as a bar chart, one line per bar, with the longest bar `width`
+    /// characters long.
+    pub fn chart(&self, width: usize) -> String {
+        let spread = self.spread();
+        let most = spread.iter().copied().max().unwrap_or(0).max(1);
+        let games = self.games.max(1);
+        let mut chart = String::new();
+        for (i, &n) in spread.iter().enumerate() {
+            let label = if i < self.max_guesses {
+                (i + 1).to_string()
+            } else {
+                "X".to_string()
+            };
+            // Round up, so that no bar with games in it disappears.
+            let bar = "█".repeat((n * width).div_ceil(most));
+            chart += &format!(
+                "{:>label_width$} {:<width$} {} ({:.1}%)\n",
+                label,
+                bar,
+                n,
+                100.0 * n as f64 / games as f64,
+                label_width = self.max_guesses.to_string().len(),
+                width = width
+            );
+        }
+        chart
+    }
+
+    /// Adds a game of `answer` that took `score` guesses, or that was never solved if that is
+    /// `None`.
+    pub fn record(&mut self, answer: &'static str, score: Option<usize>) {
+        self.games += 1;
+        match score {
+            Some(s) => {
+                if self.histogram.len() < s {
+                    self.histogram.resize(s, 0);
+                }
+                self.histogram[s - 1] += 1;
+                if s > self.max_guesses {
+                    self.failures.push(answer);
+                }
+            }
+            None => self.failures.push(answer),
+        }
+    }
+}
+
+impl fmt::Display for EvaluationStats {
+    /// The mean, median, 95th percentile and failure rate, followed by the chart.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |score: Option<usize>| score.map_or("-".to_string(), |s| s.to_string());
+        writeln!(
+            f,
+            "{} games: mean {:.4}, median {}, 95th percentile {}, {:.2}% failed",
+            self.games,
+            self.average_score(),
+            show(self.median()),
+            show(self.percentile(0.95)),
+            self.failure_rate() * 100.0
+        )?;
+        f.write_str(&self.chart(40))
+    }
+}
+
+/// Plays every one of `answers` with a fresh `G::default()`.
+pub fn evaluate_all<G>(
+    answers: impl IntoIterator<Item = &'static str>,
+    opts: &EvaluateOptions,
+) -> EvaluationStats
+where
+    G: Guesser + Default,
+{
+    evaluate_all_with(answers, &G::default, opts)
+}
+
+/// Plays every one of `answers` with a fresh guesser from `factory`.
+pub fn evaluate_all_with<G>(
+    answers: impl IntoIterator<Item = &'static str>,
+    factory: &impl GuesserFactory<Guesser = G>,
+    opts: &EvaluateOptions,
+) -> EvaluationStats
+where
+    G: Guesser,
+{
+    let w = Wordle::new();
+    let start = Instant::now();
+    let mut stats = EvaluationStats::new(opts);
+    for answer in answers {
+        stats.record(answer, w.play(answer, factory.new_guesser()));
+    }
+    stats.elapsed = start.elapsed();
+    stats
+}
+
+/// Like [`evaluate_all_with`], but plays the answers on a pool of `workers` threads.
+///
+/// Each worker creates a single guesser from `factory`, and plays each of its games with a clone
+/// of it. That way the heavy state a guesser sets up when it is created is only set up once per
+/// worker, and each game only copies the light state it changes as it plays. (State shared by
+/// every guesser, like the cache of [`Cached`](crate::algorithms::Cached), is set up only once.)
+/// The stats come out the same as with [`evaluate_all_with`], in the same order, apart from
+/// `elapsed`.
+pub fn evaluate_parallel_with<F>(
+    answers: impl IntoIterator<Item = &'static str>,
+    factory: &F,
+    opts: &EvaluateOptions,
+    workers: usize,
+) -> EvaluationStats
+where
+    F: GuesserFactory + Sync,
+    F::Guesser: Clone,
+{
+    let w = Wordle::new();
+    let start = Instant::now();
+    let answers: Vec<_> = answers.into_iter().collect();
+    let scores = in_parallel(
+        &answers,
+        workers,
+        || factory.new_guesser(),
+        |guesser, &answer| w.play(answer, guesser.clone()),
+    );
+    let mut stats = EvaluationStats::new(opts);
+    for (answer, score) in answers.into_iter().zip(scores) {
+        stats.record(answer, score);
+    }
+    stats.elapsed = start.elapsed();
+    stats
+}
+
+/// Plays every one of `answers` on a pool of `workers` threads, returning what `play` made of
+/// each game, in the same order as `answers`.
+///
+/// Unlike [`evaluate_parallel_with`], this doesn't need the guesser to be `Clone`: each worker
+/// creates a single guesser from `factory`, and [resets](Guesser::reset) it before each of its
+/// games. `play` is given that guesser and the answer, and plays the game however it likes, such
+/// as with [`Wordle::play_game`] to keep track of everything that happened in it.
+pub fn play_parallel_with<F, R>(
+    answers: impl IntoIterator<Item = &'static str>,
+    factory: &F,
+    workers: usize,
+    play: impl Fn(&mut F::Guesser, &'static str) -> R + Sync,
+) -> Vec<R>
+where
+    F: GuesserFactory + Sync,
+    R: Send,
+{
+    let answers: Vec<_> = answers.into_iter().collect();
+    in_parallel(
+        &answers,
+        workers,
+        || factory.new_guesser(),
+        |guesser, &answer| {
+            guesser.reset();
+            play(guesser, answer)
+        },
+    )
+}
+
+/// Works through `items` on a pool of `workers` threads, returning what `work` made of each, in
+/// the same order as `items`.
+///
+/// Each worker calls `setup` once, before its first item, and passes what it returned to `work`
+/// along with every one of its items, so that it can be reused between them.
+pub(crate) fn in_parallel<T, S, R>(
+    items: &[T],
+    workers: usize,
+    setup: impl Fn() -> S + Sync,
+    work: impl Fn(&mut S, &T) -> R + Sync,
+) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+{
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<_> = std::thread::scope(|s| {
+        let workers: Vec<_> = (0..workers.max(1))
+            .map(|_| {
+                s.spawn(|| {
+                    let mut state = setup();
+                    let mut done = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(i) else {
+                            return done;
+                        };
+                        done.push((i, work(&mut state, item)));
+                    }
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("a worker panicked"))
+            .collect()
+    });
+    results.sort_unstable_by_key(|&(i, _)| i);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        evaluate_all, evaluate_all_with, evaluate_parallel_with, in_parallel, play_parallel_with,
+        EvaluateOptions, EvaluationStats,
+    };
+    use crate::algorithms::{Popular, Strategy};
+    use crate::{GameMode, Wordle};
+
+    #[test]
+    fn stats() {
+        let answers = ["cigar", "rebut", "sissy"];
+        let stats = evaluate_all::<Popular>(answers, &EvaluateOptions::default());
+        assert_eq!(stats.games, 3);
+        assert_eq!(stats.solved(), 3);
+        assert!(stats.average_score() >= 1.0);
+
+        let strict = evaluate_all::<Popular>(answers, &EvaluateOptions { max_guesses: 1 });
+        assert_eq!(strict.failures.len(), 3);
+        assert_eq!(strict.failure_rate(), 1.0);
+        assert_eq!(strict.histogram, stats.histogram);
+    }
+
+    #[test]
+    fn configured_factory() {
+        let strategy: Strategy = "opener cigar".parse().unwrap();
+        let stats = evaluate_all_with(["cigar"], &strategy, &EvaluateOptions::default());
+        assert_eq!(stats.histogram, vec![1]);
+    }
+
+    #[test]
+    fn parallel_matches_sequential() {
+        let answers = [
+            "cigar", "rebut", "sissy", "humph", "awake", "blush", "focal",
+        ];
+        let opts = EvaluateOptions { max_guesses: 3 };
+        let sequential = evaluate_all::<Popular>(answers, &opts);
+        for workers in [1, 3, 16] {
+            let parallel = evaluate_parallel_with(answers, &Popular::new, &opts, workers);
+            assert_eq!(parallel.games, sequential.games);
+            assert_eq!(parallel.histogram, sequential.histogram);
+            assert_eq!(parallel.failures, sequential.failures);
+        }
+    }
+
+    #[test]
+    fn play_parallel_keeps_order() {
+        let answers = [
+            "cigar", "rebut", "sissy", "humph", "awake", "blush", "focal",
+        ];
+        let w = Wordle::new();
+        let sequential: Vec<_> = answers
+            .iter()
+            .map(|answer| w.play(answer, Popular::new()))
+            .collect();
+        for workers in [1, 3, 16] {
+            let games = play_parallel_with(answers, &Popular::new, workers, |guesser, answer| {
+                w.play_game(answer, guesser, GameMode::Normal)
+            });
+            let answered: Vec<_> = games.iter().map(|game| game.answer).collect();
+            assert_eq!(answered, answers);
+            let scores: Vec<_> = games.iter().map(|game| game.score()).collect();
+            assert_eq!(scores, sequential);
+        }
+    }
+
+    #[test]
+    fn in_parallel_sets_up_once_per_worker() {
+        let items: Vec<usize> = (0..100).collect();
+        for workers in [1, 3, 16] {
+            let done = in_parallel(&items, workers, Vec::new, |seen, &i| {
+                seen.push(i);
+                (i * 2, seen.len())
+            });
+            let doubled: Vec<_> = done.iter().map(|&(i, _)| i).collect();
+            assert_eq!(doubled, (0..200).step_by(2).collect::<Vec<_>>());
+            // Every worker kept its own state between its items.
+            let firsts = done.iter().filter(|&&(_, seen)| seen == 1).count();
+            assert!((1..=workers).contains(&firsts));
+        }
+    }
+
+    fn recorded(scores: &[Option<usize>]) -> EvaluationStats {
+        let mut stats = EvaluationStats::default();
+        for (&score, answer) in scores
+            .iter()
+            .zip(["cigar", "rebut", "sissy", "humph"].iter().cycle())
+        {
+            stats.record(answer, score);
+        }
+        stats
+    }
+
+    #[test]
+    fn spread() {
+        let stats = recorded(&[Some(3), Some(4), Some(3), Some(2), Some(5), Some(7), None]);
+        assert_eq!(stats.games, 7);
+        assert_eq!(stats.solved(), 6);
+        // Taking seven guesses counts as failing, but still towards the mean.
+        assert_eq!(stats.failures.len(), 2);
+        assert!((stats.failure_rate() - 2.0 / 7.0).abs() < 1e-9);
+        assert!((stats.average_score() - 4.0).abs() < 1e-9);
+        assert_eq!(stats.median(), Some(3));
+        assert_eq!(stats.percentile(0.95), Some(7));
+        assert_eq!(stats.percentile(0.0), Some(2));
+        assert_eq!(stats.spread(), [0, 1, 2, 1, 1, 0, 2]);
+    }
+
+    #[test]
+    fn spread_without_games() {
+        let stats = EvaluationStats::default();
+        assert_eq!(stats.games, 0);
+        assert_eq!(stats.average_score(), 0.0);
+        assert_eq!(stats.median(), None);
+        assert_eq!(stats.failure_rate(), 0.0);
+        assert_eq!(stats.spread(), [0; 7]);
+    }
+
+    #[test]
+    fn charts() {
+        let stats = recorded(&[Some(3), Some(3), Some(4), None]);
+        let chart = stats.chart(4);
+        let lines: Vec<_> = chart.lines().collect();
+        assert_eq!(lines.len(), 7);
+        assert_eq!(lines[0], "1      0 (0.0%)");
+        assert_eq!(lines[2], "3 ████ 2 (50.0%)");
+        assert_eq!(lines[3], "4 ██   1 (25.0%)");
+        assert_eq!(lines[6], "X ██   1 (25.0%)");
+    }
+}
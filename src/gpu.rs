@@ -0,0 +1,244 @@
+//! Working out the feedback of every guess against every candidate on the GPU (experimental).
+//!
+//! Scoring every allowed word as an opener means working out the feedback for every one of the
+//! ~13,000 words against every other one, which is 170 million masks. Each of them is independent
+//! of the others, so a compute shader can work them out all at once and add them up into the
+//! [buckets](crate::analysis::buckets) of each guess, leaving only the entropies to the CPU.
+
+use crate::analysis::{opener_stats, sort_openers, OpenerStats};
+use crate::profile::{duplicates, Duplicates};
+use crate::Correctness;
+use std::borrow::Cow;
+use std::sync::mpsc;
+use wgpu::util::DeviceExt;
+
+// The shader packs a word into a u32 with five bits per letter, and packs its feedback the same
+// way as the cache does.
+const MAX_GPU_WORD_LEN: usize = 5;
+// How many candidates each workgroup of the shader goes through.
+const WORKGROUP_SIZE: u32 = 64;
+// The most workgroups a dispatch can have in each dimension.
+const MAX_DISPATCH: u32 = 65535;
+
+/// A GPU set up to run the shader.
+pub struct Gpu {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+}
+
+impl Gpu {
+    /// Sets up the first GPU that wgpu can find, failing if there is none.
+    pub fn new() -> Result<Self, String> {
+        pollster::block_on(Self::new_async())
+    }
+
+    async fn new_async() -> Result<Self, String> {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                ..Default::default()
+            })
+            .await
+            .ok_or("no GPU is available")?;
+        let (device, queue) = adapter
+            .request_device(&wgpu::DeviceDescriptor::default(), None)
+            .await
+            .map_err(|e| format!("could not open the GPU: {}", e))?;
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("buckets"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!("gpu.wgsl"))),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("buckets"),
+            layout: None,
+            module: &module,
+            entry_point: "main",
+        });
+        Ok(Self {
+            device,
+            queue,
+            pipeline,
+        })
+    }
+
+    /// The [buckets](crate::analysis::buckets) of each of `guesses` against `candidates`, under
+    /// the duplicate-letter rule in use.
+    ///
+    /// The words must all have the same number of letters, at most five, all from a to z.
+    pub fn buckets(
+        &self,
+        guesses: &[&str],
+        candidates: &[&str],
+    ) -> Result<Vec<Vec<usize>>, String> {
+        let Some(len) = guesses.first().or(candidates.first()).map(|w| w.len()) else {
+            return Ok(Vec::new());
+        };
+        if len > MAX_GPU_WORD_LEN {
+            return Err(format!(
+                "the GPU only works out feedback for words of up to {} letters",
+                MAX_GPU_WORD_LEN
+            ));
+        }
+        let guess_words = pack_words(guesses, len)?;
+        let candidate_words = pack_words(candidates, len)?;
+        let patterns = Correctness::count(len);
+        if guesses.is_empty() || candidates.is_empty() {
+            return Ok(vec![vec![0; patterns]; guesses.len()]);
+        }
+
+        let params = [
+            guesses.len() as u32,
+            candidates.len() as u32,
+            len as u32,
+            match duplicates() {
+                Duplicates::Official => 0,
+                Duplicates::CountCapped => 1,
+                Duplicates::PerLetter => 2,
+            },
+            patterns as u32,
+            // Uniforms are laid out in 16-byte chunks.
+            0,
+            0,
+            0,
+        ];
+        let buffer = |label, contents: &[u32], usage| {
+            self.device
+                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some(label),
+                    contents: &as_bytes(contents),
+                    usage,
+                })
+        };
+        let params = buffer("params", &params, wgpu::BufferUsages::UNIFORM);
+        let guess_words = buffer("guesses", &guess_words, wgpu::BufferUsages::STORAGE);
+        let candidate_words = buffer("candidates", &candidate_words, wgpu::BufferUsages::STORAGE);
+        let size = (guesses.len() * patterns * std::mem::size_of::<u32>()) as u64;
+        let totals = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("buckets"),
+            size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("readback"),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("buckets"),
+            layout: &self.pipeline.get_bind_group_layout(0),
+            entries: &[
+                (0, &params),
+                (1, &guess_words),
+                (2, &candidate_words),
+                (3, &totals),
+            ]
+            .map(|(binding, buffer)| wgpu::BindGroupEntry {
+                binding,
+                resource: buffer.as_entire_binding(),
+            }),
+        });
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            let guesses = guesses.len() as u32;
+            pass.dispatch_workgroups(
+                (candidates.len() as u32).div_ceil(WORKGROUP_SIZE),
+                guesses.min(MAX_DISPATCH),
+                guesses.div_ceil(MAX_DISPATCH),
+            );
+        }
+        encoder.copy_buffer_to_buffer(&totals, 0, &readback, 0, size);
+        self.queue.submit([encoder.finish()]);
+
+        let slice = readback.slice(..);
+        let (tx, rx) = mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = tx.send(result);
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        rx.recv()
+            .map_err(|e| e.to_string())?
+            .map_err(|e| format!("could not read the buckets back from the GPU: {}", e))?;
+        let counts: Vec<usize> = slice
+            .get_mapped_range()
+            .chunks_exact(4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
+            .collect();
+        Ok(counts.chunks(patterns).map(<[usize]>::to_vec).collect())
+    }
+
+    /// The same as [`opener_table`](crate::analysis::opener_table), with the buckets worked out on
+    /// the GPU.
+    pub fn opener_table(
+        &self,
+        guesses: &[&'static str],
+        candidates: &[&str],
+    ) -> Result<Vec<OpenerStats>, String> {
+        let mut table: Vec<_> = guesses
+            .iter()
+            .zip(self.buckets(guesses, candidates)?)
+            .map(|(&word, buckets)| opener_stats(word, buckets, candidates.len()))
+            .collect();
+        sort_openers(&mut table);
+        Ok(table)
+    }
+}
+
+/// Packs each of `words` into a u32, five bits per letter, first letter lowest.
+fn pack_words(words: &[&str], len: usize) -> Result<Vec<u32>, String> {
+    words
+        .iter()
+        .map(|word| {
+            if word.len() != len || !word.bytes().all(|b| b.is_ascii_lowercase()) {
+                return Err(format!(
+                    "the GPU only works out feedback for words of {} letters from a to z, not '{}'",
+                    len, word
+                ));
+            }
+            Ok(word
+                .bytes()
+                .enumerate()
+                .map(|(i, b)| u32::from(b - b'a') << (5 * i))
+                .sum())
+        })
+        .collect()
+}
+
+fn as_bytes(words: &[u32]) -> Vec<u8> {
+    words.iter().flat_map(|w| w.to_le_bytes()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pack_words, Gpu};
+    use crate::analysis::buckets;
+
+    #[test]
+    fn pack() {
+        assert_eq!(pack_words(&["ab", "ba"], 2), Ok(vec![1 << 5, 1]));
+        assert!(pack_words(&["abc"], 2).is_err());
+        assert!(pack_words(&["aé"], 3).is_err());
+    }
+
+    #[test]
+    #[ignore = "needs a GPU"]
+    fn matches_cpu() {
+        let gpu = Gpu::new().unwrap();
+        let words = [
+            "cigar", "rebut", "sissy", "humph", "awake", "speed", "erase",
+        ];
+        let on_gpu = gpu.buckets(&words, &words).unwrap();
+        for (guess, on_gpu) in words.iter().zip(on_gpu) {
+            assert_eq!(on_gpu, buckets(guess, &words), "{}", guess);
+        }
+    }
+}
@@ -0,0 +1,69 @@
+// Adds up how many candidates give each feedback to each guess, with one invocation per pair of
+// guess and candidate. It works the feedback out the same way as `Correctness::compute_with`.
+
+struct Params {
+    guesses: u32,
+    candidates: u32,
+    len: u32,
+    // 0 is the official rule, 1 count-capped and 2 per-letter.
+    duplicates: u32,
+    patterns: u32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+// Every word packed into a u32, five bits per letter, first letter lowest.
+@group(0) @binding(1) var<storage, read> guesses: array<u32>;
+@group(0) @binding(2) var<storage, read> candidates: array<u32>;
+// `params.patterns` counts for each guess.
+@group(0) @binding(3) var<storage, read_write> buckets: array<atomic<u32>>;
+
+// A dispatch can be at most this big in each dimension, so the guesses are spread over y and z.
+const MAX_DISPATCH: u32 = 65535u;
+
+fn letter(word: u32, i: u32) -> u32 {
+    return (word >> (5u * i)) & 31u;
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let c = id.x;
+    let g = id.y + id.z * MAX_DISPATCH;
+    if c >= params.candidates || g >= params.guesses {
+        return;
+    }
+    let guess = guesses[g];
+    let answer = candidates[c];
+
+    var misplaced: array<u32, 26>;
+    var correct: array<bool, 5>;
+    for (var i = 0u; i < params.len; i++) {
+        let a = letter(answer, i);
+        correct[i] = a == letter(guess, i);
+        if params.duplicates == 0u {
+            if !correct[i] {
+                misplaced[a] += 1u;
+            }
+        } else if params.duplicates == 1u {
+            misplaced[a] += 1u;
+        } else {
+            // More than any guess can use up.
+            misplaced[a] = 0xffffffffu;
+        }
+    }
+
+    var pattern = 0u;
+    for (var i = 0u; i < params.len; i++) {
+        var feedback = 2u;
+        if correct[i] {
+            feedback = 0u;
+        } else {
+            let l = letter(guess, i);
+            if misplaced[l] > 0u {
+                feedback = 1u;
+                misplaced[l] -= 1u;
+            }
+        }
+        pattern = pattern * 3u + feedback;
+    }
+    atomicAdd(&buckets[g * params.patterns + pattern], 1u);
+}
@@ -0,0 +1,365 @@
+use crate::evaluate::in_parallel;
+use crate::{dictionary, Correctness, Guess, Guesser, GuesserFactory};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// What a suggestion depends on: the guesser that makes it, the parameters it was set up with,
+/// and the history of the game so far, written out the same way however it was entered.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HintKey {
+    implementation: String,
+    parameters: String,
+    history: String,
+}
+
+impl HintKey {
+    pub fn new(implementation: &str, parameters: &str, history: &[Guess]) -> Self {
+        let history = history
+            .iter()
+            .map(|guess| {
+                format!(
+                    "{}:{}",
+                    guess.word.to_lowercase(),
+                    Correctness::format_mask(guess.feedback())
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        Self {
+            implementation: implementation.to_string(),
+            parameters: parameters.to_string(),
+            history,
+        }
+    }
+}
+
+/// Suggestions that have already been worked out, so that games which reach the same state (as
+/// most do early on, since every game starts from the same opener) only pay for it once.
+///
+/// Suggestions are forgotten once they are older than the time to live, so that they don't
+/// outlast a change of dictionary or answers, and the least recently used are forgotten to make
+/// room once the cache is full.
+#[derive(Debug)]
+pub struct HintCache {
+    capacity: usize,
+    ttl: Duration,
+    entries: HashMap<HintKey, Entry>,
+    /// Counts lookups, to tell which entry was used longest ago.
+    uses: u64,
+    hits: u64,
+    misses: u64,
+}
+
+#[derive(Debug)]
+struct Entry {
+    suggestion: Option<String>,
+    created: Instant,
+    used: u64,
+}
+
+impl HintCache {
+    /// A cache holding up to `capacity` suggestions for up to `ttl` each.
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity,
+            ttl,
+            entries: HashMap::new(),
+            uses: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// The suggestion for `key`, calling `suggest` to work it out unless it is in the cache.
+    pub fn get_or_insert_with(
+        &mut self,
+        key: HintKey,
+        suggest: impl FnOnce() -> Option<String>,
+    ) -> Option<String> {
+        if let Some(suggestion) = self.get(&key) {
+            return suggestion;
+        }
+        let suggestion = suggest();
+        self.insert(key, suggestion.clone());
+        suggestion
+    }
+
+    /// The suggestion for `key` if it is in the cache (which may itself be that there is none).
+    pub fn get(&mut self, key: &HintKey) -> Option<Option<String>> {
+        self.uses += 1;
+        match self.entries.get_mut(key) {
+            Some(entry) if entry.created.elapsed() < self.ttl => {
+                entry.used = self.uses;
+                self.hits += 1;
+                Some(entry.suggestion.clone())
+            }
+            _ => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Remembers the suggestion for `key`, which was worked out after looking it up with
+    /// [`get`](Self::get).
+    pub fn insert(&mut self, key: HintKey, suggestion: Option<String>) {
+        if self.capacity == 0 {
+            return;
+        }
+        if !self.entries.contains_key(&key) && self.entries.len() >= self.capacity {
+            self.evict();
+        }
+        self.entries.insert(
+            key,
+            Entry {
+                suggestion,
+                created: Instant::now(),
+                used: self.uses,
+            },
+        );
+    }
+
+    /// Makes room for one more suggestion, by forgetting those that have expired or, if none
+    /// have, the one used longest ago.
+    fn evict(&mut self) {
+        let ttl = self.ttl;
+        self.entries
+            .retain(|_, entry| entry.created.elapsed() < ttl);
+        if self.entries.len() < self.capacity {
+            return;
+        }
+        if let Some(oldest) = self
+            .entries
+            .iter()
+            .min_by_key(|(_, entry)| entry.used)
+            .map(|(key, _)| key.clone())
+        {
+            self.entries.remove(&oldest);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// How many lookups found a suggestion in the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// How many lookups had to work out the suggestion.
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+}
+
+/// What a new guesser from `factory` would suggest after `history`, or `None` if the game is
+/// already solved or no word is consistent with the feedback.
+///
+/// The guesser is shown the history one guess at a time, as if it had played the game, since
+/// guessers may keep track of how the game is going between guesses.
+pub fn suggest<G: Guesser>(
+    factory: &impl GuesserFactory<Guesser = G>,
+    history: &[Guess],
+) -> Option<String> {
+    if matches!(history.last(), Some(last) if last.mask == Correctness::SOLVED) {
+        return None;
+    }
+    // The guessers assume there is always at least one word left to pick from.
+    if !dictionary()
+        .iter()
+        .any(|&(word, _)| history.iter().all(|guess| guess.matches(word)))
+    {
+        return None;
+    }
+    let mut guesser = factory.new_guesser();
+    for i in 0..history.len() {
+        guesser.guess(&history[..i]);
+    }
+    Some(guesser.guess(history).into_owned())
+}
+
+/// The suggestion after each of `histories`, working out each different one only once and
+/// remembering them in `cache` for later batches.
+///
+/// The suggestions are made by the guessers from `factory`, which is registered as
+/// `implementation` with the given `parameters`: they are part of the key in the cache.
+pub fn suggest_all<F: GuesserFactory>(
+    factory: &F,
+    implementation: &str,
+    parameters: &str,
+    histories: &[Vec<Guess<'static>>],
+    cache: &mut HintCache,
+) -> Vec<Option<String>> {
+    histories
+        .iter()
+        .map(|history| {
+            let key = HintKey::new(implementation, parameters, history);
+            cache.get_or_insert_with(key, || suggest(factory, history))
+        })
+        .collect()
+}
+
+/// Like [`suggest_all`], but works out the suggestions on a pool of `workers` threads.
+///
+/// The cache is only locked to look suggestions up and to remember them afterwards, and each
+/// different history is still only worked out once, however many times it appears.
+pub fn suggest_parallel_with<F>(
+    factory: &F,
+    implementation: &str,
+    parameters: &str,
+    histories: &[Vec<Guess<'static>>],
+    cache: &Mutex<HintCache>,
+    workers: usize,
+) -> Vec<Option<String>>
+where
+    F: GuesserFactory + Sync,
+{
+    let keys: Vec<_> = histories
+        .iter()
+        .map(|history| HintKey::new(implementation, parameters, history))
+        .collect();
+    let mut known = HashMap::new();
+    let mut missing = Vec::new();
+    {
+        let mut cache = cache.lock().expect("the hint cache was poisoned");
+        for (key, history) in keys.iter().zip(histories) {
+            if known.contains_key(key) {
+                continue;
+            }
+            match cache.get(key) {
+                Some(suggestion) => {
+                    known.insert(key.clone(), suggestion);
+                }
+                None => {
+                    known.insert(key.clone(), None);
+                    missing.push((key.clone(), history));
+                }
+            }
+        }
+    }
+
+    let worked_out = in_parallel(
+        &missing,
+        workers,
+        || (),
+        |_, (key, history)| (key.clone(), suggest(factory, history)),
+    );
+
+    let mut cache = cache.lock().expect("the hint cache was poisoned");
+    for (key, suggestion) in worked_out {
+        cache.insert(key.clone(), suggestion.clone());
+        known.insert(key, suggestion);
+    }
+    keys.iter().map(|key| known[key].clone()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{suggest, suggest_all, suggest_parallel_with, HintCache, HintKey};
+    use crate::{algorithms::Popular, Correctness, Guess};
+    use std::borrow::Cow;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    fn guess(word: &'static str, mask: &str) -> Guess<'static> {
+        Guess {
+            word: Cow::Borrowed(word),
+            mask: Correctness::parse_mask(mask, word.len()).unwrap(),
+        }
+    }
+
+    fn key(history: &[Guess]) -> HintKey {
+        HintKey::new("popular", "", history)
+    }
+
+    #[test]
+    fn keys_are_normalised() {
+        assert_eq!(
+            key(&[guess("TARES", "xyyxx")]),
+            key(&[guess("tares", "XYYXX")])
+        );
+        assert_ne!(key(&[guess("tares", "XYYXX")]), key(&[]));
+        assert_ne!(
+            HintKey::new("popular", "", &[]),
+            HintKey::new("cache", "", &[])
+        );
+    }
+
+    #[test]
+    fn memoised() {
+        let mut cache = HintCache::new(10, Duration::from_secs(60));
+        let history = [guess("tares", "XYYXX")];
+        let first =
+            cache.get_or_insert_with(key(&history), || suggest(&Popular::default, &history));
+        let second = cache.get_or_insert_with(key(&history), || unreachable!());
+        assert_eq!(first, second);
+        assert_eq!((cache.hits(), cache.misses()), (1, 1));
+    }
+
+    #[test]
+    fn expires() {
+        let mut cache = HintCache::new(10, Duration::ZERO);
+        cache.get_or_insert_with(key(&[]), || Some("tares".to_string()));
+        let again = cache.get_or_insert_with(key(&[]), || Some("crane".to_string()));
+        assert_eq!(again.as_deref(), Some("crane"));
+        assert_eq!(cache.misses(), 2);
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut cache = HintCache::new(2, Duration::from_secs(60));
+        let histories = [
+            vec![guess("tares", "XYYXX")],
+            vec![guess("tares", "XXXXX")],
+            vec![guess("tares", "GXXXX")],
+        ];
+        let word = || Some("cigar".to_string());
+        cache.get_or_insert_with(key(&histories[0]), word);
+        cache.get_or_insert_with(key(&histories[1]), word);
+        cache.get_or_insert_with(key(&histories[0]), word);
+        cache.get_or_insert_with(key(&histories[2]), word);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.hits(), 1);
+        // The second history was used longest ago, so it was the one forgotten.
+        cache.get_or_insert_with(key(&histories[0]), word);
+        cache.get_or_insert_with(key(&histories[1]), word);
+        assert_eq!((cache.hits(), cache.misses()), (2, 4));
+    }
+
+    #[test]
+    fn no_suggestion() {
+        assert_eq!(suggest(&Popular::default, &[guess("cigar", "GGGGG")]), None);
+        let contradictory = [guess("tares", "XXXXX"), guess("tares", "GXXXX")];
+        assert!(suggest(&Popular::default, &contradictory[..1]).is_some());
+        assert_eq!(suggest(&Popular::default, &contradictory), None);
+    }
+
+    #[test]
+    fn batch() {
+        let histories = vec![
+            vec![],
+            vec![guess("tares", "XYYXX")],
+            vec![],
+            vec![guess("cigar", "GGGGG")],
+        ];
+        let mut cache = HintCache::new(10, Duration::from_secs(60));
+        let suggestions = suggest_all(&Popular::default, "popular", "", &histories, &mut cache);
+        assert_eq!(suggestions[0].as_deref(), Some("tares"));
+        assert_eq!(suggestions[0], suggestions[2]);
+        assert_eq!(suggestions[3], None);
+        assert_eq!((cache.hits(), cache.misses()), (1, 3));
+
+        let cache = Mutex::new(HintCache::new(10, Duration::from_secs(60)));
+        let parallel =
+            suggest_parallel_with(&Popular::default, "popular", "", &histories, &cache, 3);
+        assert_eq!(parallel, suggestions);
+        let cache = cache.into_inner().unwrap();
+        assert_eq!((cache.len(), cache.misses()), (3, 3));
+    }
+}
@@ -0,0 +1,264 @@
+//! The little of JSON the solver reads and writes by hand.
+
+/// How deeply arrays and objects may nest, which is far deeper than anything the solver reads
+/// needs to.
+pub const MAX_DEPTH: usize = 32;
+
+/// `s` as a JSON string, in quotes and with anything that has to be escaped escaped.
+pub fn json_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// A JSON value, as read by [`Json::parse`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Reads `text`, which must be a single JSON value. Arrays and objects may nest at most
+    /// [`MAX_DEPTH`] deep, so that text from elsewhere can't overflow the stack.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut parser = Parser {
+            text: text.as_bytes(),
+            at: 0,
+            depth: 0,
+        };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        if parser.at != parser.text.len() {
+            return Err(parser.error("the end of the text"));
+        }
+        Ok(value)
+    }
+
+    /// The value of `key`, if this is an object with it.
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Self::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+}
+
+struct Parser<'t> {
+    text: &'t [u8],
+    at: usize,
+    /// How many arrays and objects the parser is inside.
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, expected: &str) -> String {
+        format!("expected {} at byte {}", expected, self.at)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.text.get(self.at), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.at += 1;
+        }
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        let found = self.text[self.at..].starts_with(token.as_bytes());
+        if found {
+            self.at += token.len();
+        }
+        found
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_whitespace();
+        if matches!(self.text.get(self.at), Some(b'{' | b'[')) {
+            if self.depth == MAX_DEPTH {
+                return Err(format!(
+                    "arrays and objects nest more than {} deep at byte {}",
+                    MAX_DEPTH, self.at
+                ));
+            }
+            self.depth += 1;
+            let value = self.nested();
+            self.depth -= 1;
+            return value;
+        }
+        self.scalar()
+    }
+
+    /// An array or object.
+    fn nested(&mut self) -> Result<Json, String> {
+        match self.text.get(self.at) {
+            Some(b'{') => {
+                self.at += 1;
+                let mut fields = Vec::new();
+                if !self.eat("}") {
+                    loop {
+                        self.skip_whitespace();
+                        let key = self.string()?;
+                        if !self.eat(":") {
+                            return Err(self.error("':'"));
+                        }
+                        fields.push((key, self.value()?));
+                        if self.eat("}") {
+                            break;
+                        }
+                        if !self.eat(",") {
+                            return Err(self.error("',' or '}'"));
+                        }
+                    }
+                }
+                Ok(Json::Object(fields))
+            }
+            Some(b'[') => {
+                self.at += 1;
+                let mut values = Vec::new();
+                if !self.eat("]") {
+                    loop {
+                        values.push(self.value()?);
+                        if self.eat("]") {
+                            break;
+                        }
+                        if !self.eat(",") {
+                            return Err(self.error("',' or ']'"));
+                        }
+                    }
+                }
+                Ok(Json::Array(values))
+            }
+            _ => unreachable!("only called at the start of an array or object"),
+        }
+    }
+
+    fn scalar(&mut self) -> Result<Json, String> {
+        match self.text.get(self.at) {
+            Some(b'"') => self.string().map(Json::String),
+            _ if self.eat("null") => Ok(Json::Null),
+            _ if self.eat("true") => Ok(Json::Bool(true)),
+            _ if self.eat("false") => Ok(Json::Bool(false)),
+            _ => {
+                let start = self.at;
+                while matches!(
+                    self.text.get(self.at),
+                    Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9')
+                ) {
+                    self.at += 1;
+                }
+                std::str::from_utf8(&self.text[start..self.at])
+                    .ok()
+                    .and_then(|number| number.parse().ok())
+                    .map(Json::Number)
+                    .ok_or_else(|| {
+                        self.at = start;
+                        self.error("a value")
+                    })
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        if self.text.get(self.at) != Some(&b'"') {
+            return Err(self.error("a string"));
+        }
+        self.at += 1;
+        let mut bytes = Vec::new();
+        loop {
+            match self.text.get(self.at) {
+                None => return Err(self.error("'\"'")),
+                Some(b'"') => break,
+                Some(b'\\') => {
+                    self.at += 1;
+                    let escaped = match self.text.get(self.at) {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => {
+                            let hex = self.text.get(self.at + 1..self.at + 5);
+                            let code = hex
+                                .and_then(|hex| std::str::from_utf8(hex).ok())
+                                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                                .ok_or_else(|| self.error("four hex digits"))?;
+                            self.at += 4;
+                            // Words are never outside the basic plane, so halves of surrogate
+                            // pairs needn't be put back together.
+                            char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER)
+                        }
+                        _ => return Err(self.error("an escape")),
+                    };
+                    bytes.extend_from_slice(escaped.encode_utf8(&mut [0; 4]).as_bytes());
+                }
+                Some(&b) => bytes.push(b),
+            }
+            self.at += 1;
+        }
+        self.at += 1;
+        // The text as a whole is UTF-8, and strings only start and end at ASCII quotes.
+        Ok(String::from_utf8(bytes).expect("the text is UTF-8"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{json_string, Json, MAX_DEPTH};
+
+    #[test]
+    fn escapes_strings() {
+        assert_eq!(json_string("tares"), "\"tares\"");
+        assert_eq!(json_string("my \"best\"\\n"), r#""my \"best\"\\n""#);
+        assert_eq!(json_string("a\nb\u{1}"), r#""a\nb\u0001""#);
+        assert_eq!(json_string("größe"), "\"größe\"");
+    }
+
+    #[test]
+    fn parses_json() {
+        let json = Json::parse(r#" {"a": [1, -2.5e1, true, null], "b!": "x\"y"} "#);
+        assert_eq!(
+            json,
+            Ok(Json::Object(vec![
+                (
+                    "a".to_string(),
+                    Json::Array(vec![
+                        Json::Number(1.0),
+                        Json::Number(-25.0),
+                        Json::Bool(true),
+                        Json::Null
+                    ])
+                ),
+                ("b!".to_string(), Json::String("x\"y".to_string())),
+            ]))
+        );
+        assert!(Json::parse("{\"a\": }").is_err());
+        assert!(Json::parse("[1, 2").is_err());
+        assert!(Json::parse("[] []").is_err());
+    }
+
+    #[test]
+    fn limits_nesting() {
+        let nested = |depth| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        assert!(Json::parse(&nested(MAX_DEPTH)).is_ok());
+        assert!(Json::parse(&nested(MAX_DEPTH + 1)).is_err());
+    }
+}
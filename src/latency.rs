@@ -0,0 +1,134 @@
+//! How long guessers take to make each guess, by round.
+//!
+//! What makes a guesser pleasant to use interactively isn't how long it takes on average, but how
+//! long the slowest guesses take, which are nearly all second guesses: the opener is known up
+//! front, and later guesses have few candidates left to go through. So the times are kept
+//! separately for each round, and summed up as percentiles rather than an average.
+
+use std::fmt;
+use std::time::Duration;
+
+/// The time taken by every guess of many games, by round.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Latencies {
+    rounds: Vec<Vec<Duration>>,
+}
+
+/// The percentiles of some guesses' times.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Summary {
+    pub guesses: usize,
+    pub p50: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+impl Latencies {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the time each guess of a game took, in the order they were made.
+    pub fn record_game(&mut self, times: &[Duration]) {
+        if self.rounds.len() < times.len() {
+            self.rounds.resize(times.len(), Vec::new());
+        }
+        for (round, &time) in self.rounds.iter_mut().zip(times) {
+            round.push(time);
+        }
+    }
+
+    /// The most guesses any game took.
+    pub fn rounds(&self) -> usize {
+        self.rounds.len()
+    }
+
+    /// The percentiles of the `round`th guesses (the opener is the first), or of every guess if
+    /// `round` is `None`. This is `None` if there were no such guesses.
+    pub fn summary(&self, round: Option<usize>) -> Option<Summary> {
+        let mut times: Vec<_> = match round {
+            Some(round) => self.rounds.get(round.checked_sub(1)?)?.clone(),
+            None => self.rounds.concat(),
+        };
+        times.sort_unstable();
+        Some(Summary {
+            guesses: times.len(),
+            p50: percentile(&times, 50.0)?,
+            p95: percentile(&times, 95.0)?,
+            p99: percentile(&times, 99.0)?,
+            max: *times.last()?,
+        })
+    }
+}
+
+/// The `p`th percentile of `sorted`, by the nearest-rank method: the smallest time that at least
+/// `p` percent of them are no slower than.
+pub fn percentile(sorted: &[Duration], p: f64) -> Option<Duration> {
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted.get(rank.clamp(1, sorted.len().max(1)) - 1).copied()
+}
+
+impl fmt::Display for Latencies {
+    /// A table of the percentiles of each round, and of every guess, in milliseconds.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<6} {:>7} {:>9} {:>9} {:>9} {:>9}",
+            "round", "guesses", "p50 ms", "p95 ms", "p99 ms", "max ms"
+        )?;
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        let rounds = (1..=self.rounds()).map(|round| (round.to_string(), Some(round)));
+        for (label, round) in rounds.chain([("all".to_string(), None)]) {
+            if let Some(s) = self.summary(round) {
+                writeln!(
+                    f,
+                    "{:<6} {:>7} {:>9.3} {:>9.3} {:>9.3} {:>9.3}",
+                    label,
+                    s.guesses,
+                    ms(s.p50),
+                    ms(s.p95),
+                    ms(s.p99),
+                    ms(s.max)
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{percentile, Latencies};
+    use std::time::Duration;
+
+    #[test]
+    fn nearest_rank() {
+        let times: Vec<_> = (1..=100).map(Duration::from_millis).collect();
+        let p = |p| percentile(&times, p).unwrap().as_millis();
+        assert_eq!(p(50.0), 50);
+        assert_eq!(p(95.0), 95);
+        assert_eq!(p(99.0), 99);
+        assert_eq!(p(100.0), 100);
+        assert_eq!(p(0.0), 1);
+        assert_eq!(percentile(&[], 50.0), None);
+    }
+
+    #[test]
+    fn by_round() {
+        let ms = Duration::from_millis;
+        let mut latencies = Latencies::new();
+        latencies.record_game(&[ms(1), ms(100), ms(10)]);
+        latencies.record_game(&[ms(1), ms(300)]);
+        assert_eq!(latencies.rounds(), 3);
+        let second = latencies.summary(Some(2)).unwrap();
+        assert_eq!(
+            (second.guesses, second.p50, second.max),
+            (2, ms(100), ms(300))
+        );
+        assert_eq!(latencies.summary(Some(3)).unwrap().guesses, 1);
+        assert_eq!(latencies.summary(None).unwrap().guesses, 5);
+        assert_eq!(latencies.summary(Some(4)), None);
+        assert_eq!(latencies.summary(Some(0)), None);
+    }
+}
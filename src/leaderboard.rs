@@ -0,0 +1,162 @@
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+use std::time::Duration;
+
+/// The best results seen so far for each algorithm, kept in a local file so improvements can be
+/// tracked across runs.
+///
+/// Results are only comparable when they were produced with the same parameters and the same
+/// dictionary, so those are part of every entry's key.
+#[derive(Debug, Default)]
+pub struct Leaderboard {
+    entries: Vec<Entry>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub algorithm: String,
+    pub parameters: String,
+    pub dictionary: u64,
+    pub average_score: f64,
+    pub runtime: Duration,
+}
+
+impl Entry {
+    fn same_key(&self, other: &Entry) -> bool {
+        self.algorithm == other.algorithm
+            && self.parameters == other.parameters
+            && self.dictionary == other.dictionary
+    }
+}
+
+impl Leaderboard {
+    /// Reads the leaderboard from `path`, which is treated as empty if it doesn't exist yet.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e),
+        };
+        let invalid = |line: &str| {
+            io::Error::new(
+                ErrorKind::InvalidData,
+                format!("malformed leaderboard line '{}'", line),
+            )
+        };
+
+        let mut entries = Vec::new();
+        for line in contents.lines().filter(|line| !line.is_empty()) {
+            let fields: Vec<_> = line.split('\t').collect();
+            let [algorithm, parameters, dictionary, average_score, runtime] = fields[..] else {
+                return Err(invalid(line));
+            };
+            entries.push(Entry {
+                algorithm: algorithm.to_string(),
+                parameters: parameters.to_string(),
+                dictionary: u64::from_str_radix(dictionary, 16).map_err(|_| invalid(line))?,
+                average_score: average_score.parse().map_err(|_| invalid(line))?,
+                runtime: Duration::from_secs_f64(runtime.parse().map_err(|_| invalid(line))?),
+            });
+        }
+        Ok(Self { entries })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut out = String::new();
+        for e in &self.entries {
+            out += &format!(
+                "{}\t{}\t{:016x}\t{}\t{}\n",
+                e.algorithm,
+                e.parameters,
+                e.dictionary,
+                e.average_score,
+                e.runtime.as_secs_f64()
+            );
+        }
+        fs::write(path, out)
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Records the result of a run, keeping the best average score and the best runtime seen for
+    /// its key independently.
+    ///
+    /// Returns true if either of them improved.
+    pub fn record(&mut self, result: Entry) -> bool {
+        match self.entries.iter_mut().find(|e| e.same_key(&result)) {
+            Some(best) => {
+                let mut improved = false;
+                if result.average_score < best.average_score {
+                    best.average_score = result.average_score;
+                    improved = true;
+                }
+                if result.runtime < best.runtime {
+                    best.runtime = result.runtime;
+                    improved = true;
+                }
+                improved
+            }
+            None => {
+                self.entries.push(result);
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Entry, Leaderboard};
+    use std::time::Duration;
+
+    fn entry(algorithm: &str, average_score: f64, runtime: u64) -> Entry {
+        Entry {
+            algorithm: algorithm.to_string(),
+            parameters: "games=10".to_string(),
+            dictionary: 0xdead_beef,
+            average_score,
+            runtime: Duration::from_secs(runtime),
+        }
+    }
+
+    #[test]
+    fn keeps_best_of_each() {
+        let mut board = Leaderboard::default();
+        assert!(board.record(entry("cache", 3.5, 10)));
+        assert!(!board.record(entry("cache", 3.6, 11)));
+        assert!(board.record(entry("cache", 3.7, 5)));
+        assert_eq!(board.entries(), [entry("cache", 3.5, 5)]);
+    }
+
+    #[test]
+    fn keys_are_separate() {
+        let mut board = Leaderboard::default();
+        board.record(entry("cache", 3.5, 10));
+        board.record(entry("escore", 3.6, 20));
+        let mut other_dictionary = entry("cache", 4.0, 10);
+        other_dictionary.dictionary = 1;
+        assert!(board.record(other_dictionary));
+        assert_eq!(board.entries().len(), 3);
+    }
+
+    #[test]
+    fn round_trip() {
+        let path = std::env::temp_dir().join(format!("leaderboard-{}.tsv", std::process::id()));
+        let mut board = Leaderboard::default();
+        board.record(entry("cache", 3.5, 10));
+        board.record(entry("popular", 4.25, 1));
+        board.save(&path).unwrap();
+        let loaded = Leaderboard::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.entries(), board.entries());
+    }
+
+    #[test]
+    fn missing_file_is_empty() {
+        let board = Leaderboard::load("/nonexistent/leaderboard.tsv").unwrap();
+        assert!(board.entries().is_empty());
+    }
+}
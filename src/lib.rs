@@ -1,16 +1,167 @@
 #![allow(clippy::type_complexity)]
-#![allow(clippy::blocks_in_if_conditions)]
+#![allow(clippy::blocks_in_conditions)]
 
 extern crate core;
 
-use std::{borrow::Cow, collections::HashSet};
+use once_cell::sync::OnceCell;
+use profile::Duplicates;
+use std::time::{Duration, Instant};
+use std::{borrow::Cow, collections::HashSet, fmt, str::FromStr};
 
+mod adversarial;
 pub mod algorithms;
+pub mod analysis;
+pub mod answers;
+#[cfg(feature = "client")]
+pub mod client;
+#[cfg(feature = "net")]
+pub mod daily;
+pub mod doctor;
+mod error;
+mod evaluate;
+#[cfg(feature = "gpu")]
+pub mod gpu;
+pub mod hints;
+pub mod json;
+pub mod latency;
+pub mod leaderboard;
+pub mod locale;
+pub mod plugin;
+pub mod profile;
+#[cfg(feature = "pyo3")]
+mod python;
+pub mod registry;
+pub mod scenario;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod session;
+pub mod tree;
+
+pub use adversarial::AdversarialWordle;
+pub use error::WordleError;
+pub use evaluate::{
+    evaluate_all, evaluate_all_with, evaluate_parallel_with, play_parallel_with, EvaluateOptions,
+    EvaluationStats,
+};
+pub use profile::{candidate_answers, dictionary, word_len};
 
 include!(concat!(env!("OUT_DIR"), "/dictionary.rs"));
 
+/// Where the dictionary came from, and which version of it is in use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DictionaryInfo {
+    pub checksum: u64,
+    pub profile: &'static profile::Profile,
+    pub source: &'static str,
+    /// The date (YYYY-MM-DD) this version of the dictionary was generated.
+    pub generated: &'static str,
+    pub words: usize,
+}
+
+/// Describes the dictionary in use, with the [profile](profile::Profile) applied.
+pub fn dictionary_info() -> DictionaryInfo {
+    static CHECKSUM: OnceCell<u64> = OnceCell::new();
+    DictionaryInfo {
+        checksum: *CHECKSUM.get_or_init(|| profile::checksum(dictionary())),
+        profile: profile::profile(),
+        source: if profile::profile().words.is_empty() {
+            DICTIONARY_SOURCE
+        } else {
+            "A word list given at runtime"
+        },
+        generated: DICTIONARY_GENERATED,
+        words: dictionary().len(),
+    }
+}
+
+impl DictionaryInfo {
+    /// The version of the dictionary, as recorded in anything derived from it.
+    pub fn version(&self) -> String {
+        format!("{:016x}", self.checksum)
+    }
+
+    /// Fails if `version` was recorded against a different dictionary than this one.
+    pub fn check(&self, version: &str) -> Result<(), String> {
+        if version == self.version() {
+            Ok(())
+        } else {
+            Err(format!(
+                "built against dictionary {}, but this is dictionary {}",
+                version,
+                self.version()
+            ))
+        }
+    }
+}
+
+/// Whether any letter appears in `word` more than once.
+pub fn repeats_letter(word: &str) -> bool {
+    if word.is_ascii() {
+        let bytes = word.as_bytes();
+        return (1..bytes.len()).any(|i| bytes[..i].contains(&bytes[i]));
+    }
+    let letters: Vec<_> = word.chars().collect();
+    (1..letters.len()).any(|i| letters[..i].contains(&letters[i]))
+}
+
+/// How many letters `word` has, which is more than its length in bytes when it has letters
+/// outside ASCII, such as the ñ of Spanish or the ä, ö and ü of German.
+pub fn letter_count(word: &str) -> usize {
+    word.chars().count()
+}
+
+/// Whether `word` is made of `len` lowercase letters, from any alphabet.
+pub fn is_lowercase_word(word: &str, len: usize) -> bool {
+    letter_count(word) == len && word.chars().all(char::is_lowercase)
+}
+
+/// Which rules a game is played by.
+///
+/// The guessers that only ever guess words that could still be the answer (such as
+/// [`Cutoff`](algorithms::Cutoff), [`Sigmoid`](algorithms::Sigmoid) and
+/// [`Cached`](algorithms::Cached)) always play by the hard-mode rules, since such a word reuses
+/// every green and yellow letter. A [strategy](algorithms::Strategy) that guesses from the whole
+/// dictionary has to be told to play by them. [`Escore`](algorithms::Escore) doesn't once the
+/// profile [only allows some words as answers](profile::Profile::answers_only), since it then also
+/// guesses the others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GameMode {
+    /// Any word in the dictionary may be guessed.
+    #[default]
+    Normal,
+    /// Every guess has to reuse the green letters revealed so far in the same place, and the
+    /// yellow ones somewhere.
+    Hard,
+}
+
+impl FromStr for GameMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "normal" => Ok(Self::Normal),
+            "hard" => Ok(Self::Hard),
+            _ => Err(format!(
+                "unknown game mode '{}', expected normal or hard",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for GameMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Normal => "normal",
+            Self::Hard => "hard",
+        })
+    }
+}
+
 pub struct Wordle {
     dictionary: HashSet<&'static str>,
+    candidates: HashSet<&'static str>,
+    no_repeats: bool,
 }
 
 impl Default for Wordle {
@@ -22,35 +173,232 @@ impl Default for Wordle {
 impl Wordle {
     pub fn new() -> Self {
         Self {
-            dictionary: HashSet::from_iter(DICTIONARY.iter().copied().map(|(word, _)| word)),
+            dictionary: HashSet::from_iter(dictionary().iter().copied().map(|(word, _)| word)),
+            candidates: HashSet::from_iter(candidate_answers().iter().map(|&(word, _)| word)),
+            no_repeats: profile::profile().no_repeats,
         }
     }
 
-    pub fn play<G: Guesser>(&self, answer: &'static str, mut guesser: G) -> Option<usize> {
-        let mut history = Vec::new();
-        // Wordle only allows six guesses.
-        // We allow more to avoid chopping off the score distribution for stats purposes.
-        for i in 1..=32 {
+    /// Forbids (or allows) guesses that use any letter more than once.
+    pub fn with_no_repeats(mut self, no_repeats: bool) -> Self {
+        self.no_repeats = no_repeats;
+        self
+    }
+
+    /// Whether `word` is allowed as a guess.
+    pub fn is_allowed(&self, word: &str) -> bool {
+        self.dictionary.contains(word) && !(self.no_repeats && repeats_letter(word))
+    }
+
+    /// Whether `word` can be the answer, which every allowed guess can unless the profile
+    /// [only allows some of them](profile::Profile::answers_only).
+    pub fn can_be_answer(&self, word: &str) -> bool {
+        self.candidates.contains(word) && self.is_allowed(word)
+    }
+
+    pub fn play<G: Guesser>(&self, answer: &'static str, guesser: G) -> Option<usize> {
+        self.play_with_mode(answer, guesser, GameMode::Normal)
+    }
+
+    /// Plays like [`play`](Self::play), by the rules of `mode`.
+    ///
+    /// # Panics
+    ///
+    /// If the guesser breaks the rules, as when it guesses a word that isn't in the dictionary.
+    pub fn play_with_mode<G: Guesser>(
+        &self,
+        answer: &'static str,
+        guesser: G,
+        mode: GameMode,
+    ) -> Option<usize> {
+        score_or_panic(self.play_recording(answer, guesser, mode, |_, _, _| {}))
+    }
+
+    /// Plays like [`play_with_mode`](Self::play_with_mode), but returns an error rather than
+    /// panicking if the guesser breaks the rules, or
+    /// [`TooManyGuesses`](WordleError::TooManyGuesses) if it never finds the answer.
+    pub fn try_play<G: Guesser>(
+        &self,
+        answer: &'static str,
+        guesser: G,
+        mode: GameMode,
+    ) -> Result<usize, WordleError> {
+        self.play_recording(answer, guesser, mode, |_, _, _| {})
+    }
+
+    /// Plays like [`play_with_mode`](Self::play_with_mode), keeping track of everything that
+    /// happened in the game.
+    ///
+    /// This also works out how many words were left after each guess, which takes about as long
+    /// as a fast guesser takes to play the game, so [`play`](Self::play) is better when only the
+    /// score matters.
+    pub fn play_game<G: Guesser>(
+        &self,
+        answer: &'static str,
+        guesser: G,
+        mode: GameMode,
+    ) -> GameResult {
+        let mut result = GameResult {
+            answer,
+            guesses: Vec::new(),
+            masks: Vec::new(),
+            remaining: Vec::new(),
+            times: Vec::new(),
+        };
+        score_or_panic(
+            self.play_recording(answer, guesser, mode, |guess, mask, time| {
+                result.guesses.push(guess.to_string());
+                result.masks.push(mask);
+                result.times.push(time);
+            }),
+        );
+        let mut candidates: Vec<_> = self
+            .candidates
+            .iter()
+            .copied()
+            .filter(|word| letter_count(word) == letter_count(answer) && self.can_be_answer(word))
+            .collect();
+        for (word, &mask) in result.guesses.iter().zip(&result.masks) {
+            let guess = Guess {
+                word: Cow::Borrowed(word),
+                mask,
+            };
+            candidates.retain(|candidate| guess.matches(candidate));
+            result.remaining.push(candidates.len());
+        }
+        result
+    }
+
+    /// Plays a game, calling `record` with each guess, its feedback and how long it took to make.
+    fn play_recording<G: Guesser>(
+        &self,
+        answer: &'static str,
+        mut guesser: G,
+        mode: GameMode,
+        mut record: impl FnMut(&str, Mask, Duration),
+    ) -> Result<usize, WordleError> {
+        let mut history: Vec<Guess> = Vec::new();
+        for i in 1..=MAX_GUESSES {
+            let start = Instant::now();
             let guess = guesser.guess(&history);
+            let time = start.elapsed();
+            self.check_rules(&guess, letter_count(answer), &history, mode)?;
             if guess == answer {
+                record(&guess, Correctness::SOLVED, time);
                 guesser.finish(i);
-                return Some(i);
+                return Ok(i);
+            }
+            if !self.dictionary.contains(&*guess) {
+                return Err(WordleError::UnknownWord(guess.into_owned()));
             }
-            assert!(
-                self.dictionary.contains(&*guess),
-                "guess '{}' is not in the dictionary",
-                guess
-            );
             let correctness = Correctness::compute(answer, &guess);
+            record(&guess, correctness, time);
             history.push(Guess {
-                word: Cow::Owned(guess),
+                word: guess,
                 mask: correctness,
             });
         }
-        None
+        Err(WordleError::TooManyGuesses(MAX_GUESSES))
+    }
+
+    /// Checks that `guess` has `len` letters and can be guessed after `history` by the rules of
+    /// `mode`, other than being in the dictionary.
+    fn check_rules(
+        &self,
+        guess: &str,
+        len: usize,
+        history: &[Guess],
+        mode: GameMode,
+    ) -> Result<(), WordleError> {
+        if letter_count(guess) != len {
+            return Err(WordleError::WrongLength {
+                word: guess.to_string(),
+                expected: len,
+            });
+        }
+        if self.no_repeats && repeats_letter(guess) {
+            return Err(WordleError::RepeatsLetter(guess.to_string()));
+        }
+        if mode == GameMode::Hard && !history.iter().all(|g| g.allows_in_hard_mode(guess)) {
+            return Err(WordleError::BreaksHardMode(guess.to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// How many guesses a game is played for before giving up. Wordle only allows six, but we allow
+/// more to avoid chopping off the score distribution for stats purposes.
+const MAX_GUESSES: usize = 32;
+
+/// The score of a game that may not have found the answer, panicking if the guesser broke the
+/// rules.
+fn score_or_panic(result: Result<usize, WordleError>) -> Option<usize> {
+    match result {
+        Ok(score) => Some(score),
+        Err(WordleError::TooManyGuesses(_)) => None,
+        Err(e) => panic!("{}", e),
     }
 }
 
+/// Everything that happened in one game, as played by [`Wordle::play_game`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameResult {
+    pub answer: &'static str,
+    /// Every guess made, in order, ending with the answer if it was found.
+    pub guesses: Vec<String>,
+    /// The feedback each guess got.
+    pub masks: Vec<Mask>,
+    /// How many words that can be the answer were still consistent with all the feedback after
+    /// each guess (which is 1 once the answer is found).
+    pub remaining: Vec<usize>,
+    /// How long the guesser took to make each guess.
+    pub times: Vec<Duration>,
+}
+
+impl GameResult {
+    /// Whether the answer was found, however many guesses it took.
+    pub fn solved(&self) -> bool {
+        self.masks.last() == Some(&Correctness::SOLVED)
+    }
+
+    /// How many guesses it took to find the answer, if it was found.
+    pub fn score(&self) -> Option<usize> {
+        self.solved().then_some(self.guesses.len())
+    }
+
+    /// Whether the answer was found within the six guesses Wordle allows.
+    pub fn won(&self) -> bool {
+        self.score().is_some_and(|score| score <= 6)
+    }
+
+    /// How long the guesser took over the whole game.
+    pub fn total_time(&self) -> Duration {
+        self.times.iter().sum()
+    }
+
+    /// The grid players share once they are done, as if this was the puzzle with the given
+    /// number (see [`share_grid`]).
+    pub fn share_string(&self, puzzle: usize) -> String {
+        share_grid(puzzle, letter_count(self.answer), &self.masks)
+    }
+}
+
+/// The grid players share once they are done, which shows how a game went without giving the
+/// answer away: a header like `Wordle 942 4/6` (or `X/6` if it wasn't won within six guesses),
+/// followed by a row of squares for the feedback to each guess, for words of `len` letters.
+pub fn share_grid(puzzle: usize, len: usize, masks: &[Mask]) -> String {
+    let score = match masks.len() {
+        n if n <= 6 && masks.last() == Some(&Correctness::SOLVED) => n.to_string(),
+        _ => "X".to_string(),
+    };
+    let mut grid = format!("Wordle {} {}/6", puzzle, score);
+    for mask in masks {
+        grid.push('\n');
+        grid.push_str(&Correctness::emoji_row(&mask[..len]));
+    }
+    grid
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Correctness {
     /// Green
@@ -61,8 +409,55 @@ pub enum Correctness {
     Wrong,
 }
 
+/// The colours feedback is shown in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Palette {
+    /// Green and yellow, as in the game by default.
+    #[default]
+    Standard,
+    /// Orange and blue, as in the game's high contrast mode, which tell apart more easily for
+    /// colour-blind players.
+    HighContrast,
+}
+
+static PALETTE: OnceCell<Palette> = OnceCell::new();
+
+/// Picks the colours feedback is shown in from now on. Fails if a different palette has already
+/// been used.
+pub fn set_palette(palette: Palette) -> Result<(), String> {
+    match PALETTE.try_insert(palette) {
+        Ok(_) => Ok(()),
+        Err((current, palette)) if *current == palette => Ok(()),
+        Err(_) => Err("feedback has already been shown in a different palette".to_string()),
+    }
+}
+
+/// The colours feedback is shown in, which are the standard ones unless others were picked before
+/// any feedback was shown.
+pub fn palette() -> Palette {
+    *PALETTE.get_or_init(Palette::default)
+}
+
+/// The longest words that can be played.
+pub const MAX_WORD_LEN: usize = 11;
+
+/// The feedback for every letter of a guess. Words shorter than [`MAX_WORD_LEN`] only use the
+/// start of it, and the rest is always [`Correctness::Correct`], so that a fully correct guess
+/// gets [`Correctness::SOLVED`] whatever its length.
+pub type Mask = [Correctness; MAX_WORD_LEN];
+
 impl Correctness {
-    fn is_misplaced(letter: u8, answer: &str, used: &mut [bool; 5]) -> bool {
+    /// The mask of a guess that is the answer.
+    pub const SOLVED: Mask = [Correctness::Correct; MAX_WORD_LEN];
+
+    /// Pads the feedback for the letters of a word out to a [`Mask`].
+    pub fn mask(letters: &[Self]) -> Mask {
+        let mut mask = Self::SOLVED;
+        mask[..letters.len()].copy_from_slice(letters);
+        mask
+    }
+
+    fn is_misplaced(letter: u8, answer: &str, used: &mut [bool; MAX_WORD_LEN]) -> bool {
         answer.bytes().enumerate().any(|(i, a)| {
             if a == letter && !used[i] {
                 used[i] = true;
@@ -72,10 +467,49 @@ impl Correctness {
         })
     }
 
-    pub fn compute(answer: &str, guess: &str) -> [Self; 5] {
-        assert_eq!(answer.len(), 5);
-        assert_eq!(guess.len(), 5);
-        let mut c = [Correctness::Wrong; 5];
+    /// The feedback for `guess` when the answer is `answer`, following the
+    /// [duplicate-letter rule](profile::Duplicates) of the profile in use.
+    ///
+    /// # Panics
+    ///
+    /// If the words aren't the same length. See [`try_compute`](Self::try_compute) for checking
+    /// them first.
+    pub fn compute(answer: &str, guess: &str) -> Mask {
+        Self::compute_with(answer, guess, profile::duplicates())
+    }
+
+    /// Like [`compute`](Self::compute), but returns an error rather than panicking (or getting
+    /// nonsense) when the words aren't lowercase letters of the same, playable, length.
+    pub fn try_compute(answer: &str, guess: &str) -> Result<Mask, WordleError> {
+        for word in [answer, guess] {
+            if !word.chars().all(char::is_lowercase) {
+                return Err(WordleError::NotLowercase(word.to_string()));
+            }
+        }
+        let len = letter_count(answer);
+        if letter_count(guess) != len || len > MAX_WORD_LEN {
+            return Err(WordleError::WrongLength {
+                word: guess.to_string(),
+                expected: len.min(MAX_WORD_LEN),
+            });
+        }
+        Ok(Self::compute(answer, guess))
+    }
+
+    /// The feedback for `guess` when the answer is `answer`, following `duplicates` rather than
+    /// the rule of the profile in use.
+    ///
+    /// Letters are compared as whole characters, so words with letters outside ASCII (such as ñ)
+    /// get one feedback per letter rather than one per byte.
+    pub fn compute_with(answer: &str, guess: &str, duplicates: Duplicates) -> Mask {
+        if !(answer.is_ascii() && guess.is_ascii()) {
+            let answer: Vec<_> = answer.chars().collect();
+            let guess: Vec<_> = guess.chars().collect();
+            return Self::compute_letters(&answer, &guess, duplicates);
+        }
+        assert_eq!(answer.len(), guess.len());
+        let mut c = Self::SOLVED;
+        c[..guess.len()].fill(Correctness::Wrong);
         let answer_bytes = answer.as_bytes();
         let guess_bytes = guess.as_bytes();
         // Array indexed by lowercase ascii letters
@@ -85,9 +519,15 @@ impl Correctness {
         for ((&answer, &guess), c) in answer_bytes.iter().zip(guess_bytes).zip(c.iter_mut()) {
             if answer == guess {
                 *c = Correctness::Correct
-            } else {
-                // If the letter does not match, count it as misplaced
-                misplaced[(answer - b'a') as usize] += 1;
+            }
+            // Count the letters that can make a guessed letter misplaced
+            let count = &mut misplaced[(answer - b'a') as usize];
+            match duplicates {
+                Duplicates::Official if answer != guess => *count += 1,
+                Duplicates::Official => {}
+                Duplicates::CountCapped => *count += 1,
+                // More than any guess can use up.
+                Duplicates::PerLetter => *count = u8::MAX,
             }
         }
         // Check all of the non matching letters if they are misplaced
@@ -102,7 +542,51 @@ impl Correctness {
         c
     }
 
-    pub fn pack(c: &[Correctness; 5]) -> u8 {
+    /// [`compute_with`](Self::compute_with) for words of any alphabet, counting the letters that
+    /// can make a guessed letter misplaced in a list rather than an array indexed by letter.
+    fn compute_letters(answer: &[char], guess: &[char], duplicates: Duplicates) -> Mask {
+        assert_eq!(answer.len(), guess.len());
+        let mut c = Self::SOLVED;
+        c[..guess.len()].fill(Correctness::Wrong);
+        let mut misplaced: Vec<(char, u8)> = Vec::with_capacity(answer.len());
+
+        for ((&answer, &guess), c) in answer.iter().zip(guess).zip(c.iter_mut()) {
+            if answer == guess {
+                *c = Correctness::Correct
+            }
+            let count = match misplaced.iter_mut().find(|(letter, _)| *letter == answer) {
+                Some((_, count)) => count,
+                None => {
+                    misplaced.push((answer, 0));
+                    &mut misplaced.last_mut().unwrap().1
+                }
+            };
+            match duplicates {
+                Duplicates::Official if answer != guess => *count += 1,
+                Duplicates::Official => {}
+                Duplicates::CountCapped => *count += 1,
+                Duplicates::PerLetter => *count = u8::MAX,
+            }
+        }
+        for (&guess, c) in guess.iter().zip(c.iter_mut()) {
+            if *c != Correctness::Wrong {
+                continue;
+            }
+            if let Some((_, count)) = misplaced
+                .iter_mut()
+                .find(|(letter, count)| *letter == guess && *count > 0)
+            {
+                *c = Correctness::Misplaced;
+                *count -= 1;
+            }
+        }
+
+        c
+    }
+
+    /// Numbers the feedback for the letters of a word from 0 up to (not including)
+    /// [`Correctness::count`], in the order of [`Correctness::patterns`].
+    pub fn pack(c: &[Correctness]) -> usize {
         c.iter().fold(0, |acc, c| {
             acc * 3
                 + match c {
@@ -113,34 +597,184 @@ impl Correctness {
         })
     }
 
-    pub fn patterns() -> impl Iterator<Item = [Self; 5]> {
-        itertools::iproduct!(
-            [Self::Correct, Self::Misplaced, Self::Wrong],
-            [Self::Correct, Self::Misplaced, Self::Wrong],
-            [Self::Correct, Self::Misplaced, Self::Wrong],
-            [Self::Correct, Self::Misplaced, Self::Wrong],
-            [Self::Correct, Self::Misplaced, Self::Wrong]
-        )
-        .map(|(a, b, c, d, e)| [a, b, c, d, e])
+    /// [`Correctness::pack`] of the feedback for `guess` when the answer is `answer`.
+    pub fn compute_packed(answer: &str, guess: &str) -> usize {
+        Self::pack(&Self::compute(answer, guess)[..letter_count(guess)])
+    }
+
+    /// How many different masks words of `len` letters can get.
+    pub fn count(len: usize) -> usize {
+        3usize.pow(len as u32)
+    }
+
+    /// Parses a mask written as one letter per position, as reported by a real game:
+    /// `G`/`C` for green, `Y`/`M` for yellow and `X`/`W`/`B` for gray (case-insensitive).
+    ///
+    /// The mask must have `len` letters, as many as the word it is feedback for.
+    pub fn parse_mask(mask: &str, len: usize) -> Option<Mask> {
+        if len > MAX_WORD_LEN || mask.chars().count() != len {
+            return None;
+        }
+        let mut c = Self::SOLVED;
+        for (c, letter) in c.iter_mut().zip(mask.chars()) {
+            *c = match letter.to_ascii_uppercase() {
+                'G' | 'C' => Correctness::Correct,
+                'Y' | 'M' => Correctness::Misplaced,
+                'X' | 'W' | 'B' => Correctness::Wrong,
+                _ => return None,
+            };
+        }
+        Some(c)
+    }
+
+    /// The square used for this correctness in the game's share grid, in the
+    /// [palette](palette) in use.
+    pub fn emoji(&self) -> char {
+        self.emoji_in(palette())
+    }
+
+    /// The squares for the feedback to a word in the game's share grid, e.g. 🟩🟨⬛⬛🟨, in the
+    /// [palette](palette) in use.
+    pub fn emoji_row(feedback: &[Self]) -> String {
+        feedback.iter().map(Self::emoji).collect()
+    }
+
+    /// The square used for this correctness in the game's share grid, in `palette`.
+    pub fn emoji_in(&self, palette: Palette) -> char {
+        match (self, palette) {
+            (Correctness::Correct, Palette::Standard) => '🟩',
+            (Correctness::Misplaced, Palette::Standard) => '🟨',
+            (Correctness::Correct, Palette::HighContrast) => '🟧',
+            (Correctness::Misplaced, Palette::HighContrast) => '🟦',
+            (Correctness::Wrong, _) => '⬛',
+        }
+    }
+
+    /// Formats the feedback for the letters of a word the way [`Correctness::parse_mask`] reads
+    /// it, e.g. `GYXXY`.
+    pub fn format_mask(mask: &[Self]) -> String {
+        mask.iter()
+            .map(|c| match c {
+                Correctness::Correct => 'G',
+                Correctness::Misplaced => 'Y',
+                Correctness::Wrong => 'X',
+            })
+            .collect()
+    }
+
+    /// Every mask words of `len` letters could be given, in the order they are
+    /// [packed](Self::pack) in, including those no guess can actually get (see
+    /// [`possible_patterns`](Self::possible_patterns)).
+    pub fn patterns(len: usize) -> impl Iterator<Item = Mask> {
+        (0..Self::count(len)).map(move |mut packed| {
+            let mut mask = Self::SOLVED;
+            for c in mask[..len].iter_mut().rev() {
+                *c = [Self::Correct, Self::Misplaced, Self::Wrong][packed % 3];
+                packed /= 3;
+            }
+            mask
+        })
+    }
+
+    /// Every mask that a guess of `len` letters can actually get, in the same order as
+    /// [`patterns`](Self::patterns).
+    ///
+    /// All but one letter being correct and the other one misplaced is impossible, since the
+    /// misplaced letter would have to be where one of the correct ones is. Leaving those out saves
+    /// the algorithms that go through every pattern from looking for candidates that give them.
+    pub fn possible_patterns(len: usize) -> &'static [Mask] {
+        static OTHER_LENGTHS: [OnceCell<Vec<Mask>>; MAX_WORD_LEN + 1] =
+            [const { OnceCell::new() }; MAX_WORD_LEN + 1];
+        if len == 5 {
+            return &POSSIBLE_PATTERNS;
+        }
+        OTHER_LENGTHS[len].get_or_init(|| {
+            Self::patterns(len)
+                .filter(|mask| Self::is_possible(&mask[..len]))
+                .collect()
+        })
+    }
+
+    const fn is_possible(mask: &[Self]) -> bool {
+        let (mut correct, mut misplaced, mut i) = (0, 0, 0);
+        while i < mask.len() {
+            match mask[i] {
+                Self::Correct => correct += 1,
+                Self::Misplaced => misplaced += 1,
+                Self::Wrong => {}
+            }
+            i += 1;
+        }
+        !(misplaced == 1 && correct + 1 == mask.len())
     }
 }
 
-pub const MAX_MASK_ENUM: usize = 3 * 3 * 3 * 3 * 3;
+/// How many masks a five-letter guess can get: 3^5 less the five with one letter misplaced and
+/// the rest correct.
+const POSSIBLE_PATTERN_COUNT: usize = 238;
+
+/// [`Correctness::possible_patterns`] for five-letter words, the most common case, worked out
+/// when compiling.
+static POSSIBLE_PATTERNS: [Mask; POSSIBLE_PATTERN_COUNT] = {
+    let mut table = [Correctness::SOLVED; POSSIBLE_PATTERN_COUNT];
+    let (mut packed, mut n) = (0, 0);
+    while packed < 243 {
+        let mut mask = Correctness::SOLVED;
+        let (mut rest, mut i) = (packed, 5);
+        while i > 0 {
+            i -= 1;
+            mask[i] = match rest % 3 {
+                0 => Correctness::Correct,
+                1 => Correctness::Misplaced,
+                _ => Correctness::Wrong,
+            };
+            rest /= 3;
+        }
+        if Correctness::is_possible(mask.split_at(5).0) {
+            table[n] = mask;
+            n += 1;
+        }
+        packed += 1;
+    }
+    assert!(n == POSSIBLE_PATTERN_COUNT);
+    table
+};
+
+/// See <https://prng.di.unimi.it/splitmix64.c>.
+pub(crate) fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e3779b97f4a7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
 
+#[derive(Debug, Clone)]
 pub struct Guess<'a> {
     pub word: Cow<'a, str>,
-    pub mask: [Correctness; 5],
+    pub mask: Mask,
 }
 
 impl Guess<'_> {
+    /// The feedback for each letter of the guess, without the padding [`Mask`] has.
+    pub fn feedback(&self) -> &[Correctness] {
+        &self.mask[..letter_count(&self.word)]
+    }
+
     pub fn matches(&self, word: &str) -> bool {
         // Check if the guess would be possible to observe when `word` is the correct answer.
         // This is equivalent to
         //     Correctness::compute(word, &self.word) == self.mask
         // without _necessarily_ computing the full mask for the tested word
-        assert_eq!(word.len(), 5);
-        assert_eq!(self.word.len(), 5);
-        let mut used = [false; 5];
+        if profile::duplicates() != Duplicates::Official
+            || !(word.is_ascii() && self.word.is_ascii())
+        {
+            // The shortcuts below only hold for the official rule, and letters that are one byte.
+            assert_eq!(letter_count(word), letter_count(&self.word));
+            return Correctness::compute(word, &self.word) == self.mask;
+        }
+        assert_eq!(word.len(), self.word.len());
+        let mut used = [false; MAX_WORD_LEN];
 
         // Check Correct letters
         for (i, (a, g)) in word.bytes().zip(self.word.bytes()).enumerate() {
@@ -167,25 +801,115 @@ impl Guess<'_> {
         // The rest will be all correctly Wrong letters
         true
     }
+
+    /// Check if `word` may be guessed after this guess in hard mode, where every green letter has
+    /// to be reused in the same position and every yellow letter has to be reused somewhere.
+    pub fn allows_in_hard_mode(&self, word: &str) -> bool {
+        let letters: Vec<_> = word.chars().collect();
+        assert_eq!(letters.len(), letter_count(&self.word));
+        let mut used = [false; MAX_WORD_LEN];
+        for (i, (&w, g)) in letters.iter().zip(self.word.chars()).enumerate() {
+            if self.mask[i] == Correctness::Correct {
+                if w != g {
+                    return false;
+                }
+                used[i] = true;
+            }
+        }
+        self.word
+            .chars()
+            .zip(self.mask.iter())
+            .filter(|(_, m)| **m == Correctness::Misplaced)
+            .all(|(g, _)| {
+                letters.iter().enumerate().any(|(i, &w)| {
+                    if w == g && !used[i] {
+                        used[i] = true;
+                        return true;
+                    }
+                    false
+                })
+            })
+    }
 }
 
 pub trait Guesser {
-    fn guess(&mut self, history: &[Guess]) -> String;
+    /// The next word to guess after `history`.
+    ///
+    /// Guessers picking from the dictionary should borrow the word they pick rather than copy
+    /// it, so that playing a game doesn't allocate for every guess.
+    fn guess(&mut self, history: &[Guess]) -> Cow<'static, str>;
     fn finish(&self, _guesses: usize) {}
+
+    /// Gets ready to play another game, as if it had just been created, while keeping whatever
+    /// doesn't depend on the game (such as what was worked out from the dictionary up front).
+    ///
+    /// Guessers that keep track of how the game is going must override this, since the default
+    /// does nothing.
+    fn reset(&mut self) {}
+}
+
+impl<G: Guesser> Guesser for &mut G {
+    fn guess(&mut self, history: &[Guess]) -> Cow<'static, str> {
+        (**self).guess(history)
+    }
+
+    fn finish(&self, guesses: usize) {
+        (**self).finish(guesses)
+    }
+
+    fn reset(&mut self) {
+        (**self).reset()
+    }
+}
+
+impl<G: Guesser + ?Sized> Guesser for Box<G> {
+    fn guess(&mut self, history: &[Guess]) -> Cow<'static, str> {
+        (**self).guess(history)
+    }
+
+    fn finish(&self, guesses: usize) {
+        (**self).finish(guesses)
+    }
+
+    fn reset(&mut self) {
+        (**self).reset()
+    }
 }
 
-impl Guesser for fn(history: &[Guess]) -> String {
-    fn guess(&mut self, history: &[Guess]) -> String {
+impl Guesser for fn(history: &[Guess]) -> Cow<'static, str> {
+    fn guess(&mut self, history: &[Guess]) -> Cow<'static, str> {
         (*self)(history)
     }
 }
 
+/// Creates a fresh guesser for every game, so that guessers which need configuring (or share
+/// some context) can be played as easily as ones that implement `Default`.
+///
+/// Any `Fn() -> impl Guesser` is a factory, including `G::default`.
+pub trait GuesserFactory {
+    type Guesser: Guesser;
+
+    fn new_guesser(&self) -> Self::Guesser;
+}
+
+impl<F, G> GuesserFactory for F
+where
+    F: Fn() -> G,
+    G: Guesser,
+{
+    type Guesser = G;
+
+    fn new_guesser(&self) -> G {
+        self()
+    }
+}
+
 #[cfg(test)]
 macro_rules! guesser {
     (|$history:ident| $impl:block) => {{
         struct G;
         impl $crate::Guesser for G {
-            fn guess(&mut self, $history: &[Guess]) -> String {
+            fn guess(&mut self, $history: &[Guess]) -> std::borrow::Cow<'static, str> {
                 $impl
             }
         }
@@ -193,15 +917,15 @@ macro_rules! guesser {
     }};
 }
 
-/// maps a list of C,M,W tokens into an array of Correctness variants
+/// maps a list of C,M,W tokens into a Mask
 #[cfg(test)]
 macro_rules! mask {
     (C) => { $crate::Correctness::Correct };
     (M) => { $crate::Correctness::Misplaced };
     (W) => { $crate::Correctness::Wrong };
-    ($($c:tt)+) => {[
-        $(mask!($c)),+
-    ]}
+    ($($c:tt)+) => {
+        $crate::Correctness::mask(&[$(mask!($c)),+])
+    }
 }
 
 #[cfg(test)]
@@ -240,15 +964,73 @@ mod tests {
             check!("aaabb" + [C M W W W] disallows "accaa");
             check!("tares" + [W M M W W] disallows "brink");
         }
+
+        #[test]
+        fn other_alphabets() {
+            check!("señor" + [M W C C W] allows "niños");
+            check!("señor" + [M W C C W] disallows "senos");
+            check!("bären" + [C W C C C] allows "baren");
+            check!("baren" + [C W C C C] disallows "baren");
+        }
+    }
+
+    mod hard_mode {
+        use crate::Guess;
+        use std::borrow::Cow;
+
+        fn guess(word: &'static str, mask: crate::Mask) -> Guess<'static> {
+            Guess {
+                word: Cow::Borrowed(word),
+                mask,
+            }
+        }
+
+        #[test]
+        fn greens_stay_in_place() {
+            let g = guess("tares", mask![C W W W W]);
+            assert!(g.allows_in_hard_mode("thing"));
+            assert!(!g.allows_in_hard_mode("night"));
+        }
+
+        #[test]
+        fn yellows_are_reused() {
+            let g = guess("tares", mask![W M W W W]);
+            assert!(g.allows_in_hard_mode("plaid"));
+            assert!(g.allows_in_hard_mode("about"));
+            assert!(!g.allows_in_hard_mode("pluck"));
+        }
+
+        #[test]
+        fn repeated_yellows_are_all_reused() {
+            let g = guess("llama", mask![M M W W W]);
+            assert!(g.allows_in_hard_mode("hello"));
+            assert!(!g.allows_in_hard_mode("plaid"));
+        }
+
+        #[test]
+        fn grays_may_be_reused() {
+            let g = guess("tares", mask![W W W W W]);
+            assert!(g.allows_in_hard_mode("tares"));
+        }
+
+        #[test]
+        fn other_alphabets() {
+            let g = guess("señor", mask![W W C W W]);
+            assert!(g.allows_in_hard_mode("niños"));
+            assert!(!g.allows_in_hard_mode("senos"));
+            let g = guess("ärger", mask![M W W W W]);
+            assert!(g.allows_in_hard_mode("bären"));
+            assert!(!g.allows_in_hard_mode("baren"));
+        }
     }
 
     mod game {
-        use crate::{Guess, Wordle};
+        use crate::{Guess, Guesser, Wordle};
 
         #[test]
         fn play_first_guess_is_correct() {
             let w = Wordle::new();
-            let guesser = guesser!(|_history| { "right".to_string() });
+            let guesser = guesser!(|_history| { "right".into() });
             assert_eq!(w.play("right", guesser), Some(1));
         }
 
@@ -257,9 +1039,9 @@ mod tests {
             let w = Wordle::new();
             let guesser = guesser!(|history| {
                 if history.len() == 1 {
-                    return "right".to_string();
+                    return "right".into();
                 }
-                "wrong".to_string()
+                "wrong".into()
             });
 
             assert_eq!(w.play("right", guesser), Some(2));
@@ -270,9 +1052,9 @@ mod tests {
             let w = Wordle::new();
             let guesser = guesser!(|history| {
                 if history.len() == 2 {
-                    return "right".to_string();
+                    return "right".into();
                 }
-                "wrong".to_string()
+                "wrong".into()
             });
 
             assert_eq!(w.play("right", guesser), Some(3));
@@ -283,9 +1065,9 @@ mod tests {
             let w = Wordle::new();
             let guesser = guesser!(|history| {
                 if history.len() == 3 {
-                    return "right".to_string();
+                    return "right".into();
                 }
-                "wrong".to_string()
+                "wrong".into()
             });
 
             assert_eq!(w.play("right", guesser), Some(4));
@@ -296,9 +1078,9 @@ mod tests {
             let w = Wordle::new();
             let guesser = guesser!(|history| {
                 if history.len() == 4 {
-                    return "right".to_string();
+                    return "right".into();
                 }
-                "wrong".to_string()
+                "wrong".into()
             });
 
             assert_eq!(w.play("right", guesser), Some(5));
@@ -309,9 +1091,9 @@ mod tests {
             let w = Wordle::new();
             let guesser = guesser!(|history| {
                 if history.len() == 5 {
-                    return "right".to_string();
+                    return "right".into();
                 }
-                "wrong".to_string()
+                "wrong".into()
             });
 
             assert_eq!(w.play("right", guesser), Some(6));
@@ -320,15 +1102,197 @@ mod tests {
         #[test]
         fn all_wrong_guesses_should_terminate() {
             let w = Wordle::new();
-            let guesser = guesser!(|_history| { "wrong".to_string() });
+            let guesser = guesser!(|_history| { "wrong".into() });
 
             assert_eq!(w.play("right", guesser), None);
         }
+
+        #[test]
+        fn no_repeats() {
+            let w = Wordle::new().with_no_repeats(true);
+            assert!(w.is_allowed("right"));
+            assert!(!w.is_allowed("sissy"));
+            let guesser = guesser!(|_history| { "right".into() });
+            assert_eq!(w.play("right", guesser), Some(1));
+        }
+
+        #[test]
+        fn hard_mode() {
+            use crate::algorithms::{Cutoff, Sigmoid};
+            use crate::GameMode;
+
+            let w = Wordle::new();
+            for answer in ["cigar", "rebut", "sissy", "humph", "awake"] {
+                assert!(w
+                    .play_with_mode(answer, Cutoff::new(), GameMode::Hard)
+                    .is_some());
+                assert!(w
+                    .play_with_mode(answer, Sigmoid::new(), GameMode::Hard)
+                    .is_some());
+            }
+            assert_eq!("hard".parse(), Ok(GameMode::Hard));
+            assert_eq!(GameMode::Normal.to_string(), "normal");
+        }
+
+        #[test]
+        #[should_panic(expected = "hard mode")]
+        fn hard_mode_rejects_unused_letters() {
+            let w = Wordle::new();
+            // After tares, the c and the r of cigar have to be reused.
+            let guesser = guesser!(|history| {
+                match history.len() {
+                    0 => "tares".into(),
+                    _ => "bound".into(),
+                }
+            });
+            w.play_with_mode("cigar", guesser, crate::GameMode::Hard);
+        }
+
+        #[test]
+        fn game_result() {
+            use crate::algorithms::Cutoff;
+            use crate::{Correctness, GameMode};
+
+            let w = Wordle::new();
+            let result = w.play_game("cigar", Cutoff::new(), GameMode::Normal);
+            assert_eq!(result.answer, "cigar");
+            assert_eq!(result.guesses.last().map(String::as_str), Some("cigar"));
+            assert_eq!(result.score(), w.play("cigar", Cutoff::new()));
+            assert!(result.solved() && result.won());
+            assert_eq!(result.masks.len(), result.guesses.len());
+            assert_eq!(result.times.len(), result.guesses.len());
+            assert_eq!(result.masks.last(), Some(&Correctness::SOLVED));
+            assert_eq!(
+                result.masks[0],
+                Correctness::compute("cigar", &result.guesses[0])
+            );
+            assert_eq!(result.remaining.last(), Some(&1));
+            assert!(result.remaining.windows(2).all(|w| w[0] >= w[1]));
+            let share = result.share_string(0);
+            let lines: Vec<_> = share.lines().collect();
+            assert_eq!(lines[0], format!("Wordle 0 {}/6", result.guesses.len()));
+            assert_eq!(lines.len(), result.guesses.len() + 1);
+            assert_eq!(lines.last(), Some(&"🟩🟩🟩🟩🟩"));
+            // The grid never gives the answer away.
+            assert!(!share.contains("cigar"));
+        }
+
+        #[test]
+        fn game_result_unsolved() {
+            let w = Wordle::new();
+            let result = w.play_game(
+                "cigar",
+                guesser!(|_history| { "right".into() }),
+                crate::GameMode::Normal,
+            );
+            assert_eq!(result.guesses.len(), 32);
+            assert_eq!(result.score(), None);
+            assert!(!result.solved() && !result.won());
+            assert!(result.remaining.iter().all(|&r| r == result.remaining[0]));
+            assert!(result.share_string(7).starts_with("Wordle 7 X/6\n"));
+        }
+
+        #[test]
+        fn reset_plays_like_new() {
+            use crate::algorithms::{Configured, Escore, Popular};
+
+            fn check<G: Guesser>(mut reused: G, new: impl Fn() -> G) {
+                let w = Wordle::new();
+                for answer in ["cigar", "rebut", "sissy"] {
+                    reused.reset();
+                    assert_eq!(w.play(answer, &mut reused), w.play(answer, new()));
+                }
+            }
+            check(Escore::new(), Escore::new);
+            check(Popular::new(), Popular::new);
+            check(Configured::default(), Configured::default);
+        }
+
+        #[test]
+        #[should_panic(expected = "repeats a letter")]
+        fn no_repeats_rejects_guess() {
+            let w = Wordle::new().with_no_repeats(true);
+            let guesser = guesser!(|_history| { "sissy".into() });
+            w.play("right", guesser);
+        }
+
+        #[test]
+        fn try_play_reports_broken_rules() {
+            use crate::{GameMode, WordleError};
+
+            let w = Wordle::new();
+            macro_rules! play {
+                ($word:literal) => {
+                    w.try_play(
+                        "right",
+                        guesser!(|_history| { $word.into() }),
+                        GameMode::Normal,
+                    )
+                };
+            }
+            assert_eq!(play!("right"), Ok(1));
+            assert_eq!(
+                play!("xxxxx"),
+                Err(WordleError::UnknownWord("xxxxx".into()))
+            );
+            assert_eq!(
+                play!("rights"),
+                Err(WordleError::WrongLength {
+                    word: "rights".into(),
+                    expected: 5
+                })
+            );
+            assert_eq!(play!("wrong"), Err(WordleError::TooManyGuesses(32)));
+            let w = Wordle::new().with_no_repeats(true);
+            let guesser = guesser!(|_history| { "sissy".into() });
+            assert_eq!(
+                w.try_play("right", guesser, GameMode::Normal),
+                Err(WordleError::RepeatsLetter("sissy".into()))
+            );
+        }
     }
 
     mod compute {
         use crate::Correctness;
 
+        #[test]
+        fn try_compute() {
+            use crate::WordleError;
+
+            assert_eq!(
+                Correctness::try_compute("abcde", "abcde"),
+                Ok(Correctness::SOLVED)
+            );
+            assert_eq!(
+                Correctness::try_compute("abcde", "abcd"),
+                Err(WordleError::WrongLength {
+                    word: "abcd".into(),
+                    expected: 5
+                })
+            );
+            assert_eq!(
+                Correctness::try_compute("abcde", "ABCDE"),
+                Err(WordleError::NotLowercase("ABCDE".into()))
+            );
+            assert!(Correctness::try_compute("abcdefghijkl", "abcdefghijkl").is_err());
+            // Letters outside ASCII are one letter each, however many bytes they take.
+            assert_eq!(
+                Correctness::try_compute("niños", "señor"),
+                Ok(mask!(M W C C W))
+            );
+            assert_eq!(
+                Correctness::try_compute("niños", "niñoss"),
+                Err(WordleError::WrongLength {
+                    word: "niñoss".into(),
+                    expected: 5
+                })
+            );
+            assert_eq!(
+                Correctness::try_compute("niños", "NIÑOS"),
+                Err(WordleError::NotLowercase("NIÑOS".into()))
+            );
+        }
+
         #[test]
         fn all_green() {
             assert_eq!(Correctness::compute("abcde", "abcde"), mask!(C C C C C))
@@ -373,5 +1337,149 @@ mod tests {
         fn some_green_some_yellow2() {
             assert_eq!(Correctness::compute("abcde", "aacde"), mask!(C W C C C))
         }
+
+        #[test]
+        fn duplicate_rules() {
+            use crate::profile::Duplicates;
+
+            let rule = |answer, guess, duplicates| {
+                let mask = Correctness::compute_with(answer, guess, duplicates);
+                Correctness::format_mask(&mask[..5])
+            };
+            // The answer has one 'e', which the last 'e' finds in place.
+            assert_eq!(rule("abide", "eerie", Duplicates::Official), "XXXYG");
+            assert_eq!(rule("abide", "eerie", Duplicates::PerLetter), "YYXYG");
+            assert_eq!(rule("abide", "eerie", Duplicates::CountCapped), "YXXYG");
+            // Two 'l's guessed where the answer has one, elsewhere.
+            assert_eq!(rule("plant", "hello", Duplicates::Official), "XXYXX");
+            assert_eq!(rule("plant", "hello", Duplicates::PerLetter), "XXYYX");
+            assert_eq!(rule("plant", "hello", Duplicates::CountCapped), "XXYXX");
+            // Without repeated letters, every rule agrees.
+            for duplicates in [Duplicates::PerLetter, Duplicates::CountCapped] {
+                assert_eq!(rule("cigar", "tares", duplicates), "XYYXX");
+            }
+        }
+
+        #[test]
+        fn other_alphabets() {
+            assert_eq!(Correctness::compute("niños", "señor"), mask!(M W C C W));
+            assert_eq!(Correctness::compute("bären", "ärger"), mask!(M M W C W));
+            // An umlaut makes it another letter.
+            assert_eq!(Correctness::compute("bären", "baren"), mask!(C W C C C));
+            assert_eq!(Correctness::compute_packed("niños", "niños"), 0);
+        }
+
+        #[test]
+        fn any_alphabet_agrees_with_ascii() {
+            use crate::profile::Duplicates;
+
+            let words = [
+                "abide", "eerie", "plant", "hello", "cigar", "tares", "llama",
+            ];
+            for duplicates in [
+                Duplicates::Official,
+                Duplicates::PerLetter,
+                Duplicates::CountCapped,
+            ] {
+                for answer in words {
+                    for guess in words {
+                        let letters = |word: &str| word.chars().collect::<Vec<_>>();
+                        assert_eq!(
+                            Correctness::compute_letters(
+                                &letters(answer),
+                                &letters(guess),
+                                duplicates
+                            ),
+                            Correctness::compute_with(answer, guess, duplicates),
+                            "{} against {} under {}",
+                            guess,
+                            answer,
+                            duplicates
+                        );
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn six_letters() {
+            assert_eq!(Correctness::compute("planet", "plates"), mask!(C C C M C W))
+        }
+
+        #[test]
+        fn packed_in_pattern_order() {
+            for len in [3, 5, 6] {
+                for (i, pattern) in Correctness::patterns(len).enumerate() {
+                    assert_eq!(Correctness::pack(&pattern[..len]), i);
+                }
+                assert_eq!(Correctness::patterns(len).count(), Correctness::count(len));
+            }
+        }
+
+        #[test]
+        fn possible_patterns() {
+            for len in [3, 5, 6] {
+                let possible: Vec<_> = Correctness::patterns(len)
+                    .filter(|pattern| Correctness::possible_patterns(len).contains(pattern))
+                    .collect();
+                assert_eq!(possible, Correctness::possible_patterns(len));
+                assert_eq!(possible.len(), Correctness::count(len) - len);
+            }
+            assert!(!Correctness::possible_patterns(5).contains(&mask!(C C M C C)));
+            assert!(Correctness::possible_patterns(5).contains(&mask!(C C M C W)));
+            assert!(Correctness::possible_patterns(5).contains(&mask!(C C M C M)));
+        }
+    }
+
+    mod parse_mask {
+        use crate::{Correctness, Palette};
+
+        #[test]
+        fn colours() {
+            assert_eq!(Correctness::parse_mask("GYXXY", 5), Some(mask!(C M W W M)))
+        }
+
+        #[test]
+        fn letters() {
+            assert_eq!(Correctness::parse_mask("cmwbw", 5), Some(mask!(C M W W W)))
+        }
+
+        #[test]
+        fn wrong_length() {
+            assert_eq!(Correctness::parse_mask("GYXX", 5), None);
+            assert_eq!(Correctness::parse_mask("GYXXYY", 5), None);
+            assert_eq!(
+                Correctness::parse_mask("GYXXYY", 6),
+                Some(mask!(C M W W M M))
+            );
+            assert_eq!(Correctness::parse_mask("GGGGGGGGGGGG", 12), None);
+        }
+
+        #[test]
+        fn round_trip() {
+            for pattern in Correctness::patterns(5) {
+                let formatted = Correctness::format_mask(&pattern[..5]);
+                assert_eq!(Correctness::parse_mask(&formatted, 5), Some(pattern));
+            }
+        }
+
+        #[test]
+        fn emoji() {
+            assert_eq!(Correctness::emoji_row(&mask!(C M W W M)[..5]), "🟩🟨⬛⬛🟨")
+        }
+
+        #[test]
+        fn high_contrast() {
+            let row: String = mask!(C M W W M)[..5]
+                .iter()
+                .map(|c| c.emoji_in(Palette::HighContrast))
+                .collect();
+            assert_eq!(row, "🟧🟦⬛⬛🟦")
+        }
+
+        #[test]
+        fn unknown_letter() {
+            assert_eq!(Correctness::parse_mask("GYXXZ", 5), None)
+        }
     }
 }
@@ -0,0 +1,403 @@
+//! Translations of what the interactive commands (assist and host) say to the player.
+//!
+//! Messages are looked up by [`Message`] in the table of the [locale](set_locale) in use, and
+//! their arguments are filled into numbered placeholders (`{0}`, `{1}`, ...), so that a
+//! translation can put them in whatever order its grammar needs.
+use crate::Correctness;
+use once_cell::sync::OnceCell;
+use std::fmt::{self, Write};
+use std::str::FromStr;
+
+/// A language the interactive commands can talk in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    En,
+    De,
+}
+
+impl Locale {
+    /// The locale asked for by the `LC_ALL`, `LC_MESSAGES` or `LANG` environment variables (in
+    /// that order), if it is one there is a translation for.
+    pub fn from_env() -> Option<Self> {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| {
+                // e.g. de_DE.UTF-8
+                let language = value.split(['_', '.', '@']).next()?;
+                language.to_ascii_lowercase().parse().ok()
+            })
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "en" => Ok(Self::En),
+            "de" => Ok(Self::De),
+            _ => Err(format!("unknown locale '{}', expected en or de", s)),
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::En => "en",
+            Self::De => "de",
+        })
+    }
+}
+
+static LOCALE: OnceCell<Locale> = OnceCell::new();
+
+/// Picks the language to talk in from now on. Fails if something has already been said in a
+/// different one.
+pub fn set_locale(locale: Locale) -> Result<(), String> {
+    match LOCALE.try_insert(locale) {
+        Ok(_) => Ok(()),
+        Err((current, locale)) if *current == locale => Ok(()),
+        Err(_) => Err("messages have already been shown in a different locale".to_string()),
+    }
+}
+
+/// The language in use, which is English unless another one was picked before anything was said.
+pub fn locale() -> Locale {
+    *LOCALE.get_or_init(Locale::default)
+}
+
+/// Everything the interactive commands say to the player. The comment on each is the English
+/// text, with the meaning of its placeholders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Message {
+    /// How to use the assist command.
+    AssistHelp,
+    /// solved in {guesses}!
+    Solved,
+    /// try '{word}' ({candidates} candidates left)
+    Try,
+    /// no words match that feedback, try undo
+    NoMatches,
+    /// '{word}' tests {letters}
+    Probe,
+    /// no untested letter tells the candidates apart
+    NoInformativeLetter,
+    /// test '{letter}': {percent}% chance the answer has it ({bits} bits)
+    TestLetter,
+    /// forgot '{word}'
+    Forgot,
+    /// nothing to undo
+    NothingToUndo,
+    /// saved to '{file}'
+    Saved,
+    /// could not save to '{file}': {error}
+    CouldNotSave,
+    /// note: '{file}' was saved using the {implementation} implementation
+    SavedByOtherImplementation,
+    /// note: '{file}' was {error}
+    SavedWithOtherDictionary,
+    /// loaded '{file}'
+    Loaded,
+    /// could not load '{file}': {error}
+    CouldNotLoad,
+    /// there is no suggestion to apply feedback to
+    NoSuggestion,
+    /// '{feedback}' is not valid feedback, e.g. GYXXY
+    InvalidFeedback,
+    /// '{word}' is not in the dictionary
+    NotInDictionary,
+    /// '{played}' compared to the suggested '{suggested}':
+    Compare,
+    /// How much information each word gives: {played} vs {suggested} bits ({difference})
+    CompareInformation,
+    /// How many guesses each word is expected to take: {played} vs {suggested} ({difference})
+    CompareExpectedGuesses,
+    /// there is no puzzle {puzzle}, pick 0 to {last}
+    NoSuchPuzzle,
+    /// puzzle {puzzle} is not family-friendly
+    NotFamilyFriendly,
+    /// Guess the word in six tries, the clock is ticking!
+    HostIntro,
+    /// solved '{answer}' in {seconds}s
+    HostSolved,
+    /// the word was '{answer}'
+    HostAnswer,
+    /// the {implementation} solver took {guesses} guesses in {seconds}s
+    SolverTook,
+    /// the {implementation} solver failed to guess it
+    SolverFailed,
+    /// Wordle {puzzle}: press enter to see each of the {implementation} solver's guesses, or type quit
+    TodayIntro,
+    /// {letter} correct in position {position}
+    LetterCorrect,
+    /// {letter} present elsewhere
+    LetterMisplaced,
+    /// {letter} not in the word
+    LetterWrong,
+    /// '{word}': {description}
+    Feedback,
+}
+
+impl Message {
+    /// Every message, for checking the translations.
+    pub const ALL: [Message; 33] = [
+        Message::AssistHelp,
+        Message::Solved,
+        Message::Try,
+        Message::NoMatches,
+        Message::Probe,
+        Message::NoInformativeLetter,
+        Message::TestLetter,
+        Message::Forgot,
+        Message::NothingToUndo,
+        Message::Saved,
+        Message::CouldNotSave,
+        Message::SavedByOtherImplementation,
+        Message::SavedWithOtherDictionary,
+        Message::Loaded,
+        Message::CouldNotLoad,
+        Message::NoSuggestion,
+        Message::InvalidFeedback,
+        Message::NotInDictionary,
+        Message::Compare,
+        Message::CompareInformation,
+        Message::CompareExpectedGuesses,
+        Message::NoSuchPuzzle,
+        Message::NotFamilyFriendly,
+        Message::HostIntro,
+        Message::HostSolved,
+        Message::HostAnswer,
+        Message::SolverTook,
+        Message::SolverFailed,
+        Message::TodayIntro,
+        Message::LetterCorrect,
+        Message::LetterMisplaced,
+        Message::LetterWrong,
+        Message::Feedback,
+    ];
+
+    /// The text of this message in `locale`, with its placeholders still in it.
+    pub fn template(self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::En => english(self),
+            Locale::De => german(self),
+        }
+    }
+}
+
+/// The text of `message` in the [locale](locale) in use, with `args` filled into its
+/// placeholders.
+pub fn message(message: Message, args: &[&dyn fmt::Display]) -> String {
+    fill(message.template(locale()), args)
+}
+
+/// Describes the feedback for `word` in words, one letter at a time, for players who can't see
+/// the colours (e.g. "C correct in position 1, R present elsewhere, A not in the word, ...").
+pub fn describe(word: &str, mask: &[Correctness]) -> String {
+    word.chars()
+        .zip(mask)
+        .enumerate()
+        .map(|(i, (letter, c))| {
+            let letter = letter.to_ascii_uppercase();
+            match c {
+                Correctness::Correct => message(Message::LetterCorrect, &[&letter, &(i + 1)]),
+                Correctness::Misplaced => message(Message::LetterMisplaced, &[&letter]),
+                Correctness::Wrong => message(Message::LetterWrong, &[&letter]),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Replaces every `{n}` in `template` with the `n`th of `args`.
+fn fill(template: &str, args: &[&dyn fmt::Display]) -> String {
+    let mut filled = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        filled.push_str(&rest[..start]);
+        let placeholder = rest[start + 1..]
+            .find('}')
+            .and_then(|end| Some((end, rest[start + 1..start + 1 + end].parse::<usize>().ok()?)));
+        match placeholder {
+            Some((end, n)) => {
+                let arg = args.get(n).expect("missing message argument");
+                write!(filled, "{}", arg).expect("writing to a String cannot fail");
+                rest = &rest[start + end + 2..];
+            }
+            None => {
+                filled.push('{');
+                rest = &rest[start + 1..];
+            }
+        }
+    }
+    filled.push_str(rest);
+    filled
+}
+
+fn english(message: Message) -> &'static str {
+    match message {
+        Message::AssistHelp => {
+            "\
+Enter the feedback for each guess as one letter per position:
+  G (green), Y (yellow) or X (gray), e.g. GYXXY
+Commands:
+  <feedback>         you played the suggested word
+  <word> <feedback>  you played a different word (and see how it compares)
+  hint               which letters are most worth testing next
+  probe              a word that tests as many of those letters as possible
+  undo               forget the last guess
+  save <file>        save this game to continue it later
+  load <file>        continue a saved game
+  quit               stop assisting"
+        }
+        Message::Solved => "solved in {0}!",
+        Message::Try => "try '{0}' ({1} candidates left)",
+        Message::NoMatches => "no words match that feedback, try undo",
+        Message::Probe => "'{0}' tests {1}",
+        Message::NoInformativeLetter => "no untested letter tells the candidates apart",
+        Message::TestLetter => "test '{0}': {1}% chance the answer has it ({2} bits)",
+        Message::Forgot => "forgot '{0}'",
+        Message::NothingToUndo => "nothing to undo",
+        Message::Saved => "saved to '{0}'",
+        Message::CouldNotSave => "could not save to '{0}': {1}",
+        Message::SavedByOtherImplementation => "note: '{0}' was saved using the {1} implementation",
+        Message::SavedWithOtherDictionary => "note: '{0}' was {1}",
+        Message::Loaded => "loaded '{0}'",
+        Message::CouldNotLoad => "could not load '{0}': {1}",
+        Message::NoSuggestion => "there is no suggestion to apply feedback to",
+        Message::InvalidFeedback => "'{0}' is not valid feedback, e.g. GYXXY",
+        Message::NotInDictionary => "'{0}' is not in the dictionary",
+        Message::Compare => "'{0}' compared to the suggested '{1}':",
+        Message::CompareInformation => "  information       {0} vs {1} bits ({2})",
+        Message::CompareExpectedGuesses => "  expected guesses  {0} vs {1} ({2})",
+        Message::NoSuchPuzzle => "there is no puzzle {0}, pick 0 to {1}",
+        Message::NotFamilyFriendly => "puzzle {0} is not family-friendly",
+        Message::HostIntro => "Guess the word in six tries, the clock is ticking!",
+        Message::HostSolved => "solved '{0}' in {1}s",
+        Message::HostAnswer => "the word was '{0}'",
+        Message::SolverTook => "the {0} solver took {1} guesses in {2}s",
+        Message::SolverFailed => "the {0} solver failed to guess it",
+        Message::TodayIntro => {
+            "Wordle {0}: press enter to see each of the {1} solver's guesses, or type quit"
+        }
+        Message::LetterCorrect => "{0} correct in position {1}",
+        Message::LetterMisplaced => "{0} present elsewhere",
+        Message::LetterWrong => "{0} not in the word",
+        Message::Feedback => "'{0}': {1}",
+    }
+}
+
+fn german(message: Message) -> &'static str {
+    match message {
+        Message::AssistHelp => {
+            "\
+Gib die Rückmeldung zu jedem Versuch mit einem Buchstaben pro Stelle ein:
+  G (grün), Y (gelb) oder X (grau), z. B. GYXXY
+Befehle:
+  <Rückmeldung>         du hast das vorgeschlagene Wort gespielt
+  <Wort> <Rückmeldung>  du hast ein anderes Wort gespielt (mit Vergleich)
+  hint                  welche Buchstaben sich als Nächstes am meisten lohnen
+  probe                 ein Wort, das möglichst viele dieser Buchstaben testet
+  undo                  den letzten Versuch vergessen
+  save <Datei>          dieses Spiel speichern, um es später fortzusetzen
+  load <Datei>          ein gespeichertes Spiel fortsetzen
+  quit                  beenden"
+        }
+        Message::Solved => "in {0} Versuchen gelöst!",
+        Message::Try => "versuch '{0}' (noch {1} Kandidaten)",
+        Message::NoMatches => "kein Wort passt zu dieser Rückmeldung, versuch undo",
+        Message::Probe => "'{0}' testet {1}",
+        Message::NoInformativeLetter => "kein ungetesteter Buchstabe unterscheidet die Kandidaten",
+        Message::TestLetter => "teste '{0}': die Lösung enthält ihn zu {1} % ({2} Bit)",
+        Message::Forgot => "'{0}' vergessen",
+        Message::NothingToUndo => "nichts rückgängig zu machen",
+        Message::Saved => "in '{0}' gespeichert",
+        Message::CouldNotSave => "konnte nicht in '{0}' speichern: {1}",
+        Message::SavedByOtherImplementation => {
+            "Hinweis: '{0}' wurde mit der Implementierung {1} gespeichert"
+        }
+        Message::SavedWithOtherDictionary => "Hinweis: '{0}': {1}",
+        Message::Loaded => "'{0}' geladen",
+        Message::CouldNotLoad => "konnte '{0}' nicht laden: {1}",
+        Message::NoSuggestion => "es gibt keinen Vorschlag, zu dem die Rückmeldung gehören könnte",
+        Message::InvalidFeedback => "'{0}' ist keine gültige Rückmeldung, z. B. GYXXY",
+        Message::NotInDictionary => "'{0}' steht nicht im Wörterbuch",
+        Message::Compare => "'{0}' im Vergleich zum vorgeschlagenen '{1}':",
+        Message::CompareInformation => "  Information         {0} vs. {1} Bit ({2})",
+        Message::CompareExpectedGuesses => "  erwartete Versuche  {0} vs. {1} ({2})",
+        Message::NoSuchPuzzle => "es gibt kein Rätsel {0}, wähle 0 bis {1}",
+        Message::NotFamilyFriendly => "Rätsel {0} ist nicht familienfreundlich",
+        Message::HostIntro => "Errate das Wort in sechs Versuchen, die Uhr tickt!",
+        Message::HostSolved => "'{0}' in {1} s gelöst",
+        Message::HostAnswer => "das Wort war '{0}'",
+        Message::SolverTook => "der Löser {0} brauchte {1} Versuche in {2} s",
+        Message::SolverFailed => "der Löser {0} hat es nicht erraten",
+        Message::TodayIntro => {
+            "Wordle {0}: drücke Enter, um jeden Versuch des Lösers {1} zu sehen, oder gib quit ein"
+        }
+        Message::LetterCorrect => "{0} richtig an Stelle {1}",
+        Message::LetterMisplaced => "{0} kommt an anderer Stelle vor",
+        Message::LetterWrong => "{0} kommt nicht vor",
+        Message::Feedback => "'{0}': {1}",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{describe, fill, Locale, Message};
+    use crate::Correctness;
+
+    /// The placeholders in `template`, in order.
+    fn placeholders(template: &str) -> Vec<usize> {
+        let mut found: Vec<usize> = template
+            .split('{')
+            .skip(1)
+            .filter_map(|s| s.split('}').next()?.parse().ok())
+            .collect();
+        found.sort_unstable();
+        found
+    }
+
+    #[test]
+    fn fills_placeholders() {
+        assert_eq!(
+            fill("try '{0}' ({1} left)", &[&"crane", &12]),
+            "try 'crane' (12 left)"
+        );
+        assert_eq!(fill("{1} before {0}", &[&"a", &"b"]), "b before a");
+        assert_eq!(fill("{not} a {placeholder", &[]), "{not} a {placeholder");
+    }
+
+    #[test]
+    fn translations_take_the_same_arguments() {
+        for message in Message::ALL {
+            assert_eq!(
+                placeholders(message.template(Locale::De)),
+                placeholders(message.template(Locale::En)),
+                "{:?}",
+                message
+            );
+        }
+    }
+
+    #[test]
+    fn describes_feedback() {
+        assert_eq!(
+            describe("tares", &Correctness::parse_mask("GYXXG", 5).unwrap()[..5]),
+            "T correct in position 1, A present elsewhere, R not in the word, \
+             E not in the word, S correct in position 5"
+        );
+    }
+
+    #[test]
+    fn parse() {
+        for locale in [Locale::En, Locale::De] {
+            assert_eq!(locale.to_string().parse(), Ok(locale));
+        }
+        assert!("fr".parse::<Locale>().is_err());
+    }
+}
@@ -1,63 +1,2745 @@
-use clap::{ArgEnum, Parser};
-use wordle_solver::{algorithms, Guesser};
-
-const GAMES: &str = include_str!("../answers.txt");
+use clap::{Parser, Subcommand};
+use std::borrow::Cow;
+use std::collections::hash_map::RandomState;
+use std::fs::File;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use wordle_solver::{
+    algorithms,
+    answers::Answers,
+    candidate_answers, dictionary, dictionary_info,
+    hints::{self, HintCache},
+    json::json_string,
+    latency::Latencies,
+    leaderboard::{Entry, Leaderboard},
+    letter_count,
+    locale::{describe, message, set_locale, Locale, Message},
+    play_parallel_with,
+    profile::{
+        cache_dictionaries_in, default_cache_dir, fnv1a, set_profile, Dictionary, Duplicates,
+        Profile, Spelling,
+    },
+    registry::{Info, Registry},
+    session::Session,
+    set_palette,
+    tree::Tree,
+    word_len, Correctness, EvaluateOptions, EvaluationStats, GameMode, GameResult, Guess, Guesser,
+    GuesserFactory, Palette,
+};
 
 /// Simple program to greet a person
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
 struct Args {
-    #[clap(short, long, arg_enum, default_value = "cache")]
-    implementation: Implementation,
+    /// Which guesser to play: one of the built-in implementations (see --list-implementations),
+    /// or the name of a loaded plugin [default: cache]
+    #[clap(short, long)]
+    implementation: Option<String>,
+
+    /// List the implementations that can be picked with --implementation, with how fast they are
+    /// and how well they score
+    #[clap(long)]
+    list_implementations: bool,
 
     #[clap(short, long)]
     games: Option<usize>,
+
+    /// Only play the official puzzle with this number
+    #[clap(long, conflicts_with_all = &["games", "date"])]
+    puzzle: Option<usize>,
+
+    /// Only play the official puzzle from this day (YYYY-MM-DD)
+    #[clap(long, parse(try_from_str = parse_date), conflicts_with = "games")]
+    date: Option<usize>,
+
+    /// Don't print answers or guessed words, only the feedback for each guess
+    #[clap(long)]
+    no_spoilers: bool,
+
+    /// Print the grid players share for each game instead: a header like "Wordle 942 4/6",
+    /// followed by the feedback to each guess as coloured squares
+    #[clap(long)]
+    share: bool,
+
+    /// Show feedback in orange and blue instead of green and yellow, like the game's high
+    /// contrast mode
+    #[clap(long)]
+    high_contrast: bool,
+
+    /// Describe feedback in words (e.g. "T correct in position 1, A present elsewhere, ...")
+    /// instead of coloured squares in the assist and host commands, for use with a screen reader
+    #[clap(long)]
+    screen_reader: bool,
+
+    /// Read what is typed into the assist and host commands from this file instead, echoing it,
+    /// to replay a conversation (see the scenario module)
+    #[clap(long, hide = true)]
+    scripted_input: Option<PathBuf>,
+
+    /// Which language the assist and host commands talk in: en or de [default: from the LANG
+    /// environment variable, or en]
+    #[clap(long)]
+    locale: Option<Locale>,
+
+    /// How to report the games played: text, or json for one event per line (game_started,
+    /// guess_made, game_finished and run_finished) to feed into a log pipeline
+    #[clap(long, default_value = "text")]
+    log_format: LogFormat,
+
+    /// How to print the games played: plain, or json or csv for a record of each game (its
+    /// answer, guesses, feedback, score and time) followed by a summary, to load into other
+    /// tools. With csv, the summary goes to standard error so that the rest is a single table
+    #[clap(long, default_value = "plain")]
+    output: OutputFormat,
+
+    /// Write how the candidates were split up by the feedback to every guess in every game to
+    /// this file, as JSON
+    #[clap(long)]
+    distributions: Option<PathBuf>,
+
+    /// Record the average score and runtime of this run in the given leaderboard file
+    #[clap(long)]
+    leaderboard: Option<PathBuf>,
+
+    /// Use the guesser from this plugin library instead of a built-in implementation
+    #[cfg(feature = "plugins")]
+    #[clap(long)]
+    plugin: Option<PathBuf>,
+
+    /// Use a guesser following the strategy in this file instead of a built-in implementation
+    #[clap(long)]
+    strategy: Option<PathBuf>,
+
+    /// Replay the strategy tree in this file (see the compile command) instead of a built-in
+    /// implementation
+    #[clap(long)]
+    tree: Option<PathBuf>,
+
+    /// Use the guesser defined by this rhai script instead of a built-in implementation
+    #[cfg(feature = "scripting")]
+    #[clap(long)]
+    script: Option<PathBuf>,
+
+    /// Which spellings to expect: us, uk (British spellings are more likely) or merged (either is
+    /// as likely as the other)
+    #[clap(long, default_value = "us")]
+    dictionary: Spelling,
+
+    /// Never guess, suggest or pick as the answer any slurs or crude words
+    #[clap(long)]
+    family_friendly: bool,
+
+    /// Treat plurals and past tenses as unlikely answers, as in the official puzzles, while still
+    /// guessing them when they give the most information
+    #[clap(long)]
+    deprioritise_inflections: bool,
+
+    /// Treat the words in this file (such as the answers to past puzzles) as unlikely answers,
+    /// while still guessing them when they give the most information
+    #[clap(long)]
+    exclude_used: Option<PathBuf>,
+
+    /// Play the variant where neither the guesses nor the answer may use a letter more than once
+    #[clap(long)]
+    no_repeats: bool,
+
+    /// Which rules to play by when playing the answers: normal, or hard (where every guess has to
+    /// reuse the letters revealed so far), failing the run if the guesser breaks them
+    #[clap(long, default_value = "normal")]
+    mode: GameMode,
+
+    /// Play the answers on as many threads as there are cores, each with its own guesser. The
+    /// games are shown in the same order as without it, once they have all been played. Plugins
+    /// and scripts can't be played this way
+    #[clap(long)]
+    parallel: bool,
+
+    /// How to score guesses that repeat a letter, to match the game being played: official,
+    /// per-letter (yellow whenever the answer has the letter) or count-capped (at most as many
+    /// yellows as the answer has copies, not counting greens against that)
+    #[clap(long, default_value = "official")]
+    duplicates: Duplicates,
+
+    /// Use the words in this file instead of the official dictionary, one per line and optionally
+    /// followed by how common each is. They may have up to 11 letters, as long as they all have
+    /// the same number
+    #[clap(long)]
+    dictionary_file: Option<PathBuf>,
+
+    /// Use the answers in this file, in order, instead of the official ones (or instead of every
+    /// word in --dictionary-file)
+    #[clap(long)]
+    answers_file: Option<PathBuf>,
+
+    /// Only expect the answers (the official ones, or those in --answers-file) to be the answer,
+    /// guessing the rest of the dictionary only to narrow them down, as Wordle itself allows far
+    /// more guesses than it has answers
+    #[clap(long)]
+    answers_only: bool,
+
+    /// Build the dictionary from scratch, rather than reading the one built for the same options
+    /// in an earlier run from the user's cache directory (and keeping it there)
+    #[clap(long)]
+    no_dictionary_cache: bool,
+
+    /// The power the sigmoid and cache implementations raise the chance of a word being the
+    /// answer to, when weighing guessing a likely answer against gaining information: 0 treats
+    /// every candidate alike, and higher values discount the unlikely ones more [default: 1]
+    #[clap(long, parse(try_from_str = parse_lambda))]
+    lambda: Option<f64>,
+
+    /// Limit each game to this many seconds, by having the escore, sigmoid and cache
+    /// implementations consider fewer candidates once a guess has used up its share of the time
+    #[clap(long, parse(try_from_str = parse_seconds))]
+    time_per_game: Option<Duration>,
+
+    /// The share of the candidates left (likeliest first, and at least 20) that the cutoff and
+    /// cache implementations consider as guesses, which the calibrate command can tune
+    /// [default: 0.3333]
+    #[clap(long)]
+    search_fraction: Option<f64>,
+
+    /// How many of the candidates left the cutoff and cache implementations consider as guesses
+    /// depending on the round and how much is left to find out, as comma-separated clauses like
+    /// "round 2 all, below 3 bits 0.1 min 5, 0.33"
+    #[clap(long, conflicts_with = "search-fraction")]
+    search_schedule: Option<algorithms::SearchSchedule>,
+
+    /// The most candidates the optimal implementation searches exactly for the best guess,
+    /// guessing like escore while there are more [default: 128]
+    #[clap(long)]
+    max_exact: Option<usize>,
+
+    /// How many of the guesses that give the most information on their own the twoply
+    /// implementation looks two guesses ahead for [default: 10]
+    #[clap(long)]
+    lookahead: Option<usize>,
+
+    /// How the guessers add up probabilities and entropies: naive, or compensated to get the same
+    /// guesses whatever order the candidates are in and on every platform (a little slower)
+    #[clap(long, default_value = "naive")]
+    summation: algorithms::Summation,
+
+    /// Which floating point type the escore, sigmoid and cache implementations work out how likely
+    /// each feedback is in: f64, or f32 to be faster on large candidate sets at the cost of an
+    /// occasional different guess (see the compare-precision command)
+    #[clap(long, default_value = "f64")]
+    precision: algorithms::Precision,
+
+    /// How the cache implementation lays out its table of masks: guess-major or candidate-major
+    /// [default: guess-major]
+    #[clap(long)]
+    cache_layout: Option<algorithms::CacheLayout>,
+
+    /// The most memory the cache implementation's table of masks may take, like 512M or 2G:
+    /// past it, only the rows that get used are allocated, or failing that the table is kept in a
+    /// memory-mapped file [default: no limit, which takes 170M with the official dictionary]
+    #[clap(long, parse(try_from_str = parse_bytes))]
+    max_cache_mem: Option<usize>,
+
+    /// Read the cache implementation's masks from a file written by the precompute command,
+    /// rather than working them out as they are needed [default: where precompute writes them,
+    /// if they are there]
+    #[clap(long)]
+    pattern_file: Option<PathBuf>,
+
+    /// After playing, print how often the cache implementation found masks already worked out,
+    /// how many candidates were scored per guess and how many feedbacks they could get, and how
+    /// often the search was cut short
+    #[clap(long)]
+    telemetry: bool,
+
+    /// Print the K guesses the escore, sigmoid and cache implementations scored best every turn:
+    /// how much information each is expected to give, its chance of being the answer and the
+    /// score it is expected to lead to, along with how many candidates were left
+    #[clap(long, value_name = "K")]
+    explain: Option<usize>,
+
+    /// After playing, print how the scores were spread out as well as their average: the median,
+    /// 95th percentile and share of games not solved in six guesses, and a bar chart of how many
+    /// games took each number of guesses
+    #[clap(long)]
+    stats: bool,
+
+    #[clap(subcommand)]
+    command: Option<Command>,
 }
 
-#[derive(ArgEnum, Debug, Clone, Copy)]
-enum Implementation {
-    Unoptimised,
-    Allocs,
-    Vecrem,
-    Precalc,
-    Weight,
-    Enum,
-    Cutoff,
-    Sigmoid,
-    Escore,
-    Popular,
-    Cache,
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Recommend guesses for a game played elsewhere, based on the feedback you enter
+    Assist,
+    /// Play a game yourself, timing every guess against the solver
+    Host {
+        /// Which official puzzle to play, instead of a random one
+        #[clap(long)]
+        puzzle: Option<usize>,
+    },
+    /// Play today's puzzle (or the given one) with the solver, fetching it from the New York Times
+    /// as the built-in answers only go so far, showing one guess at a time, and print the share
+    /// grid at the end
+    #[cfg(feature = "net")]
+    Today {
+        /// Which official puzzle to play, instead of today's
+        #[clap(long)]
+        puzzle: Option<usize>,
+    },
+    /// Answer requests for guesses over HTTP: POST /solve with the game so far as JSON, e.g.
+    /// {"history": [{"guess": "tares", "mask": "XYYXX"}]}, and optionally the "algorithm" to guess
+    /// with and a "session" to keep its guesser in between requests
+    #[cfg(feature = "server")]
+    Serve {
+        /// The address to listen on
+        #[clap(long, default_value = "127.0.0.1:8080")]
+        addr: String,
+        /// How many requests to answer at once [default: one for each CPU, and at least 4]
+        #[clap(long)]
+        workers: Option<usize>,
+        /// How many requests a second each client (by IP address) may make, in bursts of as many
+        /// as that allows in a second [default: unlimited]
+        #[clap(long)]
+        rate_limit: Option<wordle_solver::server::RateLimit>,
+    },
+    /// Check that the solver still performs as well as expected on a fixed sample of answers
+    Selfcheck {
+        /// How many answers to play
+        #[clap(long, default_value = "100")]
+        sample: usize,
+        /// Which sample of answers to play
+        #[clap(long, default_value = "2021")]
+        seed: u64,
+        /// Fail if the average score is above this
+        #[clap(long, default_value = "3.8")]
+        max_average: f64,
+        /// Fail if more than this fraction of games take over six guesses
+        #[clap(long, default_value = "0.05")]
+        max_failure_rate: f64,
+    },
+    /// Play a fixed sample of answers in both double and single precision, to show how much
+    /// faster single precision is and how little it changes the score
+    ComparePrecision {
+        /// How many answers to play
+        #[clap(long, default_value = "100")]
+        sample: usize,
+        /// Which sample of answers to play
+        #[clap(long, default_value = "2021")]
+        seed: u64,
+    },
+    /// Play a fixed sample of answers with each of the given implementations (or the one picked
+    /// with --implementation), and show how long their guesses take in each round, as
+    /// percentiles
+    Latency {
+        /// The implementations to time
+        implementations: Vec<String>,
+        /// How many answers to play
+        #[clap(long, default_value = "100")]
+        sample: usize,
+        /// Which sample of answers to play
+        #[clap(long, default_value = "2021")]
+        seed: u64,
+    },
+    /// Play each of the given implementations (or the one picked with --implementation) against
+    /// a host that puts off picking the answer for as long as it can, like Absurdle, and show
+    /// how many guesses each needed
+    Absurdle {
+        /// The implementations to play
+        implementations: Vec<String>,
+    },
+    /// Play the answers (or the first --games of them) while tuning how many candidates the
+    /// cutoff or cache implementation considers as guesses, by now and then considering all of
+    /// them to see how often the best guess is missed
+    Calibrate {
+        /// The share of the checked guesses that may miss the best guess
+        #[clap(long, default_value = "0.02")]
+        target: f64,
+        /// Check one in this many guesses
+        #[clap(long, default_value = "4")]
+        check_every: usize,
+    },
+    /// Print a short hash of every guess made on a fixed sample of answers, to tell whether a
+    /// change affected which guesses are made
+    Fingerprint {
+        /// How many answers to play
+        #[clap(long, default_value = "100")]
+        sample: usize,
+        /// Which sample of answers to play
+        #[clap(long, default_value = "2021")]
+        seed: u64,
+    },
+    /// Play every answer and write down every guess made as a strategy tree, which can be replayed
+    /// with --tree without having to compute any of the guesses again
+    Compile {
+        /// Where to write the tree
+        output: PathBuf,
+        /// Claim in the tree that the guesser plays by the hard-mode rules, for verify-strategy
+        /// to check
+        #[clap(long)]
+        hard_mode: bool,
+    },
+    /// Play every answer starting with each of the given openers (or the best ones for the
+    /// dictionary in use), and write out how much information each gives and how many answers
+    /// took each number of guesses as CSV. With --parallel, the answers are played on every core
+    Openers {
+        /// The first guesses to try, or if there are none, the --top best by expected information
+        /// out of every word in the dictionary, ranked by their average score
+        openers: Vec<String>,
+        /// How many of the best openers by expected information to try, when none are given
+        #[clap(long, default_value = "10")]
+        top: usize,
+        /// Where to write the CSV, instead of stdout
+        #[clap(long)]
+        output: Option<PathBuf>,
+    },
+    /// Compare the number of guesses each built-in implementation takes for every answer against
+    /// a baseline strategy tree, such as a published optimal one
+    Regret {
+        /// The tree to compare against
+        baseline: PathBuf,
+    },
+    /// Write out the entropy, worst-case number of candidates left and expected number of
+    /// candidates left of every allowed word as an opener, as CSV
+    EntropyTable {
+        /// Where to write the CSV, instead of stdout
+        #[clap(long)]
+        output: Option<PathBuf>,
+        /// Work out the feedback of every pair of words on the GPU (experimental)
+        #[cfg(feature = "gpu")]
+        #[clap(long)]
+        gpu: bool,
+    },
+    /// List the guesses that give the best trade-off between expected information and the chance
+    /// of being the answer, after the given guesses and feedback
+    Pareto {
+        /// Each guess followed by its feedback, e.g. tares XYYXX
+        history: Vec<String>,
+    },
+    /// Show how the candidates left after the given guesses and feedback would be split up by the
+    /// feedback to a guess, to see why the solver likes it (or doesn't)
+    Explain {
+        /// The guess to explain
+        #[clap(long)]
+        guess: String,
+        /// Each earlier guess followed by its feedback, e.g. --history tares XYYXX
+        #[clap(long, multiple_values = true)]
+        history: Vec<String>,
+        /// Draw how likely each feedback is as a bar chart
+        #[clap(long)]
+        chart: bool,
+    },
+    /// Print which version of the dictionary this was built with, and where it came from
+    Dictionary,
+    /// Work out the mask of every word against every other on every core and save them, laid out
+    /// as --cache-layout, so that the cache implementation starts out with all of them
+    Precompute {
+        /// Where to save the masks, instead of where the cache implementation looks by default
+        #[clap(long)]
+        output: Option<PathBuf>,
+    },
+    /// Suggest the next guess in many games at once, given one game per line of a file (or of
+    /// standard input) as each guess followed by its feedback, e.g. tares XYYXX. Prints the
+    /// suggestions in the same order, one per line, with - where there is none
+    Batch {
+        /// The file to read the games from, instead of standard input
+        file: Option<PathBuf>,
+    },
+    /// Check the dictionary, answers, cache, strategy and tree given (or the ones used by
+    /// default) for anything that would make the solver refuse to start or behave oddly, and
+    /// suggest how to fix it
+    Doctor,
+    /// Check that a strategy tree solves every answer using only allowed guesses (following the
+    /// hard-mode rules if it claims to), and work out its average score
+    VerifyStrategy {
+        /// The tree to check
+        file: PathBuf,
+    },
 }
 
 fn main() {
     let args = Args::parse();
+    if let Some(Command::Doctor) = &args.command {
+        return doctor(&args);
+    }
+    let used = match &args.exclude_used {
+        Some(path) => match std::fs::read_to_string(path) {
+            Ok(words) => words
+                .split_whitespace()
+                .map(|word| word.to_lowercase())
+                .collect(),
+            Err(e) => {
+                eprintln!("could not read used words '{}': {}", path.display(), e);
+                std::process::exit(1);
+            }
+        },
+        None => Vec::new(),
+    };
+    let dictionary = match &args.dictionary_file {
+        Some(path) => match Dictionary::from_path(path) {
+            Ok(dictionary) => Some(dictionary),
+            Err(e) => {
+                eprintln!("could not read dictionary '{}': {}", path.display(), e);
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+    let answers = match &args.answers_file {
+        Some(path) => match std::fs::read_to_string(path) {
+            Ok(answers) => answers.split_whitespace().map(str::to_string).collect(),
+            Err(e) => {
+                eprintln!("could not read answers '{}': {}", path.display(), e);
+                std::process::exit(1);
+            }
+        },
+        None => Vec::new(),
+    };
+    if args.high_contrast {
+        if let Err(e) = set_palette(Palette::HighContrast) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+    if let Err(e) = set_locale(args.locale.or_else(Locale::from_env).unwrap_or_default()) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+    if let Err(e) = algorithms::set_summation(args.summation) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+    if !args.no_dictionary_cache {
+        if let Some(dir) = default_cache_dir() {
+            cache_dictionaries_in(dir);
+        }
+    }
+    let mut profile = Profile {
+        spelling: args.dictionary,
+        family_friendly: args.family_friendly,
+        deprioritise_inflections: args.deprioritise_inflections,
+        used,
+        no_repeats: args.no_repeats,
+        answers,
+        duplicates: args.duplicates,
+        answers_only: args.answers_only,
+        ..Default::default()
+    };
+    if let Some(dictionary) = dictionary {
+        profile = profile.with_dictionary(dictionary);
+    }
+    if let Err(e) = set_profile(profile) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+
+    if let Some(Command::VerifyStrategy { file }) = &args.command {
+        return verify_strategy(file);
+    }
+    #[cfg(not(feature = "gpu"))]
+    if let Some(Command::EntropyTable { output }) = &args.command {
+        return entropy_table(output.as_deref(), false);
+    }
+    #[cfg(feature = "gpu")]
+    if let Some(Command::EntropyTable { output, gpu }) = &args.command {
+        return entropy_table(output.as_deref(), *gpu);
+    }
+    if let Some(Command::Pareto { history }) = &args.command {
+        return pareto(history);
+    }
+    if let Some(Command::Explain {
+        guess,
+        history,
+        chart,
+    }) = &args.command
+    {
+        return explain(history, guess, *chart);
+    }
+    if let Some(Command::Dictionary) = &args.command {
+        let info = dictionary_info();
+        println!("version                  {}", info.version());
+        println!("spelling                 {}", info.profile.spelling);
+        println!("family-friendly          {}", info.profile.family_friendly);
+        println!(
+            "deprioritise-inflections {}",
+            info.profile.deprioritise_inflections
+        );
+        println!("used words               {}", info.profile.used.len());
+        println!("no repeated letters      {}", info.profile.no_repeats);
+        println!("duplicate letters        {}", info.profile.duplicates);
+        println!("words                    {}", info.words);
+        println!("letters                  {}", word_len());
+        println!("generated                {}", info.generated);
+        println!("source                   {}", info.source);
+        return;
+    }
+    if let Some(Command::Precompute { output }) = &args.command {
+        return precompute(output.as_deref(), args.cache_layout.unwrap_or_default());
+    }
+
+    let mut registry = Registry::builtin();
+    // The cache only fits feedback for words of up to five letters.
+    let mut default = if word_len() <= 5 { "cache" } else { "escore" }.to_string();
+    let mut schedule = args.search_schedule.clone().unwrap_or_default();
+    if let Some(fraction) = args.search_fraction {
+        schedule.default.fraction = fraction;
+    }
+    if args.lambda.is_some()
+        || args.time_per_game.is_some()
+        || args.precision != algorithms::Precision::F64
+        || args.search_fraction.is_some()
+        || args.search_schedule.is_some()
+        || args.explain.is_some()
+    {
+        let (lambda, budget, precision) = (args.lambda, args.time_per_game, args.precision);
+        let tuned = lambda.is_some()
+            || budget.is_some()
+            || precision != algorithms::Precision::F64
+            || args.search_fraction.is_some()
+            || args.search_schedule.is_some();
+        // The expected scores no longer apply once the balance changes, or the search is cut short
+        // or done less precisely.
+        let info = |name| {
+            let info = registry.info(name).unwrap().clone();
+            Info {
+                expected_score: info.expected_score.filter(|_| !tuned),
+                ..info
+            }
+        };
+        // Keep the explanations out of the way of the records.
+        let to_stderr = args.output != OutputFormat::Plain;
+        let explain = args.explain.map(|top| {
+            (top, move |e: &algorithms::GuessExplanation| {
+                print_explanation(e, to_stderr)
+            })
+        });
+        let (escore, sigmoid, cache) = (info("escore"), info("sigmoid"), info("cache"));
+        if args.search_fraction.is_some() || args.search_schedule.is_some() {
+            let schedule = schedule.clone();
+            registry.register_with("cutoff", info("cutoff"), move || {
+                algorithms::Cutoff::new().with_search_schedule(schedule.clone())
+            });
+        }
+        if budget.is_some() || precision != algorithms::Precision::F64 || explain.is_some() {
+            registry.register_with("escore", escore, move || {
+                let guesser = algorithms::Escore::new().with_precision(precision);
+                let guesser = match budget {
+                    Some(budget) => guesser.with_time_budget(budget),
+                    None => guesser,
+                };
+                match explain {
+                    Some((top, report)) => guesser.with_explanations(top, report),
+                    None => guesser,
+                }
+            });
+        }
+        registry.register_with("sigmoid", sigmoid, move || {
+            let guesser = algorithms::Sigmoid::new()
+                .with_lambda(lambda.unwrap_or(1.0))
+                .with_precision(precision);
+            let guesser = match budget {
+                Some(budget) => guesser.with_time_budget(budget),
+                None => guesser,
+            };
+            match explain {
+                Some((top, report)) => guesser.with_explanations(top, report),
+                None => guesser,
+            }
+        });
+        let schedule = schedule.clone();
+        registry.register_with("cache", cache, move || {
+            let guesser = algorithms::Cached::new()
+                .with_lambda(lambda.unwrap_or(1.0))
+                .with_precision(precision)
+                .with_search_schedule(schedule.clone());
+            let guesser = match budget {
+                Some(budget) => guesser.with_time_budget(budget),
+                None => guesser,
+            };
+            match explain {
+                Some((top, report)) => guesser.with_explanations(top, report),
+                None => guesser,
+            }
+        });
+    }
+
+    if let Some(max) = args.max_exact {
+        let info = Info {
+            expected_score: None,
+            ..registry.info("optimal").unwrap().clone()
+        };
+        registry.register_with("optimal", info, move || {
+            algorithms::Optimal::new().with_max_candidates(max)
+        });
+    }
+
+    if let Some(top) = args.lookahead {
+        let info = Info {
+            expected_score: None,
+            ..registry.info("twoply").unwrap().clone()
+        };
+        registry.register_with("twoply", info, move || {
+            algorithms::TwoPly::new().with_top(top)
+        });
+    }
+
+    #[cfg(feature = "plugins")]
+    if let Some(path) = &args.plugin {
+        if word_len() != 5
+            || !wordle_solver::dictionary()
+                .iter()
+                .all(|(word, _)| word.is_ascii())
+        {
+            eprintln!("plugins only play five-letter words from a to z");
+            std::process::exit(1);
+        }
+        let plugin = match unsafe { wordle_solver::plugin::Plugin::load(path) } {
+            Ok(plugin) => plugin,
+            Err(e) => {
+                eprintln!("could not load plugin '{}': {}", path.display(), e);
+                std::process::exit(1);
+            }
+        };
+        default = plugin.name().to_string();
+        registry.register_local(default.clone(), move || plugin.guesser());
+    }
+
+    #[cfg(feature = "scripting")]
+    if let Some(path) = &args.script {
+        let guesser = match algorithms::Scripted::from_file(path) {
+            Ok(guesser) => guesser,
+            Err(e) => {
+                eprintln!("could not load script '{}': {}", path.display(), e);
+                std::process::exit(1);
+            }
+        };
+        default = "script".to_string();
+        registry.register_local("script", move || guesser.clone());
+    }
+
+    if let Some(path) = &args.strategy {
+        let strategy = match std::fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|s| s.parse::<algorithms::Strategy>())
+        {
+            Ok(strategy) => strategy,
+            Err(e) => {
+                eprintln!("could not load strategy '{}': {}", path.display(), e);
+                std::process::exit(1);
+            }
+        };
+        default = "strategy".to_string();
+        registry.register("strategy", move || strategy.new_guesser());
+    }
 
-    match args.implementation {
-        Implementation::Unoptimised => play::<algorithms::Unoptimised>(args.games),
-        Implementation::Allocs => play::<algorithms::Allocs>(args.games),
-        Implementation::Vecrem => play::<algorithms::Vecrem>(args.games),
-        Implementation::Precalc => play::<algorithms::Precalc>(args.games),
-        Implementation::Weight => play::<algorithms::Weight>(args.games),
-        Implementation::Enum => play::<algorithms::Enumerate>(args.games),
-        Implementation::Cutoff => play::<algorithms::Cutoff>(args.games),
-        Implementation::Sigmoid => play::<algorithms::Sigmoid>(args.games),
-        Implementation::Escore => play::<algorithms::Escore>(args.games),
-        Implementation::Popular => play::<algorithms::Popular>(args.games),
-        Implementation::Cache => play::<algorithms::Cached>(args.games),
+    if let Some(path) = &args.tree {
+        let tree = match std::fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|s| s.parse::<Tree>())
+        {
+            Ok(tree) => tree,
+            Err(e) => {
+                eprintln!("could not load tree '{}': {}", path.display(), e);
+                std::process::exit(1);
+            }
+        };
+        // A tree's guessers borrow from it, so it is played directly rather than registered.
+        return match args.command {
+            Some(Command::Assist) => assist(&&tree, "tree", &args),
+            _ => run_with(&args, "tree", &tree, args.parallel.then_some(&tree)),
+        };
+    }
+
+    if args.list_implementations {
+        return list_implementations(&registry);
+    }
+
+    let name = args.implementation.clone().unwrap_or(default);
+    if let Some(Command::Latency {
+        implementations,
+        sample,
+        seed,
+    }) = &args.command
+    {
+        let names = match &implementations[..] {
+            [] => std::slice::from_ref(&name),
+            names => names,
+        };
+        return latency(&registry, names, *sample, *seed);
+    }
+    if let Some(Command::Absurdle { implementations }) = &args.command {
+        let names = match &implementations[..] {
+            [] => std::slice::from_ref(&name),
+            names => names,
+        };
+        return absurdle(&registry, names, args.mode);
+    }
+    let Some(factory) = registry.get(&name) else {
+        eprintln!(
+            "unknown implementation '{}', expected one of: {}",
+            name,
+            registry.names().collect::<Vec<_>>().join(", ")
+        );
+        std::process::exit(1);
+    };
+    if args.lambda.is_some() && !matches!(&*name, "sigmoid" | "cache") {
+        eprintln!("--lambda only applies to the sigmoid and cache implementations");
+        std::process::exit(1);
+    }
+    if args.time_per_game.is_some() && !matches!(&*name, "escore" | "sigmoid" | "cache") {
+        eprintln!("--time-per-game only applies to the escore, sigmoid and cache implementations");
+        std::process::exit(1);
+    }
+    if args.precision != algorithms::Precision::F64
+        && !matches!(&*name, "escore" | "sigmoid" | "cache")
+    {
+        eprintln!("--precision only applies to the escore, sigmoid and cache implementations");
+        std::process::exit(1);
+    }
+    if args.search_fraction.is_some() && !matches!(&*name, "cutoff" | "cache") {
+        eprintln!("--search-fraction only applies to the cutoff and cache implementations");
+        std::process::exit(1);
+    }
+    if args.search_schedule.is_some() && !matches!(&*name, "cutoff" | "cache") {
+        eprintln!("--search-schedule only applies to the cutoff and cache implementations");
+        std::process::exit(1);
+    }
+    if args.max_exact.is_some() && name != "optimal" {
+        eprintln!("--max-exact only applies to the optimal implementation");
+        std::process::exit(1);
+    }
+    if args.lookahead.is_some() && name != "twoply" {
+        eprintln!("--lookahead only applies to the twoply implementation");
+        std::process::exit(1);
+    }
+    if let Some(layout) = args.cache_layout {
+        if !matches!(&*name, "cache" | "twoply") {
+            eprintln!("--cache-layout only applies to the cache and twoply implementations");
+            std::process::exit(1);
+        }
+        if let Err(e) = algorithms::Cached::set_layout(layout) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+    if let Some(bytes) = args.max_cache_mem {
+        if !matches!(&*name, "cache" | "twoply") {
+            eprintln!("--max-cache-mem only applies to the cache and twoply implementations");
+            std::process::exit(1);
+        }
+        if let Err(e) = algorithms::Cached::set_memory_budget(bytes) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
     }
+    if let Some(path) = &args.pattern_file {
+        if !matches!(&*name, "cache" | "twoply") {
+            eprintln!("--pattern-file only applies to the cache and twoply implementations");
+            std::process::exit(1);
+        }
+        if let Err(e) = algorithms::Cached::set_pattern_file(path) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+    if matches!(&*name, "cache" | "twoply") && word_len() > 5 {
+        eprintln!(
+            "the {} implementation only plays words of up to five letters",
+            name
+        );
+        std::process::exit(1);
+    }
+    if matches!(&*name, "cache" | "twoply") {
+        // Sets up the cache now, so that anything that stops it being kept as asked is reported
+        // before the games rather than in the middle of them.
+        algorithms::Cached::new();
+        for fallback in algorithms::Cached::fallbacks() {
+            eprintln!("{}", fallback);
+        }
+    }
+    if args.telemetry {
+        if args.command.is_some() {
+            eprintln!("--telemetry only applies to playing the answers");
+            std::process::exit(1);
+        }
+        algorithms::enable_telemetry();
+    }
+    if args.share {
+        if args.command.is_some() {
+            eprintln!("--share only applies to playing the answers");
+            std::process::exit(1);
+        }
+        if args.output != OutputFormat::Plain || args.log_format != LogFormat::Text {
+            eprintln!("--share can't be used with --output or --log-format json");
+            std::process::exit(1);
+        }
+    }
+    if args.output != OutputFormat::Plain {
+        if args.command.is_some() {
+            eprintln!("--output only applies to playing the answers");
+            std::process::exit(1);
+        }
+        if args.log_format != LogFormat::Text {
+            eprintln!("--output can't be used with --log-format json");
+            std::process::exit(1);
+        }
+    }
+    match args.command {
+        Some(Command::Assist) => assist(&factory, &name, &args),
+        Some(Command::ComparePrecision { sample, seed }) => {
+            compare_precision(&name, args.lambda, args.time_per_game, sample, seed)
+        }
+        Some(Command::Calibrate {
+            target,
+            check_every,
+        }) => calibrate(&name, &args, schedule.default, target, check_every),
+        #[cfg(feature = "server")]
+        Some(Command::Serve {
+            ref addr,
+            workers,
+            rate_limit,
+        }) => serve(&registry, &name, addr, workers, rate_limit),
+        Some(Command::Regret { ref baseline }) => regret(&registry, args.games, baseline),
+        _ => {
+            let shared = registry.get_shared(&name);
+            if args.parallel && shared.is_none() {
+                eprintln!("{} can't be played in parallel", name);
+                std::process::exit(1);
+            }
+            // Batches are always worked out in parallel when they can be.
+            let parallel = args.parallel || matches!(args.command, Some(Command::Batch { .. }));
+            run_with(&args, &name, factory, shared.filter(|_| parallel))
+        }
+    }
+}
+
+/// Prints every registered implementation along with what is known about it.
+fn list_implementations(registry: &Registry) {
+    println!("{:<12} {:<9} {:<6} description", "name", "speed", "score");
+    for name in registry.names() {
+        let info = registry.info(name).unwrap();
+        println!(
+            "{:<12} {:<9} {:<6} {}",
+            name,
+            info.speed.map_or("-".to_string(), |s| s.to_string()),
+            info.expected_score
+                .map_or("-".to_string(), |s| format!("{:.4}", s)),
+            info.description
+        );
+    }
+}
+
+/// How the games played are reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LogFormat {
+    Text,
+    Json,
+}
+
+impl std::str::FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("unknown log format '{}', expected text or json", s)),
+        }
+    }
+}
+
+/// How the games played are printed, for reading by people or by other tools.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Plain,
+    Json,
+    Csv,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plain" => Ok(Self::Plain),
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            _ => Err(format!(
+                "unknown output format '{}', expected plain, json or csv",
+                s
+            )),
+        }
+    }
+}
+
+/// Prints an event as a line of JSON, with the `event` name followed by the `fields`, whose values
+/// must already be JSON.
+fn log_event(event: &str, fields: &[(&str, String)]) {
+    let mut line = format!("{{\"event\":\"{}\"", event);
+    for (key, value) in fields {
+        line.push_str(&format!(",\"{}\":{}", key, value));
+    }
+    line.push('}');
+    println!("{}", line);
+}
+
+/// Runs any of the commands that only need to be able to create new guessers.
+/// Plays the guessers from `factory`, or when playing in parallel the ones from `shared` (which
+/// creates the same guessers from other threads).
+fn run_with<G, S>(
+    args: &Args,
+    name: &str,
+    factory: impl GuesserFactory<Guesser = G>,
+    shared: Option<S>,
+) where
+    G: Guesser,
+    S: GuesserFactory + Sync,
+{
+    match args.command {
+        None => {
+            let answers = Answers::in_use();
+            let (parameters, stats) = match args.puzzle.or(args.date) {
+                Some(puzzle) => match answers.answer_for(puzzle) {
+                    Some(answer) => (
+                        format!("puzzle={}", puzzle),
+                        play(
+                            &factory,
+                            std::iter::once((puzzle, answer)),
+                            args,
+                            shared.as_ref(),
+                        ),
+                    ),
+                    None => {
+                        eprintln!("there is no puzzle {} in the answer list", puzzle);
+                        std::process::exit(1);
+                    }
+                },
+                None => {
+                    let games = args.games.unwrap_or(usize::MAX).min(answers.len());
+                    (
+                        format!("games={}", games),
+                        play(&factory, answers.iter().take(games), args, shared.as_ref()),
+                    )
+                }
+            };
+            let average_score = stats.average_score();
+            let runtime = stats.elapsed;
+            match (args.output, args.log_format) {
+                (OutputFormat::Json, _) => println!(
+                    "{{\"summary\":{{\"implementation\":{},\
+                     \"average_score\":{:.4},\"seconds\":{:.3}{}}}}}",
+                    json_string(name),
+                    average_score,
+                    runtime.as_secs_f64(),
+                    stats_fields(&stats)
+                        .into_iter()
+                        .filter(|_| args.stats)
+                        .map(|(key, value)| format!(",\"{}\":{}", key, value))
+                        .collect::<String>()
+                ),
+                (OutputFormat::Csv, _) => eprintln!(
+                    "average score {:.4} in {:.2}s",
+                    average_score,
+                    runtime.as_secs_f64()
+                ),
+                (OutputFormat::Plain, LogFormat::Text) => println!(
+                    "average score {:.4} in {:.2}s",
+                    average_score,
+                    runtime.as_secs_f64()
+                ),
+                (OutputFormat::Plain, LogFormat::Json) => log_event(
+                    "run_finished",
+                    &[
+                        ("implementation", json_string(name)),
+                        ("average_score", format!("{:.4}", average_score)),
+                        ("seconds", format!("{:.3}", runtime.as_secs_f64())),
+                    ],
+                ),
+            }
+            if args.stats {
+                match (args.output, args.log_format) {
+                    (OutputFormat::Json, _) => {}
+                    (OutputFormat::Csv, _) => eprint!("{}", stats),
+                    (OutputFormat::Plain, LogFormat::Text) => print!("{}", stats),
+                    (OutputFormat::Plain, LogFormat::Json) => {
+                        log_event("stats", &stats_fields(&stats))
+                    }
+                }
+            }
+            if args.telemetry {
+                let telemetry = algorithms::telemetry();
+                match args.log_format {
+                    // Keep it out of the way of the records.
+                    LogFormat::Text if args.output != OutputFormat::Plain => {
+                        eprint!("{}", telemetry)
+                    }
+                    LogFormat::Text => print!("{}", telemetry),
+                    LogFormat::Json => log_event(
+                        "telemetry",
+                        &[
+                            ("searches", telemetry.searches.to_string()),
+                            ("truncated", telemetry.truncated.to_string()),
+                            ("scored", telemetry.scored.to_string()),
+                            ("buckets", telemetry.buckets.to_string()),
+                            ("lookups", telemetry.lookups.to_string()),
+                            ("hits", telemetry.hits.to_string()),
+                        ],
+                    ),
+                }
+            }
+            if let Some(path) = &args.leaderboard {
+                update_leaderboard(path, name, parameters, average_score, runtime);
+            }
+        }
+        Some(
+            Command::Assist
+            | Command::VerifyStrategy { .. }
+            | Command::EntropyTable { .. }
+            | Command::Pareto { .. }
+            | Command::Explain { .. }
+            | Command::Dictionary
+            | Command::Precompute { .. }
+            | Command::Doctor
+            | Command::ComparePrecision { .. }
+            | Command::Latency { .. }
+            | Command::Absurdle { .. }
+            | Command::Calibrate { .. }
+            | Command::Regret { .. },
+        ) => {
+            unreachable!("handled before picking a guesser")
+        }
+        #[cfg(feature = "server")]
+        Some(Command::Serve { .. }) => unreachable!("handled before picking a guesser"),
+        Some(Command::Host { puzzle }) => host(&factory, name, puzzle, args),
+        #[cfg(feature = "net")]
+        Some(Command::Today { puzzle }) => today(&factory, name, puzzle, args),
+        Some(Command::Batch { ref file }) => {
+            batch(&factory, shared.as_ref(), name, file.as_deref())
+        }
+        Some(Command::Selfcheck {
+            sample,
+            seed,
+            max_average,
+            max_failure_rate,
+        }) => selfcheck(&factory, sample, seed, max_average, max_failure_rate),
+        Some(Command::Fingerprint { sample, seed }) => fingerprint(&factory, sample, seed),
+        Some(Command::Compile {
+            ref output,
+            hard_mode,
+        }) => compile(&factory, args.games, output, hard_mode),
+        Some(Command::Openers {
+            ref openers,
+            top,
+            ref output,
+        }) => evaluate_openers(
+            &factory,
+            shared.as_ref(),
+            args.games,
+            openers,
+            top,
+            output.as_deref(),
+        ),
+    }
+}
+
+/// Parses a YYYY-MM-DD date into the number of the official puzzle on that day.
+fn parse_date(date: &str) -> Result<usize, String> {
+    let mut parts = date.splitn(3, '-').map(|part| part.parse::<i64>());
+    let (Some(Ok(y)), Some(Ok(m)), Some(Ok(d))) = (parts.next(), parts.next(), parts.next()) else {
+        return Err(format!("'{}' is not a date like 2023-12-18", date));
+    };
+    // This runs before the profile is set, so mustn't look at the answers in use.
+    Answers::official()
+        .day_on(y, m, d)
+        .ok_or_else(|| format!("there is no puzzle for {}", date))
+}
+
+/// Parses a non-negative number to raise chances to the power of.
+fn parse_lambda(lambda: &str) -> Result<f64, String> {
+    match lambda.parse::<f64>() {
+        Ok(lambda) if lambda.is_finite() && lambda >= 0.0 => Ok(lambda),
+        _ => Err(format!("'{}' is not a non-negative number", lambda)),
+    }
+}
+
+/// Parses a (non-negative) number of seconds.
+fn parse_seconds(seconds: &str) -> Result<Duration, String> {
+    seconds
+        .parse()
+        .ok()
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+        .ok_or_else(|| format!("'{}' is not a number of seconds", seconds))
+}
+
+/// Parses a number of bytes, optionally followed by K, M or G (for kibibytes, mebibytes and
+/// gibibytes).
+fn parse_bytes(bytes: &str) -> Result<usize, String> {
+    let (number, unit) = match bytes.char_indices().find(|(_, c)| c.is_ascii_alphabetic()) {
+        Some((i, _)) => bytes.split_at(i),
+        None => (bytes, ""),
+    };
+    let unit: u32 = match unit
+        .to_ascii_uppercase()
+        .trim_end_matches("IB")
+        .trim_end_matches('B')
+    {
+        "" => 0,
+        "K" => 1,
+        "M" => 2,
+        "G" => 3,
+        _ => return Err(format!("'{}' is not a number of bytes like 512M", bytes)),
+    };
+    number
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|n| *n >= 0.0)
+        .map(|n| (n * 1024f64.powi(unit as i32)) as usize)
+        .ok_or_else(|| format!("'{}' is not a number of bytes like 512M", bytes))
+}
+
+/// How the scores were spread out, as JSON fields: the `histogram` has the games solved in one to
+/// six guesses, followed by the ones that weren't.
+fn stats_fields(stats: &EvaluationStats) -> Vec<(&'static str, String)> {
+    let json = |score: Option<usize>| score.map_or("null".to_string(), |s| s.to_string());
+    let histogram: Vec<_> = stats.spread().iter().map(usize::to_string).collect();
+    vec![
+        ("median", json(stats.median())),
+        ("p95", json(stats.percentile(0.95))),
+        ("failure_rate", format!("{:.4}", stats.failure_rate())),
+        ("histogram", format!("[{}]", histogram.join(","))),
+    ]
 }
 
-fn play<G>(games: Option<usize>)
+/// Plays every one of `answers`, reporting them as `args` asks, and returns their scores.
+///
+/// If there is a `shared` factory the games are played in parallel with its guessers, one per
+/// core, and shown once they have all been played.
+fn play<G>(
+    factory: &impl GuesserFactory<Guesser = G>,
+    answers: impl Iterator<Item = (usize, &'static str)>,
+    args: &Args,
+    shared: Option<&(impl GuesserFactory + Sync)>,
+) -> EvaluationStats
 where
-    G: Guesser + Default,
+    G: Guesser,
 {
+    let (no_spoilers, share, log_format, output, mode) = (
+        args.no_spoilers,
+        args.share,
+        args.log_format,
+        args.output,
+        args.mode,
+    );
+    let distributions = args.distributions.as_deref();
     let w = wordle_solver::Wordle::new();
-    for answer in GAMES.split_whitespace().take(games.unwrap_or(usize::MAX)) {
-        let guesser = G::default();
-        if let Some(s) = w.play(answer, guesser) {
-            println!("guessed '{}' in {}", &answer, s);
+    let start = Instant::now();
+    let mut stats = EvaluationStats::default();
+    let mut distribution_games = Vec::new();
+    let played: Box<dyn Iterator<Item = (usize, GameResult)>> = match shared {
+        Some(shared) => {
+            let answers: Vec<_> = answers.collect();
+            let workers = std::thread::available_parallelism().map_or(1, |n| n.get());
+            let played = play_parallel_with(
+                answers.iter().map(|&(_, answer)| answer),
+                shared,
+                workers,
+                |guesser, answer| w.play_game(answer, guesser, mode),
+            );
+            Box::new(answers.into_iter().map(|(puzzle, _)| puzzle).zip(played))
+        }
+        None => {
+            let mut guesser = factory.new_guesser();
+            Box::new(answers.map(move |(puzzle, answer)| {
+                guesser.reset();
+                (puzzle, w.play_game(answer, &mut guesser, mode))
+            }))
+        }
+    };
+    if output == OutputFormat::Csv {
+        println!("puzzle,answer,score,guesses,feedback,seconds");
+    }
+    for (puzzle, game) in played {
+        let answer = game.answer;
+        if log_format == LogFormat::Json {
+            log_event("game_started", &[("puzzle", puzzle.to_string())]);
+            log_game(puzzle, &game, no_spoilers);
+        }
+        match output {
+            OutputFormat::Plain => {}
+            OutputFormat::Json => println!("{}", game_record_json(puzzle, &game, no_spoilers)),
+            OutputFormat::Csv => println!("{}", game_record_csv(puzzle, &game, no_spoilers)),
+        }
+        stats.record(answer, game.score());
+        if let Some(s) = game.score() {
+            if distributions.is_some() {
+                distribution_games.push(game_distributions(answer, &game.guesses));
+            }
+            if log_format == LogFormat::Json || output != OutputFormat::Plain {
+                continue;
+            }
+            if share {
+                println!("{}", game.share_string(puzzle));
+            } else if no_spoilers {
+                println!("puzzle {} in {}", puzzle, s);
+                for mask in &game.masks {
+                    println!("{}", Correctness::emoji_row(&mask[..letter_count(answer)]));
+                }
+            } else {
+                println!("guessed '{}' in {}", &answer, s);
+            }
         } else {
             eprintln!("failed to guess.. exiting!");
         }
     }
+    stats.elapsed = start.elapsed();
+    if let Some(path) = distributions {
+        let json = format!("[\n{}\n]\n", distribution_games.join(",\n"));
+        if let Err(e) = std::fs::write(path, json) {
+            eprintln!("could not write '{}': {}", path.display(), e);
+        }
+    }
+    stats
+}
+
+/// Logs the guesses of a game that was just played, and how it ended, as JSON events.
+fn log_game(puzzle: usize, game: &GameResult, no_spoilers: bool) {
+    let answer = game.answer;
+    let rounds = game.guesses.iter().zip(&game.masks).zip(&game.times);
+    for (i, ((guess, mask), time)) in rounds.enumerate() {
+        let mut fields = vec![
+            ("puzzle", puzzle.to_string()),
+            ("guess", (i + 1).to_string()),
+        ];
+        if !no_spoilers {
+            fields.push(("word", json_string(guess)));
+        }
+        fields.push((
+            "feedback",
+            json_string(&Correctness::format_mask(&mask[..letter_count(answer)])),
+        ));
+        fields.push(("seconds", format!("{:.6}", time.as_secs_f64())));
+        log_event("guess_made", &fields);
+    }
+    let mut fields = vec![("puzzle", puzzle.to_string())];
+    if !no_spoilers {
+        fields.push(("answer", json_string(answer)));
+    }
+    fields.push(("solved", game.solved().to_string()));
+    fields.push(("guesses", game.guesses.len().to_string()));
+    fields.push(("seconds", format!("{:.6}", game.total_time().as_secs_f64())));
+    log_event("game_finished", &fields);
+}
+
+/// A game that was just played as a JSON object, with the `puzzle`, the `answer`, the `guesses`,
+/// the `feedback` each got, the `score` (null if it wasn't solved) and the `seconds` the guesses
+/// took.
+fn game_record_json(puzzle: usize, game: &GameResult, no_spoilers: bool) -> String {
+    let quoted = |words: &[String]| {
+        words
+            .iter()
+            .map(|word| json_string(word))
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+    let mut record = format!("{{\"puzzle\":{}", puzzle);
+    if !no_spoilers {
+        record += &format!(
+            ",\"answer\":{},\"guesses\":[{}]",
+            json_string(game.answer),
+            quoted(&game.guesses)
+        );
+    }
+    record += &format!(
+        ",\"feedback\":[{}],\"score\":{},\"seconds\":{:.6}}}",
+        quoted(&game_feedback(game)),
+        game.score().map_or("null".to_string(), |s| s.to_string()),
+        game.total_time().as_secs_f64()
+    );
+    record
+}
+
+/// A game that was just played as a line of CSV, with the guesses and the feedback each got
+/// separated by spaces, and the score left empty if it wasn't solved. Without spoilers the answer
+/// and guesses are left empty too.
+fn game_record_csv(puzzle: usize, game: &GameResult, no_spoilers: bool) -> String {
+    format!(
+        "{},{},{},{},{},{:.6}",
+        puzzle,
+        if no_spoilers { "" } else { game.answer },
+        game.score().map_or(String::new(), |s| s.to_string()),
+        if no_spoilers {
+            String::new()
+        } else {
+            game.guesses.join(" ")
+        },
+        game_feedback(game).join(" "),
+        game.total_time().as_secs_f64()
+    )
+}
+
+/// The feedback each guess in `game` got, as [`Correctness::format_mask`] writes it, like GYXXY.
+fn game_feedback(game: &GameResult) -> Vec<String> {
+    game.masks
+        .iter()
+        .map(|mask| Correctness::format_mask(&mask[..letter_count(game.answer)]))
+        .collect()
+}
+
+/// The feedback distribution of every guess in a game, as a JSON object with the `answer` and the
+/// `guesses`, each with the `guess`, the `feedback` it got and the `buckets` it could have got.
+fn game_distributions(answer: &str, guesses: &[String]) -> String {
+    use wordle_solver::analysis::{distribution, distribution_json};
+
+    let mut history = Vec::new();
+    let mut rounds = Vec::new();
+    for guess in guesses {
+        let buckets = distribution(&history, guess);
+        let mask = Correctness::compute(answer, guess);
+        rounds.push(format!(
+            "{{\"guess\":{},\"feedback\":{},\"buckets\":{}}}",
+            json_string(guess),
+            json_string(&Correctness::format_mask(&mask[..letter_count(guess)])),
+            distribution_json(&buckets, letter_count(guess))
+        ));
+        history.push(Guess {
+            word: std::borrow::Cow::Owned(guess.clone()),
+            mask,
+        });
+    }
+    format!(
+        "{{\"answer\":{},\"guesses\":[{}]}}",
+        json_string(answer),
+        rounds.join(",")
+    )
+}
+
+fn update_leaderboard(
+    path: &Path,
+    name: &str,
+    parameters: String,
+    average_score: f64,
+    runtime: Duration,
+) {
+    let mut leaderboard = match Leaderboard::load(path) {
+        Ok(leaderboard) => leaderboard,
+        Err(e) => {
+            eprintln!("could not read leaderboard '{}': {}", path.display(), e);
+            return;
+        }
+    };
+    let improved = leaderboard.record(Entry {
+        algorithm: name.to_string(),
+        parameters,
+        dictionary: dictionary_info().checksum,
+        average_score,
+        runtime,
+    });
+    if improved {
+        if let Err(e) = leaderboard.save(path) {
+            eprintln!("could not write leaderboard '{}': {}", path.display(), e);
+        } else {
+            println!("new best result recorded in '{}'", path.display());
+        }
+    }
+}
+
+/// Remembers every guess made by the wrapped guesser, and how long it took to make, so a game can
+/// be shown after it is played.
+struct Recorder<G> {
+    inner: G,
+    guesses: Vec<String>,
+    times: Vec<Duration>,
+}
+
+impl<G> Recorder<G> {
+    fn new(inner: G) -> Self {
+        Self {
+            inner,
+            guesses: Vec::new(),
+            times: Vec::new(),
+        }
+    }
+}
+
+impl<G: Guesser> Guesser for Recorder<G> {
+    fn guess(&mut self, history: &[Guess]) -> Cow<'static, str> {
+        let start = Instant::now();
+        let guess = self.inner.guess(history);
+        self.times.push(start.elapsed());
+        self.guesses.push(guess.to_string());
+        guess
+    }
+
+    fn finish(&self, guesses: usize) {
+        self.inner.finish(guesses)
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+        self.guesses.clear();
+        self.times.clear();
+    }
+}
+
+/// Prints a [`Message`] in the locale in use, filled in with the given arguments.
+macro_rules! say {
+    ($message:ident $(, $arg:expr)* $(,)?) => {
+        println!("{}", message(Message::$message, &[$(&$arg),*]))
+    };
+}
+
+fn assist<G>(factory: &impl GuesserFactory<Guesser = G>, name: &str, args: &Args)
+where
+    G: Guesser + Clone,
+{
+    let screen_reader = args.screen_reader;
+    say!(AssistHelp);
+    let mut session = Session::new(factory.new_guesser());
+    let mut lines = input_lines(args.scripted_input.as_deref());
+    loop {
+        if session.is_solved() {
+            say!(Solved, session.history().len());
+            return;
+        }
+        match session.suggestion() {
+            Some(suggestion) => say!(Try, suggestion, session.candidates().len()),
+            None => say!(NoMatches),
+        }
+        print!("> ");
+        io::stdout().flush().expect("could not flush stdout");
+
+        let Some(line) = lines.next() else {
+            return;
+        };
+        let words: Vec<_> = line.split_whitespace().collect();
+        match words[..] {
+            [] => continue,
+            ["quit"] => return,
+            ["hint"] => hint(session.history()),
+            ["probe"] => match wordle_solver::analysis::probe_word(session.history()) {
+                Some(probe) => say!(
+                    Probe,
+                    probe.word,
+                    probe
+                        .letters
+                        .iter()
+                        .map(|letter| format!("'{}'", letter))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                None => say!(NoInformativeLetter),
+            },
+            ["undo"] => match session.undo() {
+                Some(guess) => say!(Forgot, guess.word),
+                None => say!(NothingToUndo),
+            },
+            ["save", path] => {
+                let settings = [
+                    ("implementation", name),
+                    ("dictionary", &*dictionary_info().version()),
+                ];
+                match File::create(path).and_then(|f| session.save(f, &settings)) {
+                    Ok(()) => say!(Saved, path),
+                    Err(e) => say!(CouldNotSave, path, e),
+                }
+            }
+            ["load", path] => {
+                match File::open(path)
+                    .and_then(|f| Session::load(factory.new_guesser(), BufReader::new(f)))
+                {
+                    Ok((loaded, settings)) => {
+                        for (key, value) in settings {
+                            if key == "implementation" && value != name {
+                                say!(SavedByOtherImplementation, path, value);
+                            }
+                            if key == "dictionary" {
+                                if let Err(e) = dictionary_info().check(&value) {
+                                    say!(SavedWithOtherDictionary, path, e);
+                                }
+                            }
+                        }
+                        session = loaded;
+                        say!(Loaded, path);
+                    }
+                    Err(e) => say!(CouldNotLoad, path, e),
+                }
+            }
+            [feedback] => {
+                let Some(word) = session.suggestion().map(str::to_string) else {
+                    say!(NoSuggestion);
+                    continue;
+                };
+                record(&mut session, &word, feedback, screen_reader);
+            }
+            [word, feedback] => {
+                let word = word.to_lowercase();
+                let suggestion = session.suggestion().map(str::to_string);
+                let history = session.history().to_vec();
+                if record(&mut session, &word, feedback, screen_reader) {
+                    if let Some(suggestion) = suggestion.filter(|s| *s != word) {
+                        what_if(&history, &word, &suggestion);
+                    }
+                }
+            }
+            _ => say!(AssistHelp),
+        }
+    }
+}
+
+/// The lines typed into an interactive command, from standard input or, to replay a
+/// conversation, from the file `scripted`. Scripted lines are echoed as they are read, as if they
+/// had been typed.
+fn input_lines(scripted: Option<&Path>) -> Box<dyn Iterator<Item = String>> {
+    match scripted {
+        Some(path) => match std::fs::read_to_string(path) {
+            Ok(input) => {
+                let lines: Vec<_> = input.lines().map(str::to_string).collect();
+                Box::new(lines.into_iter().inspect(|line| println!("{}", line)))
+            }
+            Err(e) => {
+                eprintln!("could not read '{}': {}", path.display(), e);
+                std::process::exit(1);
+            }
+        },
+        None => Box::new(
+            io::stdin()
+                .lines()
+                .map(|line| line.expect("could not read from stdin")),
+        ),
+    }
+}
+
+/// Records feedback for `word`, or explains why it can't. Returns whether it did. For screen
+/// reader users, it reads back the feedback so that typos are easier to catch.
+fn record<G>(session: &mut Session<G>, word: &str, feedback: &str, screen_reader: bool) -> bool
+where
+    G: Guesser + Clone,
+{
+    let Some(mask) = Correctness::parse_mask(feedback, letter_count(word)) else {
+        say!(InvalidFeedback, feedback);
+        return false;
+    };
+    if !session.record(word, mask) {
+        say!(NotInDictionary, word);
+        return false;
+    }
+    if screen_reader {
+        say!(Feedback, word, describe(word, &mask[..letter_count(word)]));
+    }
+    true
+}
+
+/// Suggests the letters that would tell the candidates left apart best, for a human who would
+/// rather work out the next guess themselves.
+fn hint(history: &[Guess]) {
+    // How many letters to suggest.
+    const LETTERS: usize = 3;
+
+    let splits = wordle_solver::analysis::informative_letters(history);
+    if splits.is_empty() {
+        say!(NoInformativeLetter);
+        return;
+    }
+    for split in splits.iter().take(LETTERS) {
+        say!(
+            TestLetter,
+            split.letter,
+            format!("{:.0}", 100.0 * split.probability),
+            format!("{:.2}", split.entropy)
+        );
+    }
+}
+
+/// Compares the word that was played after `history` with the one that was suggested, so that
+/// the difference is something to learn from rather than just a different word.
+fn what_if(history: &[Guess], played: &str, suggested: &str) {
+    let [played_eval, suggested_eval] =
+        [played, suggested].map(|word| wordle_solver::analysis::evaluate_guess(history, word));
+    say!(Compare, played, suggested);
+    say!(
+        CompareInformation,
+        format!("{:.4}", played_eval.entropy),
+        format!("{:.4}", suggested_eval.entropy),
+        format!("{:+.4}", played_eval.entropy - suggested_eval.entropy)
+    );
+    say!(
+        CompareExpectedGuesses,
+        format!("{:.4}", played_eval.expected_score),
+        format!("{:.4}", suggested_eval.expected_score),
+        format!(
+            "{:+.4}",
+            played_eval.expected_score - suggested_eval.expected_score
+        )
+    );
+}
+
+fn host<G>(
+    factory: &impl GuesserFactory<Guesser = G>,
+    name: &str,
+    puzzle: Option<usize>,
+    args: &Args,
+) where
+    G: Guesser,
+{
+    let answers = Answers::in_use();
+    let puzzle = match puzzle {
+        Some(puzzle) if puzzle < answers.len() => puzzle,
+        Some(puzzle) => {
+            eprintln!(
+                "{}",
+                message(Message::NoSuchPuzzle, &[&puzzle, &(answers.len() - 1)])
+            );
+            std::process::exit(1);
+        }
+        None => answers.sample(1, RandomState::new().build_hasher().finish())[0].0,
+    };
+    let Some(answer) = answers.answer_for(puzzle) else {
+        eprintln!("{}", message(Message::NotFamilyFriendly, &[&puzzle]));
+        std::process::exit(1);
+    };
+    let w = wordle_solver::Wordle::new();
+
+    say!(HostIntro);
+    let screen_reader = args.screen_reader;
+    let mut lines = input_lines(args.scripted_input.as_deref());
+    let mut rows = Vec::new();
+    let mut solved = false;
+    let mut start = Instant::now();
+    while !solved && rows.len() < 6 {
+        print!("> ");
+        io::stdout().flush().expect("could not flush stdout");
+        let Some(line) = lines.next() else {
+            return;
+        };
+        let guess = line.trim().to_lowercase();
+        if !w.is_allowed(&guess) {
+            say!(NotInDictionary, guess);
+            continue;
+        }
+        let elapsed = start.elapsed();
+        let mask = Correctness::compute(answer, &guess);
+        let feedback = &mask[..letter_count(answer)];
+        let row = if screen_reader {
+            format!("{}.", describe(&guess, feedback))
+        } else {
+            Correctness::emoji_row(feedback)
+        };
+        println!("{} {:.1}s", row, elapsed.as_secs_f64());
+        rows.push((row, elapsed));
+        solved = mask == Correctness::SOLVED;
+        start = Instant::now();
+    }
+
+    let total: Duration = rows.iter().map(|(_, t)| *t).sum();
+    if solved {
+        say!(HostSolved, answer, format!("{:.1}", total.as_secs_f64()));
+    } else {
+        say!(HostAnswer, answer);
+    }
+    let mut solver = Recorder::new(factory.new_guesser());
+    match w.play(answer, &mut solver) {
+        Some(s) => {
+            let solver_total: Duration = solver.times.iter().sum();
+            say!(
+                SolverTook,
+                name,
+                s,
+                format!("{:.1}", solver_total.as_secs_f64())
+            );
+        }
+        None => say!(SolverFailed, name),
+    }
+
+    let score = if solved {
+        rows.len().to_string()
+    } else {
+        "X".to_string()
+    };
+    println!();
+    println!(
+        "Wordle {} {}/6 in {:.1}s",
+        puzzle,
+        score,
+        total.as_secs_f64()
+    );
+    for (row, elapsed) in &rows {
+        println!("{} {:.1}s", row, elapsed.as_secs_f64());
+    }
+}
+
+/// Plays the puzzle of the day (or the one numbered `puzzle`), revealing the solver's guesses one
+/// at a time as enter is pressed, and then prints the share grid.
+#[cfg(feature = "net")]
+fn today<G>(
+    factory: &impl GuesserFactory<Guesser = G>,
+    name: &str,
+    puzzle: Option<usize>,
+    args: &Args,
+) where
+    G: Guesser,
+{
+    let answers = Answers::in_use();
+    let number = puzzle.unwrap_or_else(wordle_solver::answers::todays_puzzle);
+    let answer = match puzzle.filter(|&puzzle| puzzle < answers.len()) {
+        Some(puzzle) => match answers.answer_for(puzzle) {
+            Some(answer) => answer,
+            None => {
+                eprintln!("{}", message(Message::NotFamilyFriendly, &[&puzzle]));
+                std::process::exit(1);
+            }
+        },
+        None => match wordle_solver::daily::DailyPuzzle::fetch(number) {
+            // Not saying what the answer is, since it is the puzzle of the day.
+            Ok(daily) => match algorithms::WordIndex::of(&daily.solution) {
+                Some(idx) => idx.word(),
+                None => {
+                    eprintln!("the answer to puzzle {} is not in the dictionary", number);
+                    std::process::exit(1);
+                }
+            },
+            Err(e) => match answers.answer_for(number) {
+                Some(answer) => {
+                    eprintln!("{}, so playing the built-in answer instead", e);
+                    answer
+                }
+                None => {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                }
+            },
+        },
+    };
+    let game = wordle_solver::Wordle::new().play_game(answer, factory.new_guesser(), args.mode);
+
+    say!(TodayIntro, number, name);
+    let mut lines = input_lines(args.scripted_input.as_deref());
+    for ((guess, mask), remaining) in game.guesses.iter().zip(&game.masks).zip(&game.remaining) {
+        print!("> ");
+        io::stdout().flush().expect("could not flush stdout");
+        match lines.next() {
+            Some(line) if line.trim() != "quit" => {}
+            _ => return,
+        }
+        let feedback = &mask[..letter_count(answer)];
+        if args.screen_reader {
+            println!("{}: {}.", guess, describe(guess, feedback));
+        } else {
+            let row = Correctness::emoji_row(feedback);
+            println!("{} {} ({} left)", row, guess, remaining);
+        }
+    }
+    println!();
+    println!("{}", game.share_string(number));
+}
+
+/// How many different games the batch command remembers suggestions for.
+const BATCH_CACHE_SIZE: usize = 100_000;
+
+/// Prints the suggestion for every game read from `file` (or standard input), working out the
+/// suggestion for each different game only once.
+/// Suggests the next guess for each game read from `file`, on a thread for each CPU if the
+/// guessers can be created from other threads (by `shared`).
+fn batch<G, S>(
+    factory: &impl GuesserFactory<Guesser = G>,
+    shared: Option<&S>,
+    name: &str,
+    file: Option<&Path>,
+) where
+    G: Guesser,
+    S: GuesserFactory + Sync,
+{
+    let input: Box<dyn BufRead> = match file {
+        Some(path) => match File::open(path) {
+            Ok(f) => Box::new(BufReader::new(f)),
+            Err(e) => {
+                eprintln!("could not open '{}': {}", path.display(), e);
+                std::process::exit(1);
+            }
+        },
+        None => Box::new(BufReader::new(io::stdin())),
+    };
+    let mut histories = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        let line = line.expect("could not read the games");
+        let words: Vec<_> = line.split_whitespace().collect();
+        match read_history(&words) {
+            Ok(history) => histories.push(history),
+            Err(e) => {
+                eprintln!("line {}: {}", i + 1, e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let start = Instant::now();
+    let mut cache = HintCache::new(BATCH_CACHE_SIZE, Duration::MAX);
+    let version = dictionary_info().version();
+    let suggestions = match shared {
+        Some(shared) => {
+            let workers = std::thread::available_parallelism().map_or(1, |n| n.get());
+            let locked = Mutex::new(cache);
+            let suggestions =
+                hints::suggest_parallel_with(shared, name, &version, &histories, &locked, workers);
+            cache = locked.into_inner().expect("the hint cache was poisoned");
+            suggestions
+        }
+        None => hints::suggest_all(factory, name, &version, &histories, &mut cache),
+    };
+    for suggestion in suggestions {
+        println!("{}", suggestion.as_deref().unwrap_or("-"));
+    }
+    eprintln!(
+        "{} games ({} different) in {:.2}s",
+        histories.len(),
+        cache.misses(),
+        start.elapsed().as_secs_f64()
+    );
+}
+
+fn selfcheck<G>(
+    factory: &impl GuesserFactory<Guesser = G>,
+    sample: usize,
+    seed: u64,
+    max_average: f64,
+    max_failure_rate: f64,
+) where
+    G: Guesser,
+{
+    let answers = Answers::in_use().sample(sample, seed);
+    let stats = wordle_solver::evaluate_all_with(
+        answers.into_iter().map(|(_, answer)| answer),
+        factory,
+        &EvaluateOptions::default(),
+    );
+    let average = stats.average_score();
+    let failure_rate = stats.failure_rate();
+    println!("average score {:.4} (limit {})", average, max_average);
+    println!(
+        "failure rate {:.4} (limit {})",
+        failure_rate, max_failure_rate
+    );
+    if average > max_average || failure_rate > max_failure_rate {
+        eprintln!("selfcheck failed");
+        std::process::exit(1);
+    }
+    println!("selfcheck passed");
+}
+
+fn compare_precision(
+    name: &str,
+    lambda: Option<f64>,
+    budget: Option<Duration>,
+    sample: usize,
+    seed: u64,
+) {
+    use algorithms::Precision;
+
+    if budget.is_some() {
+        eprintln!("--time-per-game makes the guesses depend on timing, so they can't be compared");
+        std::process::exit(1);
+    }
+    let lambda = lambda.unwrap_or(1.0);
+    let new_guesser = |precision| -> Box<dyn Guesser> {
+        match name {
+            "escore" => Box::new(algorithms::Escore::new().with_precision(precision)),
+            "sigmoid" => Box::new(
+                algorithms::Sigmoid::new()
+                    .with_lambda(lambda)
+                    .with_precision(precision),
+            ),
+            "cache" => Box::new(
+                algorithms::Cached::new()
+                    .with_lambda(lambda)
+                    .with_precision(precision),
+            ),
+            _ => {
+                eprintln!("only the escore, sigmoid and cache implementations have a precision");
+                std::process::exit(1);
+            }
+        }
+    };
+
+    let w = wordle_solver::Wordle::new();
+    let answers = Answers::in_use().sample(sample, seed);
+    let mut games = Vec::new();
+    for precision in [Precision::F64, Precision::F32] {
+        let mut guesser = Recorder::new(new_guesser(precision));
+        let mut played = Vec::new();
+        let mut score = 0;
+        let mut runtime = Duration::ZERO;
+        for &(_, answer) in &answers {
+            guesser.reset();
+            score += w.play(answer, &mut guesser).unwrap_or(0);
+            runtime += guesser.times.iter().sum::<Duration>();
+            played.push(std::mem::take(&mut guesser.guesses));
+        }
+        println!(
+            "{} average score {:.4} in {:.2}s",
+            precision,
+            score as f64 / answers.len().max(1) as f64,
+            runtime.as_secs_f64()
+        );
+        games.push(played);
+    }
+    let changed = games[0]
+        .iter()
+        .zip(&games[1])
+        .filter(|(a, b)| a != b)
+        .count();
+    println!("{} of {} games guessed differently", changed, answers.len());
+}
+
+/// Plays a sample of answers with each of the implementations called `names`, and prints the
+/// percentiles of how long their guesses took in each round.
+fn latency(registry: &Registry, names: &[String], sample: usize, seed: u64) {
+    let w = wordle_solver::Wordle::new();
+    let answers = Answers::in_use().sample(sample, seed);
+    for (i, name) in names.iter().enumerate() {
+        let Some(factory) = registry.get(name) else {
+            eprintln!(
+                "unknown implementation '{}', expected one of: {}",
+                name,
+                registry.names().collect::<Vec<_>>().join(", ")
+            );
+            std::process::exit(1);
+        };
+        let mut guesser = Recorder::new(factory.new_guesser());
+        let mut latencies = Latencies::new();
+        for &(_, answer) in &answers {
+            guesser.reset();
+            w.play(answer, &mut guesser);
+            latencies.record_game(&guesser.times);
+        }
+        if i > 0 {
+            println!();
+        }
+        println!("{}", name);
+        print!("{}", latencies);
+    }
+}
+
+#[cfg(feature = "server")]
+fn serve(
+    registry: &Registry,
+    name: &str,
+    addr: &str,
+    workers: Option<usize>,
+    rate_limit: Option<wordle_solver::server::RateLimit>,
+) {
+    if registry.get_shared(name).is_none() {
+        eprintln!(
+            "{} can't be served, since it can't be played in parallel",
+            name
+        );
+        std::process::exit(1);
+    }
+    let listener = match std::net::TcpListener::bind(addr) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("could not listen on {}: {}", addr, e);
+            std::process::exit(1);
+        }
+    };
+    eprintln!("answering POST /solve on {} with {}", addr, name);
+    let mut server = wordle_solver::server::Server::new(registry, name);
+    if let Some(workers) = workers {
+        server = server.with_workers(workers);
+    }
+    if let Some(limit) = rate_limit {
+        eprintln!("allowing each client {}", limit);
+        server = server.with_rate_limit(limit);
+    }
+    server.serve(listener);
+}
+
+fn absurdle(registry: &Registry, names: &[String], mode: GameMode) {
+    let w = wordle_solver::AdversarialWordle::new();
+    let width = names.iter().map(String::len).max().unwrap_or(0);
+    for name in names {
+        let Some(factory) = registry.get(name) else {
+            eprintln!(
+                "unknown implementation '{}', expected one of: {}",
+                name,
+                registry.names().collect::<Vec<_>>().join(", ")
+            );
+            std::process::exit(1);
+        };
+        let mut guesser = Recorder::new(factory.new_guesser());
+        let score = match w.try_play(&mut guesser, mode) {
+            Ok(score) => score.to_string(),
+            Err(wordle_solver::WordleError::TooManyGuesses(_)) => "never".to_string(),
+            Err(e) => {
+                eprintln!("{} broke the rules: {}", name, e);
+                std::process::exit(1);
+            }
+        };
+        println!(
+            "{:<width$} {:>5}  {}",
+            name,
+            score,
+            guesser.guesses.join(" "),
+            width = width
+        );
+    }
+}
+
+fn calibrate(
+    name: &str,
+    args: &Args,
+    width: algorithms::SearchWidth,
+    target: f64,
+    check_every: usize,
+) {
+    if args.time_per_game.is_some() {
+        eprintln!("--time-per-game cuts the search short too, so it can't be calibrated");
+        std::process::exit(1);
+    }
+    if args.search_schedule.is_some() {
+        eprintln!("only a single --search-fraction can be calibrated, not a --search-schedule");
+        std::process::exit(1);
+    }
+    if !(0.0..0.5).contains(&target) {
+        eprintln!("--target must be at least 0 and less than 0.5");
+        std::process::exit(1);
+    }
+    let calibration = Arc::new(algorithms::Calibration::new(width, target, check_every));
+    let lambda = args.lambda.unwrap_or(1.0);
+    let new_guesser = || -> Box<dyn Guesser> {
+        match name {
+            "cutoff" => Box::new(algorithms::Cutoff::new().with_calibration(calibration.clone())),
+            "cache" => Box::new(
+                algorithms::Cached::new()
+                    .with_lambda(lambda)
+                    .with_calibration(calibration.clone()),
+            ),
+            _ => {
+                eprintln!("only the cutoff and cache implementations can be calibrated");
+                std::process::exit(1);
+            }
+        }
+    };
+
+    let w = wordle_solver::Wordle::new();
+    let answers = Answers::in_use();
+    let games = args.games.unwrap_or(usize::MAX).min(answers.len());
+    let mut guesser = new_guesser();
+    let mut score = 0;
+    for (_, answer) in answers.iter().take(games) {
+        guesser.reset();
+        score += w.play(answer, &mut guesser).unwrap_or(0);
+    }
+    let (checks, misses) = calibration.checks();
+    let fraction = calibration.width().fraction;
+    println!(
+        "missed the best guess in {} of {} checked guesses (target {})",
+        misses, checks, target
+    );
+    println!(
+        "average score {:.4} while calibrating",
+        score as f64 / games.max(1) as f64
+    );
+    println!("play with --search-fraction {:.4}", fraction);
+}
+
+fn fingerprint<G>(factory: &impl GuesserFactory<Guesser = G>, sample: usize, seed: u64)
+where
+    G: Guesser,
+{
+    let w = wordle_solver::Wordle::new();
+    let mut games = String::new();
+    for (_, answer) in Answers::in_use().sample(sample, seed) {
+        let mut guesser = Recorder::new(factory.new_guesser());
+        w.play(answer, &mut guesser);
+        games.push_str(answer);
+        for guess in &guesser.guesses {
+            games.push(' ');
+            games.push_str(guess);
+        }
+        games.push('\n');
+    }
+    println!("{:016x}", fnv1a(games.bytes()));
+}
+
+fn compile<G>(
+    factory: &impl GuesserFactory<Guesser = G>,
+    games: Option<usize>,
+    output: &Path,
+    hard_mode: bool,
+) where
+    G: Guesser,
+{
+    let answers = Answers::in_use();
+    let games = games.unwrap_or(usize::MAX).min(answers.len());
+    let tree = match Tree::compile(
+        factory,
+        answers.iter().take(games).map(|(_, answer)| answer),
+    ) {
+        Ok(tree) => tree.with_hard_mode(hard_mode),
+        Err(e) => {
+            eprintln!("could not compile a tree: {}", e);
+            std::process::exit(1);
+        }
+    };
+    match File::create(output).and_then(|f| tree.write(io::BufWriter::new(f))) {
+        Ok(()) => println!("wrote {} answers to '{}'", games, output.display()),
+        Err(e) => {
+            eprintln!("could not write '{}': {}", output.display(), e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Checks the files and options given, printing everything wrong with them and how to fix it,
+/// and exits with an error if anything would stop the solver from working.
+fn doctor(args: &Args) {
+    use std::collections::HashSet;
+    use wordle_solver::doctor::{self, Finding, Severity};
+
+    let mut findings = Vec::new();
+    let mut read = |path: &Path, what: &str| match std::fs::read_to_string(path) {
+        Ok(text) => Some(text),
+        Err(e) => {
+            findings.push(Finding::problem(
+                format!("the {} '{}' can't be read: {}", what, path.display(), e),
+                "check the path, and that you are allowed to read it",
+            ));
+            None
+        }
+    };
+    let dictionary_file = args
+        .dictionary_file
+        .as_ref()
+        .map(|path| read(path, "dictionary file"));
+    let answers_file = args
+        .answers_file
+        .as_ref()
+        .map(|path| read(path, "answers file"));
+    let used_file = args
+        .exclude_used
+        .as_ref()
+        .map(|path| read(path, "used words file"));
+    let strategy_file = args.strategy.as_ref().map(|path| read(path, "strategy"));
+    let tree_file = args.tree.as_ref().map(|path| read(path, "tree"));
+
+    let words = match dictionary_file {
+        Some(Some(text)) => {
+            let (found, words) = doctor::check_dictionary(&text);
+            findings.extend(found);
+            words
+        }
+        Some(None) => None,
+        None => Some(Vec::new()),
+    };
+    let guesses: HashSet<&str> = match &words {
+        Some(words) if !words.is_empty() => words.iter().map(|(w, _)| w.as_str()).collect(),
+        _ => wordle_solver::DICTIONARY.iter().map(|&(w, _)| w).collect(),
+    };
+    let len = guesses.iter().next().map_or(5, |w| w.len());
+    let answers: Vec<String> = match &answers_file {
+        Some(Some(text)) => {
+            let answers: Vec<_> = text.split_whitespace().collect();
+            findings.extend(doctor::check_answers(&answers, &guesses, len));
+            answers.into_iter().map(str::to_string).collect()
+        }
+        _ => Vec::new(),
+    };
+    let used: Vec<String> = match &used_file {
+        Some(Some(text)) => text
+            .split_whitespace()
+            .map(|word| word.to_lowercase())
+            .collect(),
+        _ => Vec::new(),
+    };
+    let unknown = used
+        .iter()
+        .filter(|w| !guesses.contains(w.as_str()))
+        .count();
+    if unknown > 0 {
+        findings.push(Finding::warning(
+            format!("{} of the used words are not in the dictionary", unknown),
+            "they make no difference, so check the file is the list of past answers",
+        ));
+    }
+
+    let set_up = !findings.iter().any(|f| f.severity == Severity::Problem);
+    if let (true, Some(words)) = (set_up, words) {
+        let profile = Profile {
+            spelling: args.dictionary,
+            family_friendly: args.family_friendly,
+            deprioritise_inflections: args.deprioritise_inflections,
+            used,
+            no_repeats: args.no_repeats,
+            words,
+            answers,
+            duplicates: args.duplicates,
+            answers_only: args.answers_only,
+        };
+        let cache_dir = default_cache_dir().filter(|_| !args.no_dictionary_cache);
+        if let Some(dir) = &cache_dir {
+            cache_dictionaries_in(dir);
+        }
+        match set_profile(profile) {
+            Ok(()) => {
+                findings.extend(doctor::check_in_use());
+                if args.answers_file.is_none() {
+                    let answers: Vec<_> = Answers::in_use().iter().map(|(_, a)| a).collect();
+                    let guesses = dictionary().iter().map(|&(w, _)| w).collect();
+                    findings.extend(doctor::check_answers(&answers, &guesses, word_len()));
+                }
+                if let Some(dir) = &cache_dir {
+                    findings.extend(doctor::check_cache_dir(dir));
+                }
+                if let Some(Some(text)) = &strategy_file {
+                    findings.extend(doctor::check_strategy(text));
+                }
+                if let Some(Some(text)) = &tree_file {
+                    let answers: Vec<_> = Answers::in_use().iter().map(|(_, a)| a).collect();
+                    findings.extend(doctor::check_tree(text, &answers));
+                }
+            }
+            Err(e) => findings.push(Finding::problem(
+                format!("the dictionary can't be set up: {}", e),
+                "fix the problems above",
+            )),
+        }
+    } else if matches!(strategy_file, Some(Some(_))) || matches!(tree_file, Some(Some(_))) {
+        findings.push(Finding::warning(
+            "the strategy and tree weren't checked, since they depend on the dictionary",
+            "fix the problems above, and check again",
+        ));
+    }
+
+    for finding in &findings {
+        println!("{}", finding);
+    }
+    if findings.iter().any(|f| f.severity == Severity::Problem) {
+        std::process::exit(1);
+    }
+}
+
+fn verify_strategy(path: &Path) {
+    let tree = match std::fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|s| s.parse::<Tree>())
+    {
+        Ok(tree) => tree,
+        Err(e) => {
+            eprintln!("could not load tree '{}': {}", path.display(), e);
+            std::process::exit(1);
+        }
+    };
+    let answers: Vec<_> = Answers::in_use().iter().map(|(_, answer)| answer).collect();
+    let verification = tree.verify(&answers);
+    for problem in &verification.problems {
+        println!("{}", problem);
+    }
+    println!(
+        "solves {} of {} answers, average score {:.4}, at most {} guesses",
+        verification.solved,
+        answers.len(),
+        verification.average_score(),
+        verification.max_guesses
+    );
+    if !verification.is_ok() {
+        eprintln!("'{}' is not a valid strategy", path.display());
+        std::process::exit(1);
+    }
+    println!("'{}' is a valid strategy", path.display());
+}
+
+/// Compares the number of guesses every built-in implementation (as configured by the flags)
+/// takes for each answer against those `baseline` takes.
+fn regret(registry: &Registry, games: Option<usize>, baseline: &Path) {
+    let tree = match std::fs::read_to_string(baseline)
+        .map_err(|e| e.to_string())
+        .and_then(|s| s.parse::<Tree>())
+    {
+        Ok(tree) => tree,
+        Err(e) => {
+            eprintln!("could not load tree '{}': {}", baseline.display(), e);
+            std::process::exit(1);
+        }
+    };
+    let w = wordle_solver::Wordle::new();
+    let answers: Vec<_> = Answers::in_use()
+        .iter()
+        .take(games.unwrap_or(usize::MAX))
+        .map(|(_, answer)| answer)
+        .collect();
+    if answers.is_empty() {
+        eprintln!("there are no answers to compare");
+        std::process::exit(1);
+    }
+    let best: Vec<_> = answers
+        .iter()
+        .map(|answer| match tree.score(answer) {
+            Some(b) => b,
+            None => {
+                eprintln!("'{}' does not solve '{}'", baseline.display(), answer);
+                std::process::exit(1);
+            }
+        })
+        .collect();
+    let workers = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let builtin = Registry::builtin();
+    let mut summaries = Vec::new();
+    println!("implementation answer guesses baseline regret");
+    for name in builtin.names() {
+        let scores = match registry.get_shared(name) {
+            Some(shared) => play_parallel_with(
+                answers.iter().copied(),
+                &shared,
+                workers,
+                |guesser, answer| w.play(answer, guesser),
+            ),
+            None => {
+                let factory = registry.get(name).expect("built-ins are always registered");
+                answers
+                    .iter()
+                    .map(|answer| w.play(answer, factory()))
+                    .collect()
+            }
+        };
+        let mut regrets = Vec::new();
+        for ((&answer, s), &b) in answers.iter().zip(scores).zip(&best) {
+            let Some(s) = s else {
+                eprintln!("{} failed to guess '{}'", name, answer);
+                std::process::exit(1);
+            };
+            regrets.push((answer, s, b));
+        }
+
+        // Worst regret first, and otherwise in alphabetical order.
+        regrets.sort_by_key(|&(answer, s, b)| (std::cmp::Reverse(s as i64 - b as i64), answer));
+        for &(answer, s, b) in &regrets {
+            if s != b {
+                println!("{} {} {} {} {:+}", name, answer, s, b, s as i64 - b as i64);
+            }
+        }
+        let score: usize = regrets.iter().map(|&(_, s, _)| s).sum();
+        let worse = regrets.iter().filter(|&&(_, s, b)| s > b).count();
+        let better = regrets.iter().filter(|&&(_, s, b)| s < b).count();
+        summaries.push((name, score, worse, better));
+    }
+
+    let games = answers.len();
+    let best = best.iter().sum::<usize>() as f64 / games as f64;
+    for (name, score, worse, better) in summaries {
+        println!(
+            "{}: average score {:.4} against {:.4}, worse on {} and better on {} of {} answers",
+            name,
+            score as f64 / games as f64,
+            best,
+            worse,
+            better,
+            games
+        );
+    }
+}
+
+fn evaluate_openers<G>(
+    factory: &impl GuesserFactory<Guesser = G>,
+    shared: Option<&(impl GuesserFactory + Sync)>,
+    games: Option<usize>,
+    openers: &[String],
+    top: usize,
+    output: Option<&Path>,
+) where
+    G: Guesser,
+{
+    let w = wordle_solver::Wordle::new();
+    let answers = Answers::in_use();
+    let games = games.unwrap_or(usize::MAX).min(answers.len());
+    let candidates: Vec<_> = candidate_answers().iter().map(|&(word, _)| word).collect();
+    let workers = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let search = openers.is_empty();
+    let openers: Vec<(String, f64)> = if search {
+        let words: Vec<_> = dictionary().iter().map(|&(word, _)| word).collect();
+        let table = wordle_solver::analysis::rank_openers(&words, &candidates, workers);
+        table
+            .into_iter()
+            .take(top)
+            .map(|stats| (stats.word.to_string(), stats.entropy))
+            .collect()
+    } else {
+        let mut chosen = Vec::new();
+        for opener in openers {
+            let opener = opener.to_lowercase();
+            let Some(&(word, _)) = dictionary()
+                .iter()
+                .find(|&&(word, _)| word == opener && w.is_allowed(word))
+            else {
+                eprintln!("'{}' is not in the dictionary", opener);
+                std::process::exit(1);
+            };
+            let stats = wordle_solver::analysis::opener_table(&[word], &candidates);
+            chosen.push((opener, stats[0].entropy));
+        }
+        chosen
+    };
+
+    let mut rows = Vec::new();
+    for (opener, entropy) in openers {
+        let answers: Vec<_> = answers.iter().take(games).map(|(_, a)| a).collect();
+        let scores = match shared {
+            Some(shared) => play_parallel_with(
+                answers.iter().copied(),
+                &|| algorithms::Opener::new(opener.clone(), shared.new_guesser()),
+                workers,
+                |guesser, answer| w.play(answer, guesser),
+            ),
+            None => answers
+                .iter()
+                .map(|answer| {
+                    w.play(
+                        answer,
+                        algorithms::Opener::new(opener.clone(), factory.new_guesser()),
+                    )
+                })
+                .collect(),
+        };
+        // counts[i] is how many answers took i + 1 guesses.
+        let mut counts = Vec::new();
+        for (answer, score) in answers.iter().zip(scores) {
+            let Some(s) = score else {
+                eprintln!(
+                    "failed to guess '{}' after opening with '{}'",
+                    answer, opener
+                );
+                std::process::exit(1);
+            };
+            if counts.len() < s {
+                counts.resize(s, 0);
+            }
+            counts[s - 1] += 1;
+        }
+        eprintln!("evaluated '{}'", opener);
+        rows.push((opener, entropy, counts));
+    }
+    let total =
+        |counts: &[usize]| -> usize { counts.iter().enumerate().map(|(i, c)| (i + 1) * c).sum() };
+    if search {
+        rows.sort_by_key(|(_, _, counts)| total(counts));
+    }
+
+    // Every row gets a column for every score any opener needed, and at least up to six.
+    let max = rows
+        .iter()
+        .map(|(_, _, c)| c.len())
+        .max()
+        .unwrap_or(0)
+        .max(6);
+    let mut csv = String::from("opener,entropy,games,average");
+    for s in 1..=max {
+        csv += &format!(",{}", s);
+    }
+    csv += "\n";
+    for (opener, entropy, counts) in &rows {
+        csv += &format!(
+            "{},{:.4},{},{:.4}",
+            opener,
+            entropy,
+            games,
+            total(counts) as f64 / games as f64
+        );
+        for s in 0..max {
+            csv += &format!(",{}", counts.get(s).copied().unwrap_or(0));
+        }
+        csv += "\n";
+    }
+
+    match output {
+        Some(path) => {
+            if let Err(e) = std::fs::write(path, csv) {
+                eprintln!("could not write '{}': {}", path.display(), e);
+                std::process::exit(1);
+            }
+        }
+        None => print!("{}", csv),
+    }
+}
+
+fn precompute(output: Option<&Path>, layout: algorithms::CacheLayout) {
+    if word_len() > 5 {
+        eprintln!("the cache implementation only plays words of up to five letters");
+        std::process::exit(1);
+    }
+    let path = match output.map(Path::to_path_buf) {
+        Some(path) => path,
+        None => match algorithms::PatternMatrix::default_path(layout) {
+            Some(path) => path,
+            None => {
+                eprintln!("there is no cache directory to save to, so pass --output");
+                std::process::exit(1);
+            }
+        },
+    };
+    let workers = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let start = std::time::Instant::now();
+    let matrix = algorithms::PatternMatrix::compute(layout, workers);
+    if let Err(e) = matrix.save(&path) {
+        eprintln!("could not write '{}': {}", path.display(), e);
+        std::process::exit(1);
+    }
+    println!(
+        "saved the masks of {} words to '{}' in {:.1?}",
+        matrix.len(),
+        path.display(),
+        start.elapsed()
+    );
+}
+
+fn entropy_table(output: Option<&Path>, gpu: bool) {
+    let words: Vec<_> = dictionary().iter().map(|&(word, _)| word).collect();
+    let table = if gpu {
+        #[cfg(feature = "gpu")]
+        match wordle_solver::gpu::Gpu::new().and_then(|gpu| gpu.opener_table(&words, &words)) {
+            Ok(table) => table,
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        #[cfg(not(feature = "gpu"))]
+        unreachable!("--gpu needs the gpu feature")
+    } else {
+        let workers = std::thread::available_parallelism().map_or(1, |n| n.get());
+        wordle_solver::analysis::rank_openers(&words, &words, workers)
+    };
+    let mut csv = String::from("word,entropy,worst_case,expected_remaining\n");
+    for stats in table {
+        csv += &format!(
+            "{},{:.6},{},{:.4}\n",
+            stats.word, stats.entropy, stats.worst_case, stats.expected_remaining
+        );
+    }
+    match output {
+        Some(path) => {
+            if let Err(e) = std::fs::write(path, csv) {
+                eprintln!("could not write '{}': {}", path.display(), e);
+                std::process::exit(1);
+            }
+        }
+        None => print!("{}", csv),
+    }
+}
+
+/// Parses guesses each followed by their feedback, e.g. `tares XYYXX`, exiting if they aren't.
+fn parse_history(history: &[String]) -> Vec<Guess<'static>> {
+    match read_history(history) {
+        Ok(guesses) => guesses,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Reads guesses each followed by its feedback, e.g. tares XYYXX.
+fn read_history(history: &[impl AsRef<str>]) -> Result<Vec<Guess<'static>>, String> {
+    if !history.len().is_multiple_of(2) {
+        return Err("expected feedback after every guess, e.g. tares XYYXX".to_string());
+    }
+    let mut guesses = Vec::new();
+    for pair in history.chunks(2) {
+        let (word, feedback) = (pair[0].as_ref(), pair[1].as_ref());
+        let Some(mask) = Correctness::parse_mask(feedback, letter_count(word)) else {
+            return Err(format!("'{}' is not valid feedback, e.g. GYXXY", feedback));
+        };
+        guesses.push(Guess {
+            word: std::borrow::Cow::Owned(word.to_lowercase()),
+            mask,
+        });
+    }
+    Ok(guesses)
+}
+
+fn pareto(history: &[String]) {
+    let guesses = parse_history(history);
+    println!("word entropy probability");
+    for point in wordle_solver::analysis::pareto(&guesses) {
+        println!(
+            "{} {:.4} {:.4}",
+            point.word, point.entropy, point.probability
+        );
+    }
+}
+
+/// Prints how a guess was chosen as a block of lines, all at once so that the blocks of games
+/// played in parallel don't run into each other.
+fn print_explanation(explanation: &algorithms::GuessExplanation, to_stderr: bool) {
+    let mut block = format!(
+        "guess {}: {} from {} candidates\n",
+        explanation.round, explanation.guess, explanation.remaining
+    );
+    block += "  guess  information p(word) expected score\n";
+    for guess in &explanation.top {
+        let expected_score = match guess.expected_score {
+            Some(score) => format!("{:.4}", score),
+            None => "-".to_string(),
+        };
+        block += &format!(
+            "  {:5} {:11.4} {:7.4} {}\n",
+            guess.word, guess.information, guess.probability, expected_score
+        );
+    }
+    if to_stderr {
+        eprint!("{}", block);
+    } else {
+        print!("{}", block);
+    }
+}
+
+fn explain(history: &[String], guess: &str, chart: bool) {
+    // How many of the candidates giving each feedback to list.
+    const EXAMPLES: usize = 5;
+    // How wide the bar for the likeliest feedback is.
+    const CHART_WIDTH: usize = 40;
+
+    let history = parse_history(history);
+    let guess = guess.to_lowercase();
+    if !dictionary().iter().any(|&(word, _)| word == guess) {
+        eprintln!("'{}' is not an allowed guess", guess);
+        std::process::exit(1);
+    }
+    let buckets = wordle_solver::analysis::distribution(&history, &guess);
+    if buckets.is_empty() {
+        eprintln!("no word in the dictionary fits that feedback");
+        std::process::exit(1);
+    }
+    let candidates: usize = buckets.iter().map(|b| b.candidates.len()).sum();
+    let evaluation = wordle_solver::analysis::evaluate_guess(&history, &guess);
+    println!(
+        "{} splits {} candidates into {} groups: {:.4} bits, {:.1} candidates left on average \
+         ({} at worst), {:.4} guesses to go",
+        guess,
+        candidates,
+        buckets.len(),
+        evaluation.entropy,
+        evaluation.expected_remaining,
+        evaluation.worst_case,
+        evaluation.expected_score
+    );
+    if chart {
+        print!(
+            "{}",
+            wordle_solver::analysis::chart(&buckets, letter_count(&guess), CHART_WIDTH)
+        );
+        return;
+    }
+    println!("pattern probability candidates");
+    for bucket in &buckets {
+        let mut examples = bucket.candidates[..bucket.candidates.len().min(EXAMPLES)].join(" ");
+        if bucket.candidates.len() > EXAMPLES {
+            examples += " ...";
+        }
+        println!(
+            "{} {:.4} {} ({})",
+            Correctness::format_mask(&bucket.mask[..letter_count(&guess)]),
+            bucket.probability,
+            bucket.candidates.len(),
+            examples
+        );
+    }
 }
@@ -0,0 +1,375 @@
+//! Guessers compiled separately from this crate, and loaded from a shared library at runtime.
+//!
+//! A plugin is a `cdylib` exporting these functions, which [`export_guesser!`] generates for any
+//! `Guesser + Default` type:
+//!
+//! ```c
+//! uint32_t wordle_plugin_abi_version(void);
+//! const char *wordle_plugin_name(void);
+//! void *wordle_guesser_new(void);
+//! void wordle_guesser_free(void *guesser);
+//! bool wordle_guesser_guess(void *guesser, const PluginGuess *history, size_t len, uint8_t out[5]);
+//! ```
+//!
+//! `wordle_guesser_guess` writes the five letters of its guess to `out`, and returns false if it
+//! could not make one. Plugins only play five-letter words, whatever the dictionary in use.
+//!
+//! [`export_guesser!`]: crate::export_guesser
+
+use crate::{Correctness, Guess, Guesser, GuesserFactory};
+use std::any::Any;
+use std::borrow::Cow;
+use std::ffi::c_void;
+use std::rc::Rc;
+
+/// Bumped whenever the functions a plugin exports change in an incompatible way.
+pub const ABI_VERSION: u32 = 1;
+
+/// A single guess as it is passed across the plugin boundary.
+///
+/// Each byte of `mask` is 0 for green, 1 for yellow or 2 for gray, as in [`Correctness::pack`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PluginGuess {
+    pub word: [u8; 5],
+    pub mask: [u8; 5],
+}
+
+impl PluginGuess {
+    /// `guess` as it is passed to a plugin, unless its word isn't five bytes long.
+    fn from_guess(guess: &Guess) -> Option<Self> {
+        let word = guess.word.as_bytes().try_into().ok()?;
+        let mut mask = [0; 5];
+        for (m, c) in mask.iter_mut().zip(guess.feedback()) {
+            *m = match c {
+                Correctness::Correct => 0,
+                Correctness::Misplaced => 1,
+                Correctness::Wrong => 2,
+            };
+        }
+        Some(Self { word, mask })
+    }
+
+    fn to_guess(self) -> Option<Guess<'static>> {
+        let mut mask = [Correctness::Wrong; 5];
+        for (c, m) in mask.iter_mut().zip(self.mask) {
+            *c = match m {
+                0 => Correctness::Correct,
+                1 => Correctness::Misplaced,
+                2 => Correctness::Wrong,
+                _ => return None,
+            };
+        }
+        Some(Guess {
+            word: Cow::Owned(String::from_utf8(self.word.to_vec()).ok()?),
+            mask: Correctness::mask(&mask),
+        })
+    }
+}
+
+type NewFn = unsafe extern "C" fn() -> *mut c_void;
+type FreeFn = unsafe extern "C" fn(*mut c_void);
+type GuessFn = unsafe extern "C" fn(*mut c_void, *const PluginGuess, usize, *mut [u8; 5]) -> bool;
+
+struct Vtable {
+    new: NewFn,
+    free: FreeFn,
+    guess: GuessFn,
+    // Keeps the library the functions above live in loaded.
+    _library: Option<Box<dyn Any>>,
+}
+
+/// A guesser provided by a plugin.
+pub struct Plugin {
+    name: String,
+    vtable: Rc<Vtable>,
+}
+
+impl Plugin {
+    /// Loads the plugin in the shared library at `path`.
+    ///
+    /// # Safety
+    ///
+    /// This runs arbitrary code from the library, which must export the functions described in
+    /// the [module documentation](self) with exactly those signatures.
+    #[cfg(feature = "plugins")]
+    pub unsafe fn load(path: impl AsRef<std::ffi::OsStr>) -> std::io::Result<Self> {
+        use std::io::Error;
+
+        let library = libloading::Library::new(path).map_err(Error::other)?;
+        let symbols = || -> Result<_, libloading::Error> {
+            let version: libloading::Symbol<unsafe extern "C" fn() -> u32> =
+                library.get(b"wordle_plugin_abi_version\0")?;
+            let name: libloading::Symbol<unsafe extern "C" fn() -> *const std::ffi::c_char> =
+                library.get(b"wordle_plugin_name\0")?;
+            let new: libloading::Symbol<NewFn> = library.get(b"wordle_guesser_new\0")?;
+            let free: libloading::Symbol<FreeFn> = library.get(b"wordle_guesser_free\0")?;
+            let guess: libloading::Symbol<GuessFn> = library.get(b"wordle_guesser_guess\0")?;
+            Ok((version(), name(), *new, *free, *guess))
+        };
+        let (version, name, new, free, guess) = symbols().map_err(Error::other)?;
+        if version != ABI_VERSION {
+            return Err(Error::other(format!(
+                "plugin was built for ABI version {}, expected {}",
+                version, ABI_VERSION
+            )));
+        }
+
+        Ok(Self::from_raw(
+            name,
+            Vtable {
+                new,
+                free,
+                guess,
+                _library: Some(Box::new(library)),
+            },
+        ))
+    }
+
+    #[cfg(any(feature = "plugins", test))]
+    unsafe fn from_raw(name: *const std::ffi::c_char, vtable: Vtable) -> Self {
+        Self {
+            name: std::ffi::CStr::from_ptr(name)
+                .to_string_lossy()
+                .into_owned(),
+            vtable: Rc::new(vtable),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Creates a new instance of the plugin's guesser, ready to play a game.
+    pub fn guesser(&self) -> PluginGuesser {
+        PluginGuesser {
+            state: unsafe { (self.vtable.new)() },
+            vtable: Rc::clone(&self.vtable),
+            history: Vec::new(),
+            calls: Vec::new(),
+        }
+    }
+}
+
+impl GuesserFactory for Plugin {
+    type Guesser = PluginGuesser;
+
+    fn new_guesser(&self) -> Self::Guesser {
+        self.guesser()
+    }
+}
+
+pub struct PluginGuesser {
+    vtable: Rc<Vtable>,
+    state: *mut c_void,
+    // The plugin's state can't be copied, so a clone is a new guesser that is told about every
+    // guess this one was asked for: the latest history, and how much of it each guess saw.
+    history: Vec<PluginGuess>,
+    calls: Vec<usize>,
+}
+
+impl PluginGuesser {
+    fn call(&mut self, len: usize) -> Option<[u8; 5]> {
+        let mut out = [0; 5];
+        let ok = unsafe { (self.vtable.guess)(self.state, self.history.as_ptr(), len, &mut out) };
+        ok.then_some(out)
+    }
+}
+
+impl Clone for PluginGuesser {
+    fn clone(&self) -> Self {
+        let mut clone = Self {
+            vtable: Rc::clone(&self.vtable),
+            state: unsafe { (self.vtable.new)() },
+            history: self.history.clone(),
+            calls: self.calls.clone(),
+        };
+        for len in self.calls.clone() {
+            clone.call(len).expect("plugin failed to repeat a guess");
+        }
+        clone
+    }
+}
+
+impl Drop for PluginGuesser {
+    fn drop(&mut self) {
+        unsafe { (self.vtable.free)(self.state) }
+    }
+}
+
+impl Guesser for PluginGuesser {
+    fn guess(&mut self, history: &[Guess]) -> Cow<'static, str> {
+        self.history = history
+            .iter()
+            .map(PluginGuess::from_guess)
+            .collect::<Option<_>>()
+            .expect("plugins only play five-letter words");
+        self.calls.push(history.len());
+        let out = self
+            .call(history.len())
+            .expect("plugin failed to make a guess");
+        String::from_utf8(out.to_vec())
+            .expect("plugin guessed a word that is not utf-8")
+            .into()
+    }
+
+    fn reset(&mut self) {
+        // The plugin can't be told to start over, so start over with a new instance of it.
+        unsafe {
+            (self.vtable.free)(self.state);
+            self.state = (self.vtable.new)();
+        }
+        self.history.clear();
+        self.calls.clear();
+    }
+}
+
+/// Runs `G::guess` on behalf of the functions generated by [`export_guesser!`].
+///
+/// [`export_guesser!`]: crate::export_guesser
+#[doc(hidden)]
+pub unsafe fn call_guess<G: Guesser>(
+    guesser: *mut c_void,
+    history: *const PluginGuess,
+    len: usize,
+    out: *mut [u8; 5],
+) -> bool {
+    std::panic::catch_unwind(|| {
+        let guesser = &mut *guesser.cast::<G>();
+        let history: Option<Vec<_>> = std::slice::from_raw_parts(history, len)
+            .iter()
+            .map(|g| g.to_guess())
+            .collect();
+        let guess = guesser.guess(&history?);
+        (*out).copy_from_slice(guess.as_bytes().get(..5)?);
+        Some(())
+    })
+    .ok()
+    .flatten()
+    .is_some()
+}
+
+/// Exports a `Guesser + Default` type from a `cdylib` crate so it can be loaded as a [`Plugin`].
+///
+/// ```ignore
+/// wordle_solver::export_guesser!(MyGuesser, "my-guesser");
+/// ```
+///
+/// [`Plugin`]: crate::plugin::Plugin
+#[macro_export]
+macro_rules! export_guesser {
+    ($guesser:ty, $name:literal) => {
+        #[no_mangle]
+        pub extern "C" fn wordle_plugin_abi_version() -> u32 {
+            $crate::plugin::ABI_VERSION
+        }
+
+        #[no_mangle]
+        pub extern "C" fn wordle_plugin_name() -> *const ::std::ffi::c_char {
+            concat!($name, "\0").as_ptr().cast()
+        }
+
+        #[no_mangle]
+        pub extern "C" fn wordle_guesser_new() -> *mut ::std::ffi::c_void {
+            Box::into_raw(Box::new(<$guesser as ::std::default::Default>::default())).cast()
+        }
+
+        /// # Safety
+        ///
+        /// `guesser` must have come from `wordle_guesser_new`, and not been freed before.
+        #[no_mangle]
+        pub unsafe extern "C" fn wordle_guesser_free(guesser: *mut ::std::ffi::c_void) {
+            drop(Box::from_raw(guesser.cast::<$guesser>()))
+        }
+
+        /// # Safety
+        ///
+        /// `guesser` must have come from `wordle_guesser_new`, `history` must point to `len`
+        /// guesses and `out` to five writable bytes.
+        #[no_mangle]
+        pub unsafe extern "C" fn wordle_guesser_guess(
+            guesser: *mut ::std::ffi::c_void,
+            history: *const $crate::plugin::PluginGuess,
+            len: usize,
+            out: *mut [u8; 5],
+        ) -> bool {
+            $crate::plugin::call_guess::<$guesser>(guesser, history, len, out)
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Plugin, PluginGuess, Vtable};
+    use crate::algorithms::Popular;
+    use crate::{Correctness, Guess, Guesser, Wordle};
+    use std::borrow::Cow;
+
+    crate::export_guesser!(Popular, "popular");
+
+    fn plugin() -> Plugin {
+        unsafe {
+            Plugin::from_raw(
+                wordle_plugin_name(),
+                Vtable {
+                    new: wordle_guesser_new,
+                    free: wordle_guesser_free,
+                    guess: wordle_guesser_guess,
+                    _library: None,
+                },
+            )
+        }
+    }
+
+    #[test]
+    fn converts_guesses() {
+        let guess = Guess {
+            word: Cow::Borrowed("tares"),
+            mask: Correctness::compute("cigar", "tares"),
+        };
+        let plugin_guess = PluginGuess::from_guess(&guess).unwrap();
+        assert_eq!(&plugin_guess.word, b"tares");
+        assert_eq!(plugin_guess.mask, [2, 1, 1, 2, 2]);
+        let round_trip = plugin_guess.to_guess().unwrap();
+        assert_eq!((round_trip.word, round_trip.mask), (guess.word, guess.mask));
+
+        // Longer words, and words with letters that take more than one byte, don't fit.
+        for word in ["tarest", "cafés"] {
+            let guess = Guess {
+                word: Cow::Borrowed(word),
+                mask: guess.mask,
+            };
+            assert!(PluginGuess::from_guess(&guess).is_none());
+        }
+    }
+
+    #[test]
+    fn name() {
+        assert_eq!(plugin().name(), "popular");
+    }
+
+    #[test]
+    fn plays_like_the_wrapped_guesser() {
+        let w = Wordle::new();
+        let plugin = plugin();
+        for answer in ["cigar", "rebut", "sissy"] {
+            assert_eq!(
+                w.play(answer, plugin.guesser()),
+                w.play(answer, Popular::default())
+            );
+        }
+    }
+
+    #[test]
+    fn clone_carries_on() {
+        let plugin = plugin();
+        let mut guesser = plugin.guesser();
+        let history = [crate::Guess {
+            word: "tares".into(),
+            mask: crate::Correctness::compute("cigar", "tares"),
+        }];
+        let guess = guesser.guess(&history);
+        let mut clone = guesser.clone();
+        assert_eq!(clone.guess(&history), guess);
+    }
+}
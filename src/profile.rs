@@ -0,0 +1,766 @@
+//! Variants of the dictionary: which spellings to expect, for playing clones of Wordle that
+//! expect British rather than American ones, and which words to leave out altogether.
+//!
+//! The official list of allowed guesses already includes most British spellings, so the
+//! spellings mostly differ in how likely each one is to be the answer. The profile has to be
+//! picked (with [`set_profile`]) before the dictionary is first used, since the guessers
+//! precompute what they need from it once.
+//!
+//! A profile can also replace the official dictionary and answers altogether, such as with a
+//! [`Dictionary`] read from a file, for playing with words of another length.
+//!
+//! Building the dictionary for a profile other than the default one means going over every word
+//! in it a few times. With [`cache_dictionaries_in`], it is instead only built the first time a
+//! profile is used, and read back from a file after that.
+
+use crate::algorithms::WordIndex;
+use crate::answers::Answers;
+use crate::{
+    is_lowercase_word, letter_count, repeats_letter, WordleError, DICTIONARY, DICTIONARY_CHECKSUM,
+    MAX_WORD_LEN,
+};
+use once_cell::sync::OnceCell;
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// How the dictionary is adjusted from the official one.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Profile {
+    pub spelling: Spelling,
+    /// Leave out slurs and crude words, so they are never guessed, suggested or picked as the
+    /// answer.
+    pub family_friendly: bool,
+    /// Make plurals and simple past tenses less likely to be the answer, as the official answers
+    /// hardly ever are, while still allowing them as guesses.
+    pub deprioritise_inflections: bool,
+    /// Words that have already been the answer, which are much less likely to be it again (but
+    /// still can be).
+    pub used: Vec<String>,
+    /// Leave out every word that uses a letter more than once, as in variants of the game where
+    /// neither the guesses nor the answer may.
+    pub no_repeats: bool,
+    /// Every allowed guess along with how common it is, to use instead of the official
+    /// dictionary (and its spellings) if not empty. The words must all be the same length.
+    pub words: Vec<(String, usize)>,
+    /// The answers, in the order they are used, to use instead of the official ones if not
+    /// empty. They must all be in the dictionary. A dictionary from [`words`](Self::words)
+    /// without any answers has every word in it as a possible answer.
+    pub answers: Vec<String>,
+    /// How guesses that use a letter more often than the answer are scored.
+    pub duplicates: Duplicates,
+    /// Only the [answers](Self::answers) (or the official ones) can be the answer, as in Wordle
+    /// itself, which picks its answers from a much shorter list than the guesses it allows. The
+    /// rest of the dictionary can still be guessed, to narrow the answers down.
+    pub answers_only: bool,
+}
+
+/// How the feedback marks letters that are not in the right place, which is where clones of
+/// Wordle differ when a guess uses the same letter more than once. Guesses without repeated
+/// letters get the same feedback whichever rule is used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Duplicates {
+    /// As in Wordle: each letter of the answer that isn't guessed in its place turns at most one
+    /// of the other guessed copies of it yellow, from left to right, and the rest gray.
+    #[default]
+    Official,
+    /// Every guessed letter that isn't in its place is yellow if the answer has that letter
+    /// anywhere, however many times it is guessed.
+    PerLetter,
+    /// Like [`Official`](Self::Official), except that the copies of a letter guessed in the right
+    /// place still count towards how many of the others can be yellow, so at most as many copies
+    /// as the answer has in total are yellow.
+    CountCapped,
+}
+
+impl FromStr for Duplicates {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "official" => Ok(Self::Official),
+            "per-letter" => Ok(Self::PerLetter),
+            "count-capped" => Ok(Self::CountCapped),
+            _ => Err(format!(
+                "unknown duplicate-letter rule '{}', expected official, per-letter or count-capped",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Duplicates {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Official => "official",
+            Self::PerLetter => "per-letter",
+            Self::CountCapped => "count-capped",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Spelling {
+    /// The official word list and frequencies, as used by the New York Times.
+    #[default]
+    Us,
+    /// British spellings are as common as the American ones are in the US profile, and spellings
+    /// only used in American English are rarer.
+    Uk,
+    /// Either spelling is as likely as the most common of the two.
+    Merged,
+}
+
+impl FromStr for Spelling {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "us" => Ok(Self::Us),
+            "uk" => Ok(Self::Uk),
+            "merged" => Ok(Self::Merged),
+            _ => Err(format!(
+                "unknown spelling '{}', expected us, uk or merged",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Spelling {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Us => "us",
+            Self::Uk => "uk",
+            Self::Merged => "merged",
+        })
+    }
+}
+
+/// American and British spellings of the same word, where both are five letters long.
+const VARIANTS: &[(&str, &str)] = &[
+    ("fiber", "fibre"),
+    ("liter", "litre"),
+    ("meter", "metre"),
+    ("miter", "mitre"),
+    ("niter", "nitre"),
+    ("saber", "sabre"),
+    ("ocher", "ochre"),
+    ("grays", "greys"),
+    ("tires", "tyres"),
+    ("curbs", "kerbs"),
+];
+
+/// American spellings whose British spelling is not five letters long, so can't be swapped.
+const US_ONLY: &[&str] = &[
+    "color", "honor", "humor", "labor", "favor", "rumor", "tumor", "vapor", "valor", "ardor",
+    "savor", "arbor", "armor", "odors", "molds", "plows", "fetal",
+];
+
+/// How much rarer the spellings in [`US_ONLY`] are assumed to be in British English.
+const US_ONLY_DISCOUNT: usize = 10;
+
+/// Slurs and crude words that a family-friendly profile leaves out. Words that are also commonly
+/// used innocently (such as "cocks" or "asses") are kept.
+const OFFENSIVE: &[&str] = &[
+    "arsed", "arses", "bimbo", "boobs", "crapy", "cunts", "dicks", "dildo", "fucks", "gipsy",
+    "gyppo", "gypsy", "honky", "horny", "jewed", "negro", "pervs", "pissy", "poofs", "poofy",
+    "porno", "prick", "shite", "shits", "skank", "titty", "turds", "twats", "wanks", "wanky",
+];
+
+/// How much less likely [inflections](is_inflection) are to be the answer when deprioritised.
+const INFLECTION_DISCOUNT: usize = 20;
+
+/// Whether `word` looks like a plural ending in -s or a past tense ending in -ed. This is only a
+/// heuristic: it leaves out words like "glass", "bonus" and "bleed", but not every exception.
+fn is_inflection(word: &str) -> bool {
+    let plural = word.ends_with('s')
+        && !["ss", "us", "is", "ys", "as", "os"]
+            .iter()
+            .any(|end| word.ends_with(end));
+    let past = word.ends_with("ed") && !word.ends_with("eed");
+    plural || past
+}
+
+/// How much less likely [used](Profile::used) answers are to be the answer again.
+const USED_DISCOUNT: usize = 100;
+
+static PROFILE: OnceCell<Profile> = OnceCell::new();
+
+/// Picks the profile to use from now on. Fails if a different one is already in use, or if its
+/// words or answers are not valid.
+pub fn set_profile(profile: Profile) -> Result<(), String> {
+    profile.validate()?;
+    match PROFILE.try_insert(profile) {
+        Ok(_) => Ok(()),
+        Err((current, profile)) if *current == profile => Ok(()),
+        Err(_) => Err("a different dictionary profile is already in use".to_string()),
+    }
+}
+
+/// The profile in use, which is the default one unless another one was picked before the
+/// dictionary was first used.
+pub fn profile() -> &'static Profile {
+    PROFILE.get_or_init(Profile::default)
+}
+
+/// Whether the profile in use leaves `word` out of the dictionary (and the answers).
+pub fn is_excluded(word: &str) -> bool {
+    profile().excludes(word)
+}
+
+/// How the profile in use scores guesses with repeated letters.
+pub fn duplicates() -> Duplicates {
+    profile().duplicates
+}
+
+/// How many letters every word in the dictionary in use has.
+pub fn word_len() -> usize {
+    dictionary()
+        .first()
+        .map_or(5, |(word, _)| letter_count(word))
+}
+
+/// Parses a dictionary with one word per line, optionally followed by a space and how common
+/// it is (as in `dictionary.txt`). Words without a count are all as common as each other.
+pub fn read_words(text: &str) -> Result<Vec<(String, usize)>, String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| match line.split_once(' ') {
+            Some((word, count)) => count
+                .trim()
+                .parse()
+                .map(|count| (word.to_string(), count))
+                .map_err(|_| format!("'{}' is not a word followed by a count", line)),
+            None => Ok((line.to_string(), 1)),
+        })
+        .collect()
+}
+
+/// A list of allowed guesses, along with how common each one is, to play with instead of the
+/// official one (for clones of Wordle with other word lists, or other frequency data) by
+/// [setting](set_profile) a profile [with](Profile::with_dictionary) it.
+///
+/// There is only ever one dictionary in use, rather than one for each [`Wordle`](crate::Wordle)
+/// or guesser, since the guessers precompute what they need from it once for the whole run.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Dictionary {
+    words: Vec<(String, usize)>,
+}
+
+impl Dictionary {
+    /// The official dictionary that the solver was built with.
+    pub fn builtin() -> Self {
+        Self {
+            words: DICTIONARY
+                .iter()
+                .map(|&(word, count)| (word.to_string(), count))
+                .collect(),
+        }
+    }
+
+    /// Reads a dictionary in the format of [`read_words`], failing unless every word is made of
+    /// the same number of lowercase letters.
+    pub fn from_reader(mut reader: impl io::Read) -> Result<Self, WordleError> {
+        let mut text = String::new();
+        let parse = WordleError::DictionaryParse;
+        reader
+            .read_to_string(&mut text)
+            .map_err(|e| parse(e.to_string()))?;
+        let words = read_words(&text).map_err(parse)?;
+        if words.is_empty() {
+            return Err(parse("the dictionary has no words in it".to_string()));
+        }
+        check_words(&words).map_err(parse)?;
+        Ok(Self { words })
+    }
+
+    /// Reads a dictionary from a file, as with [`from_reader`](Self::from_reader).
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, WordleError> {
+        let file = fs::File::open(path).map_err(|e| WordleError::DictionaryParse(e.to_string()))?;
+        Self::from_reader(io::BufReader::new(file))
+    }
+
+    /// Every word along with how common it is, in the order they were read.
+    pub fn words(&self) -> &[(String, usize)] {
+        &self.words
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+}
+
+/// Checks that every one of `words` is made of the same number of lowercase letters (from any
+/// alphabet), that that number can be played, and that the guessers can index every word.
+fn check_words(words: &[(String, usize)]) -> Result<(), String> {
+    let len = match words.first() {
+        Some((word, _)) => letter_count(word),
+        None => 5,
+    };
+    if !(1..=MAX_WORD_LEN).contains(&len) {
+        return Err(format!(
+            "words must be between 1 and {} letters long",
+            MAX_WORD_LEN
+        ));
+    }
+    if words.len() > WordIndex::MAX_WORDS {
+        return Err(format!(
+            "dictionaries can have at most {} words",
+            WordIndex::MAX_WORDS
+        ));
+    }
+    for (word, _) in words {
+        if !is_lowercase_word(word, len) {
+            return Err(format!(
+                "'{}' is not {} lowercase letters, like the first word",
+                word, len
+            ));
+        }
+    }
+    Ok(())
+}
+
+impl Profile {
+    /// Plays with `dictionary` instead of the official dictionary.
+    pub fn with_dictionary(mut self, dictionary: Dictionary) -> Self {
+        self.words = dictionary.words;
+        self
+    }
+
+    fn excludes(&self, word: &str) -> bool {
+        (self.family_friendly && OFFENSIVE.contains(&word))
+            || (self.no_repeats && repeats_letter(word))
+    }
+
+    /// Checks that every word and answer is made of the same number of lowercase letters, and
+    /// that every answer is in the dictionary.
+    fn validate(&self) -> Result<(), String> {
+        let words: HashSet<&str> = if self.words.is_empty() {
+            DICTIONARY.iter().map(|&(word, _)| word).collect()
+        } else {
+            self.words.iter().map(|(word, _)| word.as_str()).collect()
+        };
+        check_words(&self.words)?;
+        if let Some(answer) = self.answers.iter().find(|a| !words.contains(a.as_str())) {
+            return Err(format!("answer '{}' is not in the dictionary", answer));
+        }
+        Ok(())
+    }
+}
+
+/// Every allowed guess in the profile in use, along with how common it is, most common first.
+pub fn dictionary() -> &'static [(&'static str, usize)] {
+    static WORDS: OnceCell<Vec<(&'static str, usize)>> = OnceCell::new();
+    if *profile() == Profile::default() {
+        return &DICTIONARY;
+    }
+    WORDS.get_or_init(|| match CACHE_DIR.get() {
+        Some(dir) => build_cached(profile(), dir),
+        None => build(profile()),
+    })
+}
+
+/// The words in the dictionary in use that can be the answer, along with how common each is, most
+/// common first. These are every word in it, unless the profile in use only allows the
+/// [answers](Profile::answers_only), in which case the rest are only ever guessed.
+pub fn candidate_answers() -> &'static [(&'static str, usize)] {
+    static CANDIDATES: OnceCell<Vec<(&'static str, usize)>> = OnceCell::new();
+    if !profile().answers_only {
+        return dictionary();
+    }
+    CANDIDATES.get_or_init(|| {
+        let answers = Answers::in_use();
+        dictionary()
+            .iter()
+            .copied()
+            .filter(|&(word, _)| answers.day_of(word).is_some())
+            .collect()
+    })
+}
+
+/// Changed whenever the format of the cached dictionaries, or how they are built, changes, so that
+/// the ones cached by older versions are built again rather than used.
+const CACHE_VERSION: u32 = 1;
+
+static CACHE_DIR: OnceCell<PathBuf> = OnceCell::new();
+
+/// Keeps the dictionary built for each profile (other than the default one, which needs no
+/// building) in a file in `dir`, and reads it back from there rather than building it again the
+/// next time the same profile is used. Only the first directory given is used, and only if it is
+/// given before the dictionary is first used.
+pub fn cache_dictionaries_in(dir: impl Into<PathBuf>) {
+    let _ = CACHE_DIR.set(dir.into());
+}
+
+/// Where dictionaries are cached unless told otherwise: `wordle-solver` in the user's cache
+/// directory, if they have one.
+pub fn default_cache_dir() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_CACHE_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => match std::env::var_os("LOCALAPPDATA") {
+            Some(dir) => PathBuf::from(dir),
+            None => PathBuf::from(std::env::var_os("HOME")?).join(".cache"),
+        },
+    };
+    Some(base.join("wordle-solver"))
+}
+
+/// Identifies what went into building a dictionary: the profile, the official dictionary it
+/// adjusts and how it adjusts it.
+fn cache_key(profile: &Profile) -> u64 {
+    fnv1a(
+        format!(
+            "{} {:016x} {:?}",
+            CACHE_VERSION, DICTIONARY_CHECKSUM, profile
+        )
+        .bytes(),
+    )
+}
+
+/// Where the dictionary built for `profile` is cached in `dir`.
+fn cached_path(profile: &Profile, dir: &Path) -> PathBuf {
+    dir.join(format!("dictionary-{:016x}.txt", cache_key(profile)))
+}
+
+/// Where the dictionary built for the profile in use is cached in `dir`, if it has been.
+pub(crate) fn cached_dictionary_path(dir: &Path) -> PathBuf {
+    cached_path(profile(), dir)
+}
+
+/// Whether `path` is a dictionary cached by this version of the solver, which it can read back.
+pub(crate) fn is_valid_cached(path: &Path) -> bool {
+    let key = path
+        .file_name()
+        .and_then(|name| {
+            name.to_str()?
+                .strip_prefix("dictionary-")?
+                .strip_suffix(".txt")
+        })
+        .and_then(|key| u64::from_str_radix(key, 16).ok());
+    let (Some(key), Ok(text)) = (key, fs::read_to_string(path)) else {
+        return false;
+    };
+    match text.split_once('\n') {
+        Some((header, words)) => {
+            header == cache_header(key) && words.lines().all(|line| parse_cached(line).is_some())
+        }
+        None => false,
+    }
+}
+
+fn build_cached(profile: &'static Profile, dir: &Path) -> Vec<(&'static str, usize)> {
+    let key = cache_key(profile);
+    let path = cached_path(profile, dir);
+    if let Some(words) = load_cached(&path, key) {
+        return words;
+    }
+    let words = build(profile);
+    // Not being able to cache it only makes the next run slower.
+    let _ = save_cached(&path, key, &words);
+    words
+}
+
+/// Reads a dictionary written by [`save_cached`] for the same `key`, if there is one.
+fn load_cached(path: &Path, key: u64) -> Option<Vec<(&'static str, usize)>> {
+    let text = fs::read_to_string(path).ok()?;
+    let (header, words) = text.split_once('\n')?;
+    if header != cache_header(key) {
+        return None;
+    }
+    // The dictionary is used for the rest of the run, like the official one.
+    let words: &'static str = words.to_string().leak();
+    words.lines().map(parse_cached).collect()
+}
+
+fn parse_cached(line: &str) -> Option<(&str, usize)> {
+    let (word, count) = line.split_once(' ')?;
+    Some((word, count.parse().ok()?))
+}
+
+fn save_cached(path: &Path, key: u64, words: &[(&str, usize)]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut out = cache_header(key) + "\n";
+    for (word, count) in words {
+        out += &format!("{} {}\n", word, count);
+    }
+    // Write it somewhere else first, so that another run never reads half of it.
+    let partial = path.with_extension(format!("{}.tmp", std::process::id()));
+    fs::write(&partial, out)?;
+    fs::rename(&partial, path)
+}
+
+fn cache_header(key: u64) -> String {
+    format!("# wordle-solver dictionary {:016x}", key)
+}
+
+fn build(profile: &Profile) -> Vec<(&str, usize)> {
+    let mut words = if profile.words.is_empty() {
+        respell(profile.spelling)
+    } else {
+        profile
+            .words
+            .iter()
+            .map(|(word, count)| (word.as_str(), *count))
+            .collect()
+    };
+    words.retain(|&(word, _)| !profile.excludes(word));
+    let used: HashSet<_> = profile.used.iter().map(String::as_str).collect();
+    for (word, count) in &mut words {
+        if profile.deprioritise_inflections && is_inflection(word) {
+            *count /= INFLECTION_DISCOUNT;
+        }
+        if used.contains(word) {
+            *count /= USED_DISCOUNT;
+        }
+    }
+    words.sort_by_key(|&(_, c)| std::cmp::Reverse(c));
+    words
+}
+
+fn respell(spelling: Spelling) -> Vec<(&'static str, usize)> {
+    let count = |word| {
+        DICTIONARY
+            .iter()
+            .find(|&&(w, _)| w == word)
+            .map_or(0, |&(_, c)| c)
+    };
+    let mut words = DICTIONARY.to_vec();
+    for &(us, uk) in VARIANTS {
+        let (us_count, uk_count) = (count(us), count(uk));
+        let (us_count, uk_count) = match spelling {
+            Spelling::Us => (us_count, uk_count),
+            Spelling::Uk => (us_count.min(uk_count), us_count.max(uk_count)),
+            Spelling::Merged => (us_count.max(uk_count), us_count.max(uk_count)),
+        };
+        for (word, c) in [(us, us_count), (uk, uk_count)] {
+            match words.iter_mut().find(|(w, _)| *w == word) {
+                Some(entry) => entry.1 = c,
+                None if c > 0 => words.push((word, c)),
+                None => {}
+            }
+        }
+    }
+    if spelling == Spelling::Uk {
+        for (word, c) in &mut words {
+            if US_ONLY.contains(word) {
+                *c /= US_ONLY_DISCOUNT;
+            }
+        }
+    }
+    words.sort_by_key(|&(_, c)| std::cmp::Reverse(c));
+    words
+}
+
+/// FNV-1a over the dictionary, as the build script computes for [`DICTIONARY_CHECKSUM`].
+///
+/// [`DICTIONARY_CHECKSUM`]: crate::DICTIONARY_CHECKSUM
+pub(crate) fn checksum(words: &[(&str, usize)]) -> u64 {
+    fnv1a(
+        words
+            .iter()
+            .flat_map(|(word, count)| format!("{} {}\n", word, count).into_bytes()),
+    )
+}
+
+/// The 64-bit FNV-1a hash of `bytes`, which unlike the std hashers is guaranteed to stay the same
+/// between builds.
+pub fn fnv1a(bytes: impl IntoIterator<Item = u8>) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for b in bytes {
+        hash ^= u64::from(b);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        build, cache_key, checksum, is_inflection, load_cached, read_words, respell, save_cached,
+        Dictionary, Duplicates, Profile, Spelling, OFFENSIVE,
+    };
+    use crate::{WordleError, DICTIONARY, DICTIONARY_CHECKSUM};
+
+    fn count(words: &[(&str, usize)], word: &str) -> Option<usize> {
+        words.iter().find(|&&(w, _)| w == word).map(|&(_, c)| c)
+    }
+
+    #[test]
+    fn dictionary_from_reader() {
+        let dictionary = Dictionary::from_reader("cigar 10\nrebut\n".as_bytes()).unwrap();
+        assert_eq!(
+            dictionary.words(),
+            [("cigar".to_string(), 10), ("rebut".to_string(), 1)]
+        );
+        assert!(Dictionary::from_reader("cigar\ncat\n".as_bytes()).is_err());
+        assert!(Dictionary::from_reader("Cigar\n".as_bytes()).is_err());
+        // Letters outside ASCII count once, however many bytes they take.
+        let spanish = Dictionary::from_reader("niños\nseñor\nnieto\n".as_bytes()).unwrap();
+        assert_eq!(spanish.len(), 3);
+        assert!(Dictionary::from_reader("niños\nniñoss\n".as_bytes()).is_err());
+        assert!(Dictionary::from_reader("Niños\n".as_bytes()).is_err());
+        // Every four-letter word from "aaaa" on, one more than can be indexed.
+        let too_many: String = (0..=u16::MAX as u32 + 1)
+            .map(|i| {
+                let letter = |place: u32| (b'a' + (i / 26u32.pow(place) % 26) as u8) as char;
+                format!("{}{}{}{}\n", letter(3), letter(2), letter(1), letter(0))
+            })
+            .collect();
+        assert!(Dictionary::from_reader(too_many.as_bytes()).is_err());
+        assert_eq!(
+            Dictionary::from_reader("".as_bytes()).err(),
+            Some(WordleError::DictionaryParse(
+                "the dictionary has no words in it".to_string()
+            ))
+        );
+        assert!(Dictionary::from_path("no/such/dictionary.txt").is_err());
+        assert_eq!(Dictionary::builtin().len(), DICTIONARY.len());
+
+        let profile = Profile::default().with_dictionary(dictionary);
+        assert_eq!(profile.words.len(), 2);
+        assert!(profile.validate().is_ok());
+    }
+
+    #[test]
+    fn us_is_the_official_dictionary() {
+        assert_eq!(respell(Spelling::Us), DICTIONARY);
+        assert_eq!(checksum(&DICTIONARY), DICTIONARY_CHECKSUM);
+    }
+
+    #[test]
+    fn uk() {
+        let words = respell(Spelling::Uk);
+        assert!(count(&words, "fibre") > count(&words, "fiber"));
+        assert!(count(&words, "litre") > count(&words, "liter"));
+        assert!(count(&words, "color") < count(&DICTIONARY, "color"));
+        assert!(words.windows(2).all(|w| w[0].1 >= w[1].1));
+    }
+
+    #[test]
+    fn merged() {
+        let words = respell(Spelling::Merged);
+        assert_eq!(count(&words, "fibre"), count(&words, "fiber"));
+        assert_eq!(count(&words, "fibre"), count(&DICTIONARY, "fiber"));
+        assert_eq!(words.len(), DICTIONARY.len() + 1);
+    }
+
+    #[test]
+    fn parse() {
+        for spelling in [Spelling::Us, Spelling::Uk, Spelling::Merged] {
+            assert_eq!(spelling.to_string().parse(), Ok(spelling));
+        }
+        assert!("au".parse::<Spelling>().is_err());
+        for duplicates in [
+            Duplicates::Official,
+            Duplicates::PerLetter,
+            Duplicates::CountCapped,
+        ] {
+            assert_eq!(duplicates.to_string().parse(), Ok(duplicates));
+        }
+        assert!("capped".parse::<Duplicates>().is_err());
+    }
+
+    #[test]
+    fn offensive_words_are_allowed_guesses() {
+        // Otherwise there would be no point in leaving them out.
+        for word in OFFENSIVE {
+            assert!(DICTIONARY.iter().any(|&(w, _)| w == *word), "{}", word);
+        }
+    }
+
+    #[test]
+    fn inflections() {
+        assert!(is_inflection("tares"));
+        assert!(is_inflection("tried"));
+        assert!(!is_inflection("glass"));
+        assert!(!is_inflection("bonus"));
+        assert!(!is_inflection("bleed"));
+        assert!(!is_inflection("cigar"));
+    }
+
+    #[test]
+    fn adjustments() {
+        let profile = Profile {
+            family_friendly: true,
+            deprioritise_inflections: true,
+            used: vec!["cigar".to_string()],
+            no_repeats: true,
+            ..Profile::default()
+        };
+        let words = build(&profile);
+        assert_eq!(count(&words, "porno"), None);
+        assert_eq!(count(&words, "sissy"), None);
+        assert!(count(&words, "tares") < count(&DICTIONARY, "tares"));
+        assert!(count(&words, "cigar") < count(&DICTIONARY, "cigar"));
+        assert_eq!(count(&words, "rebut"), count(&DICTIONARY, "rebut"));
+        assert_eq!(build(&Profile::default()), DICTIONARY);
+    }
+
+    #[test]
+    fn other_word_lengths() {
+        let words = read_words("planet 20\nstream 30\n\nsquash\n").unwrap();
+        assert_eq!(words[1], ("stream".to_string(), 30));
+        assert_eq!(words[2], ("squash".to_string(), 1));
+        assert!(read_words("planet lots").is_err());
+        let profile = Profile {
+            words,
+            answers: vec!["planet".to_string()],
+            ..Profile::default()
+        };
+        assert!(profile.validate().is_ok());
+        assert_eq!(
+            build(&profile),
+            [("stream", 30), ("planet", 20), ("squash", 1)]
+        );
+
+        let unknown_answer = Profile {
+            answers: vec!["cigars".to_string()],
+            ..profile.clone()
+        };
+        assert!(unknown_answer.validate().is_err());
+        let mut mixed = profile.clone();
+        mixed.words.push(("cigar".to_string(), 1));
+        assert!(mixed.validate().is_err());
+        let mut shouting = profile;
+        shouting.words.push(("PLANES".to_string(), 1));
+        assert!(shouting.validate().is_err());
+        assert!(Profile {
+            answers: vec!["cigar".to_string()],
+            ..Profile::default()
+        }
+        .validate()
+        .is_ok());
+    }
+
+    #[test]
+    fn cached() {
+        let profile = Profile {
+            spelling: Spelling::Uk,
+            family_friendly: true,
+            ..Profile::default()
+        };
+        let key = cache_key(&profile);
+        assert_ne!(key, cache_key(&Profile::default()));
+        let path = std::env::temp_dir().join(format!("dictionary-{}.txt", std::process::id()));
+        let words = build(&profile);
+        save_cached(&path, key, &words).unwrap();
+        let loaded = load_cached(&path, key);
+        let other = load_cached(&path, key + 1);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, Some(words));
+        // A different profile (or version) builds it again.
+        assert_eq!(other, None);
+        assert_eq!(load_cached(&path, key), None);
+    }
+}
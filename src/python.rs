@@ -0,0 +1,349 @@
+//! Python bindings, for running experiments on the solvers from Python and plotting the results.
+//!
+//! The module is built as a shared library with the `pyo3` feature, and imported as
+//! `wordle_solver` once it is renamed to `wordle_solver.so` somewhere on the Python path:
+//!
+//! ```text
+//! $ cargo rustc --release --lib --crate-type cdylib --features pyo3,pyo3/extension-module
+//! $ cp target/release/libwordle_solver.so wordle_solver.so
+//! $ python3
+//! >>> import wordle_solver
+//! >>> solver = wordle_solver.Solver("cache")
+//! >>> [guess.word for guess in solver.play("crane").guesses]
+//! ['tares', 'beard', 'crave', 'crane']
+//! >>> solver.next_guess([("tares", "XYYXX")])
+//! 'drain'
+//! ```
+//!
+//! Feedback is written as one letter per position, as [`Correctness::parse_mask`] reads it, and
+//! guesses can be given as [`Guess`](PyGuess) objects or as `(word, feedback)` pairs.
+
+// The code pyo3 generates for functions returning a `PyResult` converts the error to itself.
+#![allow(clippy::useless_conversion)]
+
+use crate::registry::{DynGuesser, Registry};
+use crate::{
+    dictionary, hints, letter_count, Correctness, GameMode, Guess, Mask, Wordle, WordleError,
+};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// The feedback for one letter of a guess.
+#[pyclass(name = "Correctness", eq, eq_int, module = "wordle_solver")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PyCorrectness {
+    Correct,
+    Misplaced,
+    Wrong,
+}
+
+impl From<Correctness> for PyCorrectness {
+    fn from(c: Correctness) -> Self {
+        match c {
+            Correctness::Correct => Self::Correct,
+            Correctness::Misplaced => Self::Misplaced,
+            Correctness::Wrong => Self::Wrong,
+        }
+    }
+}
+
+#[pymethods]
+impl PyCorrectness {
+    /// The feedback `guess` gets when the answer is `answer`, e.g. "XYYXX".
+    #[staticmethod]
+    fn compute(answer: &str, guess: &str) -> PyResult<String> {
+        let mask = Correctness::try_compute(answer, guess).map_err(value_error)?;
+        Ok(Correctness::format_mask(&mask[..letter_count(guess)]))
+    }
+
+    /// The feedback for each letter of feedback like "XYYXX".
+    #[staticmethod]
+    fn parse(feedback: &str) -> PyResult<Vec<Self>> {
+        let len = feedback.chars().count();
+        let mask = parse_mask(feedback, len)?;
+        Ok(mask[..len].iter().map(|&c| c.into()).collect())
+    }
+}
+
+/// A word that was guessed, and the feedback it got.
+#[pyclass(name = "Guess", module = "wordle_solver")]
+#[derive(Debug, Clone)]
+struct PyGuess {
+    word: String,
+    mask: Mask,
+}
+
+#[pymethods]
+impl PyGuess {
+    #[new]
+    fn new(word: &str, feedback: &str) -> PyResult<Self> {
+        let word = word.to_lowercase();
+        let mask = parse_mask(feedback, letter_count(&word))?;
+        Ok(Self { word, mask })
+    }
+
+    #[getter]
+    fn word(&self) -> &str {
+        &self.word
+    }
+
+    /// The feedback, e.g. "XYYXX".
+    #[getter]
+    fn feedback(&self) -> String {
+        Correctness::format_mask(self.guess().feedback())
+    }
+
+    /// Whether `word` could still be the answer after this guess.
+    fn matches(&self, word: &str) -> bool {
+        self.guess().matches(word)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Guess('{}', '{}')", self.word, self.feedback())
+    }
+}
+
+impl PyGuess {
+    fn guess(&self) -> Guess<'_> {
+        Guess {
+            word: self.word.as_str().into(),
+            mask: self.mask,
+        }
+    }
+}
+
+/// A guess passed in from Python, as either a [`PyGuess`] or a `(word, feedback)` pair.
+#[derive(FromPyObject)]
+enum GuessLike {
+    Guess(PyGuess),
+    Pair(String, String),
+}
+
+impl GuessLike {
+    fn into_guess(self) -> PyResult<Guess<'static>> {
+        let guess = match self {
+            Self::Guess(guess) => guess,
+            Self::Pair(word, feedback) => PyGuess::new(&word, &feedback)?,
+        };
+        Ok(Guess {
+            word: guess.word.into(),
+            mask: guess.mask,
+        })
+    }
+}
+
+/// A game one of the solvers played.
+#[pyclass(name = "Game", module = "wordle_solver")]
+struct PyGame {
+    #[pyo3(get)]
+    answer: String,
+    /// Every guess made, ending with the answer if it was found.
+    #[pyo3(get)]
+    guesses: Vec<PyGuess>,
+    /// How many words that can be the answer were left after each guess.
+    #[pyo3(get)]
+    remaining: Vec<usize>,
+    /// How many guesses it took, or None if the answer wasn't found.
+    #[pyo3(get)]
+    score: Option<usize>,
+}
+
+#[pymethods]
+impl PyGame {
+    fn __repr__(&self) -> String {
+        let guesses: Vec<_> = self
+            .guesses
+            .iter()
+            .map(|g| format!("'{}'", g.word))
+            .collect();
+        format!("Game('{}', [{}])", self.answer, guesses.join(", "))
+    }
+}
+
+/// One of the built-in guessers, by the name the command line knows it by.
+#[pyclass(name = "Solver", module = "wordle_solver", unsendable)]
+struct PySolver {
+    algorithm: String,
+    /// A guesser that hasn't played yet, which is copied for every game so that they are all
+    /// played from the start.
+    guesser: Box<dyn DynGuesser>,
+}
+
+#[pymethods]
+impl PySolver {
+    #[new]
+    #[pyo3(signature = (algorithm = "cache"))]
+    fn new(algorithm: &str) -> PyResult<Self> {
+        let registry = Registry::builtin();
+        let Some(factory) = registry.get(algorithm) else {
+            return Err(PyValueError::new_err(format!(
+                "unknown algorithm '{}', expected one of: {}",
+                algorithm,
+                registry.names().collect::<Vec<_>>().join(", ")
+            )));
+        };
+        Ok(Self {
+            algorithm: algorithm.to_string(),
+            guesser: factory(),
+        })
+    }
+
+    #[getter]
+    fn algorithm(&self) -> &str {
+        &self.algorithm
+    }
+
+    /// The word to guess after `history`, or None if the game is solved or no word fits the
+    /// feedback.
+    fn next_guess(&self, history: Vec<GuessLike>) -> PyResult<Option<String>> {
+        let history = history
+            .into_iter()
+            .map(GuessLike::into_guess)
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(hints::suggest(&|| self.guesser.clone(), &history))
+    }
+
+    /// Plays a game against `answer`.
+    fn play(&self, answer: &str) -> PyResult<PyGame> {
+        let answer = in_dictionary(answer)?;
+        let game = Wordle::new().play_game(answer, self.guesser.clone(), GameMode::Normal);
+        let score = game.score();
+        Ok(PyGame {
+            answer: answer.to_string(),
+            guesses: game
+                .guesses
+                .into_iter()
+                .zip(game.masks)
+                .map(|(word, mask)| PyGuess { word, mask })
+                .collect(),
+            remaining: game.remaining,
+            score,
+        })
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Solver('{}')", self.algorithm)
+    }
+}
+
+/// The rules of the game, with the dictionary built in.
+#[pyclass(name = "Wordle", module = "wordle_solver")]
+struct PyWordle(Wordle);
+
+#[pymethods]
+impl PyWordle {
+    #[new]
+    fn new() -> Self {
+        Self(Wordle::new())
+    }
+
+    /// Whether `word` may be guessed.
+    fn is_allowed(&self, word: &str) -> bool {
+        self.0.is_allowed(word)
+    }
+
+    /// Whether `word` may be the answer.
+    fn can_be_answer(&self, word: &str) -> bool {
+        self.0.can_be_answer(word)
+    }
+
+    /// How many guesses `solver` takes to find `answer`, or None if it never does.
+    fn play(&self, answer: &str, solver: &PySolver) -> PyResult<Option<usize>> {
+        let answer = in_dictionary(answer)?;
+        match self
+            .0
+            .try_play(answer, solver.guesser.clone(), GameMode::Normal)
+        {
+            Ok(score) => Ok(Some(score)),
+            Err(WordleError::TooManyGuesses(_)) => Ok(None),
+            Err(e) => Err(value_error(e)),
+        }
+    }
+}
+
+/// The names of the built-in solvers.
+#[pyfunction]
+fn algorithms() -> Vec<String> {
+    Registry::builtin().names().map(str::to_string).collect()
+}
+
+fn value_error(e: impl std::fmt::Display) -> PyErr {
+    PyValueError::new_err(e.to_string())
+}
+
+fn parse_mask(feedback: &str, len: usize) -> PyResult<Mask> {
+    Correctness::parse_mask(feedback, len).ok_or_else(|| {
+        value_error(format!(
+            "'{}' is not valid feedback for a {}-letter word, e.g. GYXXY",
+            feedback, len
+        ))
+    })
+}
+
+/// `word` as it is in the dictionary, which answers have to be.
+fn in_dictionary(word: &str) -> PyResult<&'static str> {
+    let word = word.to_lowercase();
+    dictionary()
+        .iter()
+        .map(|&(w, _)| w)
+        .find(|&w| w == word)
+        .ok_or_else(|| value_error(format!("'{}' is not in the dictionary", word)))
+}
+
+#[pymodule]
+fn wordle_solver(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyCorrectness>()?;
+    m.add_class::<PyGuess>()?;
+    m.add_class::<PyGame>()?;
+    m.add_class::<PySolver>()?;
+    m.add_class::<PyWordle>()?;
+    m.add_function(wrap_pyfunction!(algorithms, m)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GuessLike, PyCorrectness, PyGuess, PySolver, PyWordle};
+
+    #[test]
+    fn plays() {
+        let solver = PySolver::new("popular").unwrap();
+        let game = solver.play("Cigar").unwrap();
+        assert_eq!(game.answer, "cigar");
+        assert_eq!(game.guesses.last().unwrap().word, "cigar");
+        assert_eq!(game.score, Some(game.guesses.len()));
+        assert_eq!(PyWordle::new().play("cigar", &solver).unwrap(), game.score);
+
+        // Going on from the first guess gets to the same second guess.
+        let first = game.guesses[0].clone();
+        let second = solver
+            .next_guess(vec![GuessLike::Pair(first.word.clone(), first.feedback())])
+            .unwrap();
+        assert_eq!(second.as_deref(), Some(&*game.guesses[1].word));
+        assert_eq!(
+            solver.next_guess(vec![GuessLike::Guess(first)]).unwrap(),
+            second
+        );
+
+        assert!(PySolver::new("nonsense").is_err());
+        assert!(solver.play("zzzzz").is_err());
+    }
+
+    #[test]
+    fn gives_feedback() {
+        assert_eq!(PyCorrectness::compute("cigar", "tares").unwrap(), "XYYXX");
+        assert_eq!(
+            PyCorrectness::parse("gyx").unwrap(),
+            [
+                PyCorrectness::Correct,
+                PyCorrectness::Misplaced,
+                PyCorrectness::Wrong
+            ]
+        );
+        let guess = PyGuess::new("TARES", "XYYXX").unwrap();
+        assert_eq!(guess.__repr__(), "Guess('tares', 'XYYXX')");
+        assert!(guess.matches("cigar"));
+        assert!(!guess.matches("tares"));
+        assert!(PyGuess::new("tares", "XYY").is_err());
+    }
+}
@@ -0,0 +1,339 @@
+//! Looking up guessers by name at runtime, so that the built-in implementations and ones loaded
+//! from elsewhere (such as plugins) can be picked and played the same way.
+
+use crate::{algorithms, Guesser};
+use std::fmt;
+
+/// A guesser behind a `Box`, that can still be cloned (as the assistant does to undo guesses).
+pub trait DynGuesser: Guesser {
+    fn clone_box(&self) -> Box<dyn DynGuesser>;
+}
+
+impl<G: Guesser + Clone + 'static> DynGuesser for G {
+    fn clone_box(&self) -> Box<dyn DynGuesser> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn DynGuesser> {
+    fn clone(&self) -> Self {
+        (**self).clone_box()
+    }
+}
+
+/// Creates a new boxed guesser. This is a [`GuesserFactory`](crate::GuesserFactory) like any
+/// other `Fn() -> impl Guesser`.
+pub type Factory = dyn Fn() -> Box<dyn DynGuesser>;
+
+/// A [`Factory`] that can be shared between threads, to play games in parallel.
+pub type SharedFactory = dyn Fn() -> Box<dyn DynGuesser> + Sync;
+
+/// Roughly how long a guesser takes to play every answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Speed {
+    /// Minutes or more.
+    Slow,
+    /// Several seconds.
+    Moderate,
+    /// About a second or less.
+    Fast,
+}
+
+impl fmt::Display for Speed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Speed::Slow => "slow",
+            Speed::Moderate => "moderate",
+            Speed::Fast => "fast",
+        })
+    }
+}
+
+/// What is known about a registered guesser, to help choose between them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Info {
+    pub description: String,
+    pub speed: Option<Speed>,
+    /// The average number of guesses taken over every official answer.
+    pub expected_score: Option<f64>,
+}
+
+impl Info {
+    fn builtin(description: &str, speed: Speed, expected_score: f64) -> Self {
+        Self {
+            description: description.to_string(),
+            speed: Some(speed),
+            expected_score: Some(expected_score),
+        }
+    }
+}
+
+struct Entry {
+    name: String,
+    info: Info,
+    factory: Factories,
+}
+
+/// How an entry creates its guessers, which is only from other threads if it can be.
+enum Factories {
+    Local(Box<Factory>),
+    Shared(Box<SharedFactory>),
+}
+
+/// Guesser factories by name, in the order they were registered.
+#[derive(Default)]
+pub struct Registry {
+    entries: Vec<Entry>,
+}
+
+impl Registry {
+    /// A registry without any guessers in it.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry with every built-in implementation in it, from slowest to fastest.
+    pub fn builtin() -> Self {
+        use Speed::*;
+
+        let mut registry = Self::new();
+        registry.register_with(
+            "unoptimised",
+            Info::builtin(
+                "Maximises expected information, as naively as possible",
+                Slow,
+                3.8320,
+            ),
+            algorithms::Unoptimised::new,
+        );
+        registry.register_with(
+            "allocs",
+            Info::builtin(
+                "unoptimised, without allocating for every pattern",
+                Slow,
+                3.8320,
+            ),
+            algorithms::Allocs::new,
+        );
+        registry.register_with(
+            "vecrem",
+            Info::builtin(
+                "allocs, only keeping the candidates left in a Vec",
+                Slow,
+                3.8320,
+            ),
+            algorithms::Vecrem::new,
+        );
+        registry.register_with(
+            "precalc",
+            Info::builtin(
+                "vecrem, looking up which words match each pattern",
+                Slow,
+                3.8320,
+            ),
+            algorithms::Precalc::new,
+        );
+        registry.register_with(
+            "weight",
+            Info::builtin(
+                "Favours guesses that are likely to be the answer",
+                Slow,
+                3.9515,
+            ),
+            algorithms::Weight::new,
+        );
+        registry.register_with(
+            "minimax",
+            Info::builtin(
+                "Minimises the most candidates any feedback could leave",
+                Slow,
+                4.0814,
+            ),
+            algorithms::Minimax::new,
+        );
+        registry.register_with(
+            "enum",
+            Info::builtin(
+                "weight, counting every pattern in one pass",
+                Moderate,
+                3.9515,
+            ),
+            algorithms::Enumerate::new,
+        );
+        registry.register_with(
+            "cutoff",
+            Info::builtin(
+                "enum, only considering the likeliest candidates",
+                Moderate,
+                3.9515,
+            ),
+            algorithms::Cutoff::new,
+        );
+        registry.register_with(
+            "sigmoid",
+            Info::builtin(
+                "cutoff, treating all common words as equally likely",
+                Moderate,
+                3.7315,
+            ),
+            algorithms::Sigmoid::new,
+        );
+        registry.register_with(
+            "escore",
+            Info::builtin(
+                "Minimises the expected number of guesses left",
+                Moderate,
+                3.7189,
+            ),
+            algorithms::Escore::new,
+        );
+        registry.register_with(
+            "optimal",
+            Info::builtin("escore, searching every endgame exactly", Moderate, 3.7051),
+            algorithms::Optimal::new,
+        );
+        registry.register_with(
+            "twoply",
+            Info::builtin(
+                "escore, looking two guesses ahead for the best few",
+                Moderate,
+                3.7077,
+            ),
+            algorithms::TwoPly::new,
+        );
+        registry.register_with(
+            "popular",
+            Info::builtin("Always guesses the most common candidate", Fast, 3.9567),
+            algorithms::Popular::new,
+        );
+        registry.register_with(
+            "cache",
+            Info::builtin(
+                "escore, caching patterns and solving endgames exactly",
+                Fast,
+                3.7150,
+            ),
+            algorithms::Cached::new,
+        );
+        registry
+    }
+
+    /// Makes the guessers created by `factory` available as `name`, replacing whatever was
+    /// registered under that name before.
+    pub fn register<G>(&mut self, name: impl Into<String>, factory: impl Fn() -> G + Sync + 'static)
+    where
+        G: Guesser + Clone + 'static,
+    {
+        self.register_with(name, Info::default(), factory)
+    }
+
+    /// Like [`register`](Self::register), along with what is known about the guesser.
+    pub fn register_with<G>(
+        &mut self,
+        name: impl Into<String>,
+        info: Info,
+        factory: impl Fn() -> G + Sync + 'static,
+    ) where
+        G: Guesser + Clone + 'static,
+    {
+        let factory = Factories::Shared(Box::new(move || Box::new(factory())));
+        self.insert(name.into(), info, factory);
+    }
+
+    /// Like [`register`](Self::register), for factories that can't be shared between threads
+    /// (such as ones that create guessers from a plugin or a script), which can't be played in
+    /// parallel.
+    pub fn register_local<G>(&mut self, name: impl Into<String>, factory: impl Fn() -> G + 'static)
+    where
+        G: Guesser + Clone + 'static,
+    {
+        let factory = Factories::Local(Box::new(move || Box::new(factory())));
+        self.insert(name.into(), Info::default(), factory);
+    }
+
+    fn insert(&mut self, name: String, info: Info, factory: Factories) {
+        let entry = Entry {
+            name,
+            info,
+            factory,
+        };
+        match self.entries.iter_mut().find(|e| e.name == entry.name) {
+            Some(existing) => *existing = entry,
+            None => self.entries.push(entry),
+        }
+    }
+
+    /// The factory registered as `name`.
+    pub fn get(&self, name: &str) -> Option<&Factory> {
+        self.entry(name).map(|e| match &e.factory {
+            Factories::Local(factory) => &**factory as &Factory,
+            Factories::Shared(factory) => &**factory as &Factory,
+        })
+    }
+
+    /// The factory registered as `name`, if it can be shared between threads.
+    pub fn get_shared(&self, name: &str) -> Option<&SharedFactory> {
+        match &self.entry(name)?.factory {
+            Factories::Local(_) => None,
+            Factories::Shared(factory) => Some(&**factory),
+        }
+    }
+
+    /// What is known about the guesser registered as `name`.
+    pub fn info(&self, name: &str) -> Option<&Info> {
+        self.entry(name).map(|e| &e.info)
+    }
+
+    fn entry(&self, name: &str) -> Option<&Entry> {
+        self.entries.iter().find(|e| e.name == name)
+    }
+
+    /// The names of all registered guessers.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|e| e.name.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Info, Registry, Speed};
+    use crate::{algorithms, GuesserFactory, Wordle};
+
+    #[test]
+    fn builtin() {
+        let registry = Registry::builtin();
+        assert_eq!(registry.names().count(), 14);
+        assert_eq!(registry.names().last(), Some("cache"));
+        assert!(registry.get("escore").is_some());
+        assert!(registry.get("nonsense").is_none());
+        assert!(registry.get_shared("escore").is_some());
+        let info = registry.info("cache").unwrap();
+        assert_eq!(info.speed, Some(Speed::Fast));
+        assert!(info.expected_score.unwrap() < 3.8);
+    }
+
+    #[test]
+    fn register_replaces() {
+        let mut registry = Registry::new();
+        registry.register("first", algorithms::Popular::new);
+        registry.register("second", algorithms::Popular::new);
+        registry.register("first", algorithms::Escore::new);
+        assert_eq!(registry.names().collect::<Vec<_>>(), ["first", "second"]);
+        assert_eq!(registry.info("first"), Some(&Info::default()));
+        registry.register_local("first", algorithms::Popular::new);
+        assert!(registry.get("first").is_some());
+        assert!(registry.get_shared("first").is_none());
+    }
+
+    #[test]
+    fn plays_boxed() {
+        let registry = Registry::builtin();
+        let factory = registry.get("escore").unwrap();
+        let w = Wordle::new();
+        let mut guesser = factory.new_guesser();
+        // A clone carries on from the same point.
+        let clone = guesser.clone();
+        assert!(w.play("cigar", &mut guesser).is_some());
+        assert!(w.play("cigar", clone).is_some());
+    }
+}
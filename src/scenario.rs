@@ -0,0 +1,151 @@
+//! Scripted runs of the interactive commands, to check a whole conversation with the solver at
+//! once.
+//!
+//! A scenario gives the command line to run, what to type in, and what should come out. It is
+//! written as one directive per line, for example:
+//!
+//! ```text
+//! # Playing the suggested opener and then undoing it.
+//! args --implementation popular assist
+//! input XYYXX
+//! input undo
+//! input quit
+//! expect try 'tares'
+//! expect forgot 'tares'
+//! expect try 'tares'
+//! ```
+//!
+//! The directives are:
+//!
+//! - `args <arg>...`: arguments to run the solver with, added to those of earlier `args` lines.
+//! - `input <line>`: a line to type in, as it is after the space (so `input` on its own is an
+//!   empty line).
+//! - `expect <text>`: some line of the output contains `text`, after the line the previous
+//!   `expect` found.
+//!
+//! Lines starting with `#` are comments. The input is passed with the hidden `--scripted-input`
+//! option, which makes the interactive commands read from a file rather than standard input,
+//! and echo what they read, so that the output reads like the conversation it was.
+
+use std::path::Path;
+use std::process::Command;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Scenario {
+    pub args: Vec<String>,
+    pub input: Vec<String>,
+    pub expect: Vec<String>,
+}
+
+impl FromStr for Scenario {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut scenario = Self::default();
+        for line in s.lines() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (directive, rest) = line.split_once(' ').unwrap_or((line, ""));
+            match directive {
+                "args" => scenario
+                    .args
+                    .extend(rest.split_whitespace().map(str::to_string)),
+                "input" => scenario.input.push(rest.to_string()),
+                "expect" if !rest.is_empty() => scenario.expect.push(rest.to_string()),
+                _ => return Err(format!("unknown directive '{}'", line)),
+            }
+        }
+        if scenario.args.is_empty() {
+            return Err("a scenario needs args to run the solver with".to_string());
+        }
+        Ok(scenario)
+    }
+}
+
+impl Scenario {
+    /// Runs the solver installed as `program` through the scenario, failing with what went wrong
+    /// and everything it printed if it didn't go as expected.
+    pub fn run(&self, program: &Path) -> Result<(), String> {
+        // Scenarios may run in parallel, so each needs its own input file.
+        static RUNS: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "wordle-solver-scenario-{}-{}",
+            std::process::id(),
+            RUNS.fetch_add(1, Ordering::Relaxed)
+        ));
+        let mut input = self.input.join("\n");
+        input.push('\n');
+        std::fs::write(&path, input)
+            .map_err(|e| format!("could not write '{}': {}", path.display(), e))?;
+        let output = Command::new(program)
+            .arg("--scripted-input")
+            .arg(&path)
+            .args(&self.args)
+            .output();
+        let _ = std::fs::remove_file(&path);
+        let output = output.map_err(|e| format!("could not run '{}': {}", program.display(), e))?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if !output.status.success() {
+            return Err(format!(
+                "the solver failed ({}):\n{}{}",
+                output.status,
+                stdout,
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+        self.check(&stdout)
+    }
+
+    /// Checks that `output` has every expected line, in order.
+    pub fn check(&self, output: &str) -> Result<(), String> {
+        let mut lines = output.lines();
+        for expected in &self.expect {
+            if !lines.any(|line| line.contains(expected.as_str())) {
+                return Err(format!(
+                    "expected a line containing \"{}\" in:\n{}",
+                    expected, output
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Scenario;
+
+    #[test]
+    fn parse() {
+        let scenario: Scenario = "\
+# a comment
+args --implementation popular
+args assist
+input XYYXX
+input
+expect try 'tares'
+"
+        .parse()
+        .unwrap();
+        assert_eq!(scenario.args, ["--implementation", "popular", "assist"]);
+        assert_eq!(scenario.input, ["XYYXX", ""]);
+        assert_eq!(scenario.expect, ["try 'tares'"]);
+
+        assert!("input quit".parse::<Scenario>().is_err());
+        assert!("args assist\nexpect".parse::<Scenario>().is_err());
+        assert!("args assist\ntype quit".parse::<Scenario>().is_err());
+    }
+
+    #[test]
+    fn expectations_are_in_order() {
+        let scenario: Scenario = "args assist\nexpect one\nexpect two".parse().unwrap();
+        assert!(scenario.check("one\ntwo\n").is_ok());
+        assert!(scenario.check("zero one two\n").is_err());
+        assert!(scenario.check("two\none\n").is_err());
+        assert!(scenario.check("one\n").is_err());
+    }
+}
@@ -0,0 +1,669 @@
+//! The solver as a small HTTP service, so that web pages and chat bots can ask it for guesses
+//! without linking to it.
+//!
+//! There is one endpoint, `POST /solve`, which takes a JSON object with the `history` of the game
+//! so far as `{"guess": "tares", "mask": "XYYXX"}` objects, and optionally which `algorithm` to
+//! guess with and a `session` name. It answers with the `guess` to make next (null once the game
+//! is solved or no word fits the feedback) and how many words are `remaining`:
+//!
+//! ```text
+//! $ curl -d '{"history": [{"guess": "tares", "mask": "XYYXX"}]}' localhost:8080/solve
+//! {"guess":"drain","remaining":245}
+//! ```
+//!
+//! The history is always the whole game. Without a session it is played through a new guesser
+//! every time; with one, the guesser is kept between requests, so that each only plays the guesses
+//! that are new since the last.
+//!
+//! Requests are answered by a fixed number of worker threads, so that one that takes a guesser a
+//! long time only holds up its own worker. Guessers can't move between threads, so each worker
+//! keeps its own sessions, and a session answered by another worker than last time starts over
+//! from its history. Each client can also be held to a [`RateLimit`], past which its requests are
+//! turned away before they take up a worker. None of the dependencies speak HTTP, so this speaks
+//! just enough of it for all that.
+
+use crate::json::{json_string, Json};
+use crate::registry::{DynGuesser, Registry, SharedFactory};
+use crate::session::Session;
+use crate::{letter_count, Correctness, Guess, MAX_GUESSES};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{IpAddr, TcpListener, TcpStream};
+use std::str::FromStr;
+use std::sync::mpsc::{self, TrySendError};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How many sessions each worker keeps, past which the one used longest ago is dropped.
+const MAX_SESSIONS: usize = 1024;
+/// The longest request body taken, which is far longer than any game.
+const MAX_BODY: usize = 64 * 1024;
+/// The longest line taken in a request's head, and how many of them there may be.
+const MAX_LINE: usize = 8 * 1024;
+const MAX_HEADERS: usize = 100;
+/// How long a client may take to send its whole request before it is given up on, since it holds
+/// up a worker meanwhile.
+const TIMEOUT: Duration = Duration::from_secs(10);
+/// The fewest workers answer requests by default, however few CPUs there are, since a worker
+/// waiting for a slow client isn't using its CPU.
+const MIN_WORKERS: usize = 4;
+/// How many connections may wait for each worker, past which new ones are turned away.
+const QUEUED_PER_WORKER: usize = 16;
+/// How long a connection that is turned away is given to send its request (which is ignored)
+/// and read the answer, and how many may wait to be turned away, past which they are dropped.
+const TURN_AWAY_TIMEOUT: Duration = Duration::from_secs(1);
+const MAX_TURNED_AWAY: usize = 64;
+/// How many clients' rate limits are kept track of, past which the ones that have made no
+/// requests for long enough to be back to a full burst are forgotten.
+const MAX_CLIENTS: usize = 16 * 1024;
+
+/// How many requests each client (by IP address) may make: a burst of up to `burst` at once, and
+/// after that `per_second` on average, as a token bucket.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    per_second: f64,
+    burst: f64,
+}
+
+impl RateLimit {
+    /// Allows `per_second` requests a second, and bursts of as many as are allowed in a second (or
+    /// of one request, if that is fewer).
+    ///
+    /// # Panics
+    ///
+    /// If `per_second` isn't a positive number.
+    pub fn new(per_second: f64) -> Self {
+        assert!(
+            per_second.is_finite() && per_second > 0.0,
+            "the rate limit must be a positive number"
+        );
+        Self {
+            per_second,
+            burst: per_second.max(1.0),
+        }
+    }
+
+    /// Allows bursts of up to `burst` requests at once.
+    pub fn with_burst(mut self, burst: usize) -> Self {
+        self.burst = burst.max(1) as f64;
+        self
+    }
+}
+
+impl FromStr for RateLimit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse::<f64>() {
+            Ok(per_second) if per_second.is_finite() && per_second > 0.0 => {
+                Ok(Self::new(per_second))
+            }
+            _ => Err(format!(
+                "'{}' is not a number of requests per second, e.g. 2 or 0.5",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for RateLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} requests a second in bursts of up to {}",
+            self.per_second, self.burst
+        )
+    }
+}
+
+/// The token bucket of every client that has made requests recently.
+struct Limiter {
+    limit: RateLimit,
+    /// How many requests each client could make at once when last seen, and when that was.
+    buckets: HashMap<IpAddr, (f64, Instant)>,
+}
+
+impl Limiter {
+    fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            buckets: HashMap::new(),
+        }
+    }
+
+    /// Whether `client` may make a request at `now`, which uses it up if so.
+    fn allow(&mut self, client: IpAddr, now: Instant) -> bool {
+        let limit = self.limit;
+        let refilled = |&(tokens, at): &(f64, Instant)| {
+            let elapsed = now.saturating_duration_since(at).as_secs_f64();
+            (tokens + elapsed * limit.per_second).min(limit.burst)
+        };
+        if self.buckets.len() >= MAX_CLIENTS && !self.buckets.contains_key(&client) {
+            self.buckets
+                .retain(|_, bucket| refilled(bucket) < limit.burst);
+        }
+        let bucket = self.buckets.entry(client).or_insert((limit.burst, now));
+        let tokens = refilled(bucket);
+        let allowed = tokens >= 1.0;
+        *bucket = (if allowed { tokens - 1.0 } else { tokens }, now);
+        allowed
+    }
+}
+
+/// Answers requests for guesses with the guessers in a registry.
+pub struct Server<'a> {
+    guessers: Vec<(&'a str, &'a SharedFactory)>,
+    default: String,
+    workers: usize,
+    rate_limit: Option<RateLimit>,
+    sessions: HashMap<String, Kept>,
+    used: u64,
+}
+
+/// A session kept between requests.
+struct Kept {
+    algorithm: String,
+    session: Session<Box<dyn DynGuesser>>,
+    last_used: u64,
+}
+
+impl<'a> Server<'a> {
+    /// Guesses with the guessers in `registry`, with `default` unless a request names another.
+    /// Only the guessers that can be shared between threads can be served, which leaves out ones
+    /// from plugins or scripts.
+    pub fn new(registry: &'a Registry, default: impl Into<String>) -> Self {
+        let guessers = registry
+            .names()
+            .filter_map(|name| Some((name, registry.get_shared(name)?)))
+            .collect();
+        Self::with_guessers(guessers, default.into())
+    }
+
+    fn with_guessers(guessers: Vec<(&'a str, &'a SharedFactory)>, default: String) -> Self {
+        Self {
+            guessers,
+            default,
+            workers: std::thread::available_parallelism()
+                .map_or(1, |n| n.get())
+                .max(MIN_WORKERS),
+            rate_limit: None,
+            sessions: HashMap::new(),
+            used: 0,
+        }
+    }
+
+    /// Answers requests on `workers` threads, rather than one for each CPU (and at least four).
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = workers.max(1);
+        self
+    }
+
+    /// Turns away requests from clients that make more than `limit` allows, with 429 Too Many
+    /// Requests.
+    pub fn with_rate_limit(mut self, limit: RateLimit) -> Self {
+        self.rate_limit = Some(limit);
+        self
+    }
+
+    /// Answers the requests made to `listener` forever, reporting connections that couldn't be
+    /// accepted or answered to stderr.
+    ///
+    /// Once every worker is busy and the connections waiting for them fill their queue, new ones
+    /// are answered straight away with 503 Service Unavailable.
+    pub fn serve(self, listener: TcpListener) {
+        let (queue, waiting) = mpsc::sync_channel(self.workers * QUEUED_PER_WORKER);
+        let waiting = Mutex::new(waiting);
+        let mut limiter = self.rate_limit.map(Limiter::new);
+        let (turn_away, turned_away) =
+            mpsc::sync_channel::<(TcpStream, u16, String)>(MAX_TURNED_AWAY);
+        std::thread::scope(|s| {
+            for _ in 0..self.workers {
+                let (guessers, default) = (self.guessers.clone(), self.default.clone());
+                let waiting = &waiting;
+                s.spawn(move || {
+                    // Sessions hold guessers, which can't be sent to the thread.
+                    let mut worker = Self::with_guessers(guessers, default);
+                    loop {
+                        let stream: TcpStream = match waiting.lock().unwrap().recv() {
+                            Ok(stream) => stream,
+                            Err(_) => return,
+                        };
+                        if let Err(e) = worker.answer(&stream) {
+                            eprintln!("could not answer a request: {}", e);
+                        }
+                    }
+                });
+            }
+            // Connections that are turned away are answered on a thread of their own, so that
+            // waiting for them to take the answer doesn't hold up accepting others.
+            s.spawn(move || {
+                for (stream, status, body) in turned_away {
+                    if let Err(e) = answer_early(&stream, status, &body) {
+                        eprintln!("could not answer a request: {}", e);
+                    }
+                }
+            });
+            for stream in listener.incoming() {
+                let stream = match stream {
+                    Ok(stream) => stream,
+                    // Such as when too many files are open, which passes.
+                    Err(e) => {
+                        eprintln!("could not accept a connection: {}", e);
+                        continue;
+                    }
+                };
+                if let Some(limiter) = &mut limiter {
+                    let client = stream.peer_addr().map(|addr| addr.ip());
+                    if client.is_ok_and(|client| !limiter.allow(client, Instant::now())) {
+                        let limited = error("too many requests, try again later");
+                        // Past this many, the connection is dropped without an answer.
+                        let _ = turn_away.try_send((stream, 429, limited));
+                        continue;
+                    }
+                }
+                match queue.try_send(stream) {
+                    Ok(()) => {}
+                    Err(TrySendError::Full(stream)) => {
+                        let busy = error("every worker is busy, try again later");
+                        let _ = turn_away.try_send((stream, 503, busy));
+                    }
+                    Err(TrySendError::Disconnected(_)) => unreachable!("the workers never stop"),
+                }
+            }
+        });
+    }
+
+    fn answer(&mut self, stream: &TcpStream) -> io::Result<()> {
+        let reader = Deadline {
+            stream,
+            deadline: Instant::now() + TIMEOUT,
+        };
+        let (status, body) = match read_request(reader)? {
+            Ok((method, path, body)) => self.respond(&method, &path, &body),
+            Err(response) => response,
+        };
+        reply(stream, status, &body)
+    }
+
+    /// The status and JSON body of the response to a request for `path` with `body`.
+    pub fn respond(&mut self, method: &str, path: &str, body: &str) -> (u16, String) {
+        match (method, path) {
+            ("POST", "/solve") => match self.solve(body) {
+                Ok(response) => (200, response),
+                Err(e) => (400, error(&e)),
+            },
+            // Browsers ask before sending JSON to another origin.
+            ("OPTIONS", "/solve") => (204, String::new()),
+            (_, "/solve") => (405, error("only POST is supported")),
+            _ => (404, error("the only endpoint is POST /solve")),
+        }
+    }
+
+    fn solve(&mut self, body: &str) -> Result<String, String> {
+        let request = Json::parse(body)?;
+        let algorithm = match request.get("algorithm") {
+            Some(Json::String(name)) => name.clone(),
+            None | Some(Json::Null) => self.default.clone(),
+            Some(_) => return Err("algorithm must be a string".to_string()),
+        };
+        let history = match request.get("history") {
+            Some(Json::Array(guesses)) => read_history(guesses)?,
+            None => Vec::new(),
+            Some(_) => return Err("history must be an array of guesses".to_string()),
+        };
+        let Some(&(_, factory)) = self.guessers.iter().find(|(name, _)| *name == algorithm) else {
+            return Err(format!(
+                "unknown algorithm '{}', expected one of: {}",
+                algorithm,
+                self.guessers
+                    .iter()
+                    .map(|(name, _)| *name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        };
+
+        let mut new = None;
+        let session = match request.get("session") {
+            Some(Json::String(name)) => {
+                self.used += 1;
+                if self.sessions.len() >= MAX_SESSIONS && !self.sessions.contains_key(name) {
+                    let oldest = self
+                        .sessions
+                        .iter()
+                        .min_by_key(|(_, kept)| kept.last_used)
+                        .map(|(name, _)| name.clone());
+                    if let Some(oldest) = oldest {
+                        self.sessions.remove(&oldest);
+                    }
+                }
+                let kept = self.sessions.entry(name.clone()).or_insert_with(|| Kept {
+                    algorithm: algorithm.clone(),
+                    session: Session::new(factory()),
+                    last_used: 0,
+                });
+                kept.last_used = self.used;
+                // Start over if the game isn't the one the session was playing.
+                let played = kept.session.history();
+                if kept.algorithm != algorithm
+                    || played.len() > history.len()
+                    || played
+                        .iter()
+                        .zip(&history)
+                        .any(|(a, b)| a.word != b.word || a.mask != b.mask)
+                {
+                    kept.algorithm = algorithm;
+                    kept.session = Session::new(factory());
+                }
+                &mut kept.session
+            }
+            None | Some(Json::Null) => new.insert(Session::new(factory())),
+            Some(_) => return Err("session must be a string".to_string()),
+        };
+        for guess in &history[session.history().len()..] {
+            if !session.record(&guess.word, guess.mask) {
+                return Err(format!("'{}' is not in the dictionary", guess.word));
+            }
+        }
+        Ok(format!(
+            "{{\"guess\":{},\"remaining\":{}}}",
+            session.suggestion().map_or("null".to_string(), json_string),
+            session.candidates().len()
+        ))
+    }
+}
+
+/// Reads the guesses of a request's history.
+fn read_history(guesses: &[Json]) -> Result<Vec<Guess<'static>>, String> {
+    if guesses.len() > MAX_GUESSES {
+        return Err(format!("a game has at most {} guesses", MAX_GUESSES));
+    }
+    guesses
+        .iter()
+        .map(|guess| {
+            let (Some(Json::String(word)), Some(Json::String(mask))) =
+                (guess.get("guess"), guess.get("mask"))
+            else {
+                return Err("every guess needs a guess and a mask, both strings".to_string());
+            };
+            let word = word.to_lowercase();
+            let Some(mask) = Correctness::parse_mask(mask, letter_count(&word)) else {
+                return Err(format!("'{}' is not valid feedback, e.g. GYXXY", mask));
+            };
+            Ok(Guess {
+                word: word.into(),
+                mask,
+            })
+        })
+        .collect()
+}
+
+/// Reads from a stream until a deadline, after which reading fails as timed out.
+struct Deadline<'s> {
+    stream: &'s TcpStream,
+    deadline: Instant,
+}
+
+impl Read for Deadline<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let left = self.deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "the client took too long to send its request",
+            ));
+        }
+        // Each read only waits for as long as is left of the whole request.
+        self.stream.set_read_timeout(Some(left))?;
+        let mut stream = self.stream;
+        stream.read(buf)
+    }
+}
+
+/// Reads a request's method, path and body, or the response to give if it can't.
+fn read_request(reader: impl Read) -> io::Result<Result<(String, String, String), (u16, String)>> {
+    let mut reader = BufReader::new(reader);
+    let mut line = String::new();
+    if !read_line(&mut reader, &mut line)? {
+        return Ok(Err((431, error("a line of the request is too long"))));
+    }
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(path)) = (parts.next(), parts.next()) else {
+        return Ok(Err((400, error("malformed request"))));
+    };
+    let (method, path) = (method.to_string(), path.to_string());
+    let mut length = 0;
+    for headers in 0.. {
+        if headers == MAX_HEADERS || !read_line(&mut reader, &mut line)? {
+            return Ok(Err((431, error("the request's headers are too long"))));
+        }
+        if line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                match value.trim().parse() {
+                    Ok(n) => length = n,
+                    Err(_) => return Ok(Err((400, error("malformed Content-Length")))),
+                }
+            }
+        }
+    }
+    if length > MAX_BODY {
+        return Ok(Err((413, error("the request is too long"))));
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    match String::from_utf8(body) {
+        Ok(body) => Ok(Ok((method, path, body))),
+        Err(_) => Ok(Err((400, error("the request isn't UTF-8")))),
+    }
+}
+
+/// Reads the next line into `line`, unless it is longer than `MAX_LINE`.
+fn read_line(reader: &mut impl BufRead, line: &mut String) -> io::Result<bool> {
+    line.clear();
+    let read = reader.take(MAX_LINE as u64).read_line(line)?;
+    Ok(read < MAX_LINE || line.ends_with('\n'))
+}
+
+/// Answers a connection with `status` and a JSON `body` without reading its request, which is
+/// then read and ignored for a moment, since closing a connection with some of it left unread
+/// would reset it before the client read the answer.
+fn answer_early(stream: &TcpStream, status: u16, body: &str) -> io::Result<()> {
+    reply(stream, status, body)?;
+    stream.shutdown(std::net::Shutdown::Write)?;
+    let reader = Deadline {
+        stream,
+        deadline: Instant::now() + TURN_AWAY_TIMEOUT,
+    };
+    match io::copy(&mut reader.take(MAX_BODY as u64), &mut io::sink()) {
+        Err(e) if e.kind() != io::ErrorKind::TimedOut && e.kind() != io::ErrorKind::WouldBlock => {
+            Err(e)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Sends a response with `status` and a JSON `body`, and closes the connection.
+fn reply(mut stream: &TcpStream, status: u16, body: &str) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Access-Control-Allow-Origin: *\r\nAccess-Control-Allow-Headers: Content-Type\r\n\
+         Connection: close\r\n\r\n{}",
+        status,
+        reason(status),
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        204 => "No Content",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        429 => "Too Many Requests",
+        431 => "Request Header Fields Too Large",
+        503 => "Service Unavailable",
+        _ => "",
+    }
+}
+
+/// `message` as the body of an error response.
+fn error(message: &str) -> String {
+    format!("{{\"error\":{}}}", json_string(message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Limiter, RateLimit, Server, MAX_LINE};
+    use crate::registry::Registry;
+    use std::io::{Read, Write};
+    use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+    use std::time::{Duration, Instant};
+
+    /// Serves the built-in guessers on `workers` threads, on a port of its own.
+    fn serve(workers: usize, rate_limit: Option<RateLimit>) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let registry = Registry::builtin();
+            let server = Server::new(&registry, "popular").with_workers(workers);
+            match rate_limit {
+                Some(limit) => server.with_rate_limit(limit).serve(listener),
+                None => server.serve(listener),
+            }
+        });
+        addr
+    }
+
+    /// Sends `request` to the server at `addr`, and reads the response.
+    fn send(addr: SocketAddr, request: &[u8]) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(request).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn limits_nesting() {
+        // Deep enough to overflow the stack if it were parsed.
+        let registry = Registry::builtin();
+        let mut server = Server::new(&registry, "popular");
+        let (status, _) = server.respond("POST", "/solve", &"[".repeat(60_000));
+        assert_eq!(status, 400);
+    }
+
+    #[test]
+    fn solves() {
+        let registry = Registry::builtin();
+        let mut server = Server::new(&registry, "popular");
+        let (status, body) = server.respond("POST", "/solve", "{}");
+        assert_eq!(status, 200);
+        assert!(body.starts_with("{\"guess\":\""), "{}", body);
+
+        // Whether the guesser is kept or not, the same game gets the same guess.
+        let request = r#"{"history": [{"guess": "tares", "mask": "XYYXX"}]"#;
+        let (status, fresh) = server.respond("POST", "/solve", &format!("{}}}", request));
+        assert_eq!(status, 200);
+        let kept = format!(r#"{}, "session": "a"}}"#, request);
+        assert_eq!(
+            server.respond("POST", "/solve", &kept),
+            (200, fresh.clone())
+        );
+        assert_eq!(server.respond("POST", "/solve", &kept), (200, fresh));
+
+        let solved = r#"{"history": [{"guess": "cigar", "mask": "GGGGG"}], "session": "a"}"#;
+        assert_eq!(
+            server.respond("POST", "/solve", solved),
+            (200, "{\"guess\":null,\"remaining\":1}".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_bad_requests() {
+        let registry = Registry::builtin();
+        let mut server = Server::new(&registry, "popular");
+        for body in [
+            "not json",
+            r#"{"algorithm": "nonsense"}"#,
+            r#"{"history": [{"guess": "tares", "mask": "XYY"}]}"#,
+            r#"{"history": [{"guess": "zzzzz", "mask": "XXXXX"}]}"#,
+            &format!(
+                r#"{{"history": [{}]}}"#,
+                [r#"{"guess": "tares", "mask": "XXXXX"}"#; 33].join(", ")
+            ),
+        ] {
+            let (status, body) = server.respond("POST", "/solve", body);
+            assert_eq!(status, 400);
+            assert!(body.starts_with("{\"error\":"), "{}", body);
+        }
+        assert_eq!(server.respond("GET", "/solve", "").0, 405);
+        assert_eq!(server.respond("POST", "/", "").0, 404);
+    }
+
+    #[test]
+    fn answers_while_a_client_is_slow() {
+        let addr = serve(2, None);
+        // A client that never finishes its request only holds up one worker.
+        let mut slow = TcpStream::connect(addr).unwrap();
+        slow.write_all(b"POST /solve HTTP/1.1\r\n").unwrap();
+        let response = send(addr, b"POST /solve HTTP/1.1\r\nContent-Length: 2\r\n\r\n{}");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+        assert!(response.contains("{\"guess\":\""), "{}", response);
+    }
+
+    #[test]
+    fn limits_header_lines() {
+        let addr = serve(1, None);
+        let mut request = b"POST /solve HTTP/1.1\r\nX-Long: ".to_vec();
+        request.resize(request.len() + MAX_LINE, b'a');
+        let response = send(addr, &request);
+        assert!(response.starts_with("HTTP/1.1 431 "), "{}", response);
+    }
+
+    #[test]
+    fn limits_rate() {
+        let mut limiter = Limiter::new(RateLimit::new(1.0).with_burst(2));
+        let (a, b) = (
+            IpAddr::V4(Ipv4Addr::LOCALHOST),
+            IpAddr::V4(Ipv4Addr::BROADCAST),
+        );
+        let start = Instant::now();
+        assert!(limiter.allow(a, start));
+        assert!(limiter.allow(a, start));
+        assert!(!limiter.allow(a, start));
+        // Other clients have buckets of their own.
+        assert!(limiter.allow(b, start));
+        // The bucket fills back up at the rate allowed, but no further than the burst.
+        assert!(!limiter.allow(a, start + Duration::from_millis(500)));
+        assert!(limiter.allow(a, start + Duration::from_secs(1)));
+        assert!(!limiter.allow(a, start + Duration::from_secs(1)));
+        assert!(limiter.allow(b, start + Duration::from_secs(60)));
+        assert!(limiter.allow(b, start + Duration::from_secs(60)));
+        assert!(!limiter.allow(b, start + Duration::from_secs(60)));
+
+        assert_eq!("0.5".parse(), Ok(RateLimit::new(0.5)));
+        for bad in ["0", "-1", "NaN", "inf", "often"] {
+            assert!(bad.parse::<RateLimit>().is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn turns_away_clients_over_the_rate_limit() {
+        let addr = serve(1, Some(RateLimit::new(0.001)));
+        let request = b"POST /solve HTTP/1.1\r\nContent-Length: 2\r\n\r\n{}";
+        let response = send(addr, request);
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+        let response = send(addr, request);
+        assert!(response.starts_with("HTTP/1.1 429 "), "{}", response);
+    }
+}
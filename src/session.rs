@@ -0,0 +1,247 @@
+use crate::{dictionary, letter_count, Correctness, Guess, Guesser, Mask};
+use std::borrow::Cow;
+use std::io::{self, BufRead, Write};
+
+const HEADER: &str = "# wordle-solver session";
+
+/// An interactive game against an answer we don't know, where the feedback for every guess is
+/// supplied from the outside (e.g. by a human playing the real Wordle).
+///
+/// Before a round is recorded, the guesser and candidate set are snapshotted so that the round
+/// can be undone without having to replay the whole history through the guesser.
+pub struct Session<G> {
+    guesser: G,
+    history: Vec<Guess<'static>>,
+    candidates: Vec<&'static str>,
+    suggestion: Option<String>,
+    rounds: Vec<Round<G>>,
+}
+
+struct Round<G> {
+    guesser: G,
+    candidates: Vec<&'static str>,
+    suggestion: Option<String>,
+}
+
+impl<G: Guesser + Clone> Session<G> {
+    pub fn new(mut guesser: G) -> Self {
+        let suggestion = Some(guesser.guess(&[]).into_owned());
+        Self {
+            guesser,
+            history: Vec::new(),
+            candidates: dictionary().iter().map(|&(word, _)| word).collect(),
+            suggestion,
+            rounds: Vec::new(),
+        }
+    }
+
+    /// The guesser's recommendation for the next guess.
+    ///
+    /// This is `None` once the game is solved, or if no word is consistent with the feedback.
+    pub fn suggestion(&self) -> Option<&str> {
+        self.suggestion.as_deref()
+    }
+
+    pub fn history(&self) -> &[Guess<'static>] {
+        &self.history
+    }
+
+    /// The words that are still consistent with all the feedback so far.
+    pub fn candidates(&self) -> &[&'static str] {
+        &self.candidates
+    }
+
+    pub fn is_solved(&self) -> bool {
+        matches!(self.history.last(), Some(last) if last.mask == Correctness::SOLVED)
+    }
+
+    /// Records that `word` was played and got `mask` as feedback.
+    ///
+    /// Returns false, and leaves the session untouched, if `word` is not in the dictionary.
+    pub fn record(&mut self, word: &str, mask: Mask) -> bool {
+        if !dictionary().iter().any(|&(w, _)| w == word) {
+            return false;
+        }
+
+        self.rounds.push(Round {
+            guesser: self.guesser.clone(),
+            candidates: self.candidates.clone(),
+            suggestion: self.suggestion.take(),
+        });
+
+        let guess = Guess {
+            word: Cow::Owned(word.to_string()),
+            mask,
+        };
+        self.candidates.retain(|candidate| guess.matches(candidate));
+        self.history.push(guess);
+
+        // The guessers assume there is always at least one word left to pick from.
+        if !self.is_solved() && !self.candidates.is_empty() {
+            self.suggestion = Some(self.guesser.guess(&self.history).into_owned());
+        }
+        true
+    }
+
+    /// Pops the last recorded round, restoring the candidates and suggestion from before it.
+    pub fn undo(&mut self) -> Option<Guess<'static>> {
+        let round = self.rounds.pop()?;
+        self.guesser = round.guesser;
+        self.candidates = round.candidates;
+        self.suggestion = round.suggestion;
+        self.history.pop()
+    }
+
+    /// Writes the session, along with any caller-defined `settings`, in a plain-text format that
+    /// [`Session::load`] can read back.
+    pub fn save<W: Write>(&self, mut w: W, settings: &[(&str, &str)]) -> io::Result<()> {
+        writeln!(w, "{}", HEADER)?;
+        for (key, value) in settings {
+            writeln!(w, "set {} {}", key, value)?;
+        }
+        for guess in &self.history {
+            writeln!(
+                w,
+                "guess {} {}",
+                guess.word,
+                Correctness::format_mask(guess.feedback())
+            )?;
+        }
+        writeln!(w, "candidates {}", self.candidates.join(" "))
+    }
+
+    /// Restores a session written by [`Session::save`], returning it along with its settings.
+    ///
+    /// The recorded guesses are replayed through `guesser` so that it ends up in the same state
+    /// as when the session was saved. The saved candidates must agree with the replayed ones,
+    /// otherwise the file was written against a different dictionary.
+    pub fn load<R: BufRead>(guesser: G, r: R) -> io::Result<(Self, Vec<(String, String)>)> {
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+
+        let mut lines = r.lines();
+        if lines.next().transpose()?.as_deref() != Some(HEADER) {
+            return Err(invalid("not a session file".to_string()));
+        }
+
+        let mut session = Self::new(guesser);
+        let mut settings = Vec::new();
+        let mut candidates = None;
+        for line in lines {
+            let line = line?;
+            match line.split_once(' ') {
+                Some(("set", setting)) => {
+                    let (key, value) = setting.split_once(' ').unwrap_or((setting, ""));
+                    settings.push((key.to_string(), value.to_string()));
+                }
+                Some(("guess", guess)) => {
+                    let (word, mask) = guess
+                        .split_once(' ')
+                        .ok_or_else(|| invalid(format!("malformed guess '{}'", guess)))?;
+                    let mask = Correctness::parse_mask(mask, letter_count(word))
+                        .ok_or_else(|| invalid(format!("malformed mask '{}'", mask)))?;
+                    if !session.record(word, mask) {
+                        return Err(invalid(format!("'{}' is not in the dictionary", word)));
+                    }
+                }
+                Some(("candidates", words)) => candidates = Some(words.to_string()),
+                None if line == "candidates" => candidates = Some(String::new()),
+                _ if line.is_empty() => {}
+                _ => return Err(invalid(format!("unexpected line '{}'", line))),
+            }
+        }
+
+        let candidates = candidates.ok_or_else(|| invalid("missing candidates".to_string()))?;
+        if !candidates
+            .split_whitespace()
+            .eq(session.candidates.iter().copied())
+        {
+            return Err(invalid(
+                "saved candidates do not match the current dictionary".to_string(),
+            ));
+        }
+        Ok((session, settings))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Session;
+    use crate::algorithms::Popular;
+    use crate::Correctness;
+
+    #[test]
+    fn first_suggestion() {
+        let session = Session::new(Popular::default());
+        assert_eq!(session.suggestion(), Some("tares"));
+        assert!(session.history().is_empty());
+    }
+
+    #[test]
+    fn record_narrows_candidates() {
+        let mut session = Session::new(Popular::default());
+        let all = session.candidates().len();
+        assert!(session.record("tares", Correctness::compute("right", "tares")));
+        assert!(session.candidates().len() < all);
+        assert!(session.candidates().contains(&"right"));
+    }
+
+    #[test]
+    fn record_unknown_word() {
+        let mut session = Session::new(Popular::default());
+        assert!(!session.record("zzzzz", Correctness::mask(&[Correctness::Wrong; 5])));
+        assert!(session.history().is_empty());
+    }
+
+    #[test]
+    fn undo_restores_previous_round() {
+        let mut session = Session::new(Popular::default());
+        session.record("tares", Correctness::compute("right", "tares"));
+        let candidates = session.candidates().to_vec();
+        let suggestion = session.suggestion().map(str::to_string);
+
+        session.record("fight", Correctness::compute("right", "fight"));
+        let undone = session.undo().unwrap();
+        assert_eq!(undone.word, "fight");
+        assert_eq!(session.candidates(), candidates);
+        assert_eq!(session.suggestion().map(str::to_string), suggestion);
+
+        session.undo().unwrap();
+        assert_eq!(session.suggestion(), Some("tares"));
+        assert!(session.undo().is_none());
+    }
+
+    #[test]
+    fn save_and_load() {
+        let mut session = Session::new(Popular::default());
+        session.record("tares", Correctness::compute("right", "tares"));
+        session.record("fight", Correctness::compute("right", "fight"));
+
+        let mut file = Vec::new();
+        session
+            .save(&mut file, &[("implementation", "popular")])
+            .unwrap();
+        let (loaded, settings) = Session::load(Popular::default(), &file[..]).unwrap();
+
+        assert_eq!(
+            settings,
+            [("implementation".to_string(), "popular".to_string())]
+        );
+        assert_eq!(loaded.history().len(), 2);
+        assert_eq!(loaded.candidates(), session.candidates());
+        assert_eq!(loaded.suggestion(), session.suggestion());
+    }
+
+    #[test]
+    fn load_rejects_mismatched_candidates() {
+        let file = "# wordle-solver session\nguess tares GGGGG\ncandidates right\n";
+        assert!(Session::load(Popular::default(), file.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn solved() {
+        let mut session = Session::new(Popular::default());
+        session.record("right", Correctness::SOLVED);
+        assert!(session.is_solved());
+        assert_eq!(session.suggestion(), None);
+    }
+}
@@ -0,0 +1,471 @@
+use crate::{
+    dictionary_info, letter_count, word_len, Correctness, Guess, Guesser, GuesserFactory, Mask,
+    Wordle,
+};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+/// A guesser's complete behaviour on a set of answers, written down as a decision tree so that it
+/// can be replayed without doing any of the work that went into choosing the guesses.
+///
+/// In its file format, every line is the path to one answer: each guess followed by the feedback
+/// it got, ending with `GGGGG`.
+///
+/// ```text
+/// tares XYYXX bound XXXXX cigar GGGGG
+/// tares YXYYX rebus GGGGX rebut GGGGG
+/// ```
+///
+/// Other lines starting with `#` are ignored, except for `# hard-mode`, which claims that every
+/// guess reuses all the green and yellow letters revealed before it, and `# dictionary
+/// <version>`, which refuses to load the tree with any other version of the dictionary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tree {
+    root: Node,
+    hard_mode: bool,
+}
+
+/// What [`Tree::verify`] found.
+#[derive(Debug, Default)]
+pub struct Verification {
+    /// Everything wrong with the tree, in a form meant for people.
+    pub problems: Vec<String>,
+    /// How many of the answers the tree solves.
+    pub solved: usize,
+    pub total_guesses: usize,
+    pub max_guesses: usize,
+}
+
+impl Verification {
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    /// The average number of guesses over the answers the tree solves.
+    pub fn average_score(&self) -> f64 {
+        self.total_guesses as f64 / self.solved.max(1) as f64
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Node {
+    guess: String,
+    /// Whether some path ends with this guess being the answer.
+    solves: bool,
+    children: BTreeMap<Mask, Node>,
+}
+
+impl Node {
+    fn new(guess: &str) -> Self {
+        Self {
+            guess: guess.to_string(),
+            solves: false,
+            children: BTreeMap::new(),
+        }
+    }
+}
+
+impl Tree {
+    /// Plays every one of `answers` with a fresh guesser from `factory`, recording what it guessed.
+    ///
+    /// Fails if a game takes more than 32 guesses, or if the guesser made different guesses after
+    /// the same feedback (so its behaviour can't be written down as a tree).
+    pub fn compile<G: Guesser>(
+        factory: &impl GuesserFactory<Guesser = G>,
+        answers: impl IntoIterator<Item = &'static str>,
+    ) -> Result<Self, String> {
+        let mut root: Option<Node> = None;
+        for answer in answers {
+            let mut guesser = factory.new_guesser();
+            let mut history = Vec::new();
+            loop {
+                if history.len() == 32 {
+                    return Err(format!("failed to guess '{}' in 32 guesses", answer));
+                }
+                let guess = guesser.guess(&history);
+                let mask = Correctness::compute(answer, &guess);
+                history.push(Guess { word: guess, mask });
+                if mask == Correctness::SOLVED {
+                    guesser.finish(history.len());
+                    break;
+                }
+            }
+            let root = root.get_or_insert_with(|| Node::new(&history[0].word));
+            Self::insert(root, &history)?;
+        }
+        root.map(|root| Self {
+            root,
+            hard_mode: false,
+        })
+        .ok_or_else(|| "there were no answers to compile".to_string())
+    }
+
+    fn insert(mut node: &mut Node, path: &[Guess]) -> Result<(), String> {
+        for (i, guess) in path.iter().enumerate() {
+            if node.guess != guess.word {
+                return Err(format!(
+                    "{} the tree guesses '{}', not '{}'",
+                    after(&path[..i]),
+                    node.guess,
+                    guess.word
+                ));
+            }
+            if guess.mask == Correctness::SOLVED {
+                node.solves = true;
+                break;
+            }
+            let Some(next) = path.get(i + 1) else {
+                return Err(format!("{} does not end in GGGGG", describe(path)));
+            };
+            node = node
+                .children
+                .entry(guess.mask)
+                .or_insert_with(|| Node::new(&next.word));
+        }
+        Ok(())
+    }
+
+    /// Claims (or stops claiming) that the tree follows the hard-mode rules.
+    pub fn with_hard_mode(mut self, hard_mode: bool) -> Self {
+        self.hard_mode = hard_mode;
+        self
+    }
+
+    pub fn claims_hard_mode(&self) -> bool {
+        self.hard_mode
+    }
+
+    /// Checks that the tree solves every one of `answers` (by having a guess for every feedback
+    /// they can lead to), that it only guesses allowed words, and that it follows the hard-mode
+    /// rules if it claims to, while working out how well it actually scores.
+    pub fn verify(&self, answers: &[&'static str]) -> Verification {
+        fn walk<'a>(
+            tree: &Tree,
+            node: &'a Node,
+            path: &mut Vec<Guess<'a>>,
+            answers: &[&'static str],
+            w: &Wordle,
+            v: &mut Verification,
+        ) {
+            if !w.is_allowed(&node.guess) {
+                v.problems.push(format!(
+                    "{} the tree guesses '{}', which is not an allowed word",
+                    after(path),
+                    node.guess
+                ));
+            }
+            if tree.hard_mode && !path.iter().all(|g| g.allows_in_hard_mode(&node.guess)) {
+                v.problems.push(format!(
+                    "{} the tree guesses '{}', which is not allowed in hard mode",
+                    after(path),
+                    node.guess
+                ));
+            }
+
+            let mut groups: BTreeMap<_, Vec<_>> = BTreeMap::new();
+            for &answer in answers {
+                groups
+                    .entry(Correctness::compute(answer, &node.guess))
+                    .or_default()
+                    .push(answer);
+            }
+            for (mask, group) in groups {
+                if mask == Correctness::SOLVED {
+                    let guesses = path.len() + 1;
+                    v.solved += 1;
+                    v.total_guesses += guesses;
+                    v.max_guesses = v.max_guesses.max(guesses);
+                    continue;
+                }
+                path.push(Guess {
+                    word: Cow::Borrowed(&node.guess),
+                    mask,
+                });
+                match node.children.get(&mask) {
+                    Some(child) => walk(tree, child, path, &group, w, v),
+                    None => v.problems.push(format!(
+                        "{} the tree has no guess, but it could still be {} word(s) such as '{}'",
+                        after(path),
+                        group.len(),
+                        group[0]
+                    )),
+                }
+                path.pop();
+            }
+        }
+
+        let mut v = Verification::default();
+        walk(
+            self,
+            &self.root,
+            &mut Vec::new(),
+            answers,
+            &Wordle::new(),
+            &mut v,
+        );
+        v
+    }
+
+    /// How many guesses the tree takes to solve `answer`, or `None` if it can't.
+    pub fn score(&self, answer: &str) -> Option<usize> {
+        let mut node = &self.root;
+        for guesses in 1.. {
+            let mask = Correctness::compute(answer, &node.guess);
+            if mask == Correctness::SOLVED {
+                return Some(guesses);
+            }
+            node = node.children.get(&mask)?;
+        }
+        unreachable!()
+    }
+
+    /// A guesser that follows this tree.
+    pub fn guesser(&self) -> TreeGuesser<'_> {
+        TreeGuesser { tree: self }
+    }
+
+    /// Every path from the first guess to an answer, in the order they are written out.
+    pub fn paths(&self) -> Vec<Vec<Guess<'_>>> {
+        fn walk<'a>(node: &'a Node, path: &mut Vec<Guess<'a>>, paths: &mut Vec<Vec<Guess<'a>>>) {
+            if node.solves {
+                let mut solved = path.clone();
+                solved.push(Guess {
+                    word: Cow::Borrowed(&node.guess),
+                    mask: Correctness::SOLVED,
+                });
+                paths.push(solved);
+            }
+            for (mask, child) in &node.children {
+                path.push(Guess {
+                    word: Cow::Borrowed(&node.guess),
+                    mask: *mask,
+                });
+                walk(child, path, paths);
+                path.pop();
+            }
+        }
+        let mut paths = Vec::new();
+        walk(&self.root, &mut Vec::new(), &mut paths);
+        paths
+    }
+
+    pub fn write<W: Write>(&self, mut w: W) -> io::Result<()> {
+        writeln!(w, "# dictionary {}", dictionary_info().version())?;
+        if self.hard_mode {
+            writeln!(w, "# hard-mode")?;
+        }
+        for path in self.paths() {
+            writeln!(w, "{}", describe(&path))?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes `path` the way it appears in a tree file.
+fn describe(path: &[Guess]) -> String {
+    path.iter()
+        .map(|g| format!("{} {}", g.word, Correctness::format_mask(g.feedback())))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Describes where in the tree `path` leads, to start a sentence with.
+fn after(path: &[Guess]) -> String {
+    if path.is_empty() {
+        "at the start".to_string()
+    } else {
+        format!("after {}", describe(path))
+    }
+}
+
+impl FromStr for Tree {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut root: Option<Node> = None;
+        let mut hard_mode = false;
+        for (n, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line == "# hard-mode" {
+                hard_mode = true;
+                continue;
+            }
+            if let Some(version) = line.strip_prefix("# dictionary ") {
+                dictionary_info()
+                    .check(version.trim())
+                    .map_err(|e| format!("line {}: {}", n + 1, e))?;
+                continue;
+            }
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let words: Vec<_> = line.split_whitespace().collect();
+            if words.len() % 2 != 0 {
+                return Err(format!("line {}: expected a mask after every guess", n + 1));
+            }
+            let mut path = Vec::new();
+            for pair in words.chunks(2) {
+                if letter_count(pair[0]) != word_len() {
+                    return Err(format!("line {}: '{}' is not a word", n + 1, pair[0]));
+                }
+                let mask = Correctness::parse_mask(pair[1], letter_count(pair[0]))
+                    .ok_or_else(|| format!("line {}: '{}' is not a mask", n + 1, pair[1]))?;
+                path.push(Guess {
+                    word: Cow::Borrowed(pair[0]),
+                    mask,
+                });
+            }
+            let root = root.get_or_insert_with(|| Node::new(&path[0].word));
+            Self::insert(root, &path).map_err(|e| format!("line {}: {}", n + 1, e))?;
+        }
+        root.map(|root| Self { root, hard_mode })
+            .ok_or_else(|| "the tree is empty".to_string())
+    }
+}
+
+impl<'a> GuesserFactory for &'a Tree {
+    type Guesser = TreeGuesser<'a>;
+
+    fn new_guesser(&self) -> Self::Guesser {
+        self.guesser()
+    }
+}
+
+/// A guesser that looks up what to guess in a [`Tree`].
+#[derive(Clone)]
+pub struct TreeGuesser<'a> {
+    tree: &'a Tree,
+}
+
+impl Guesser for TreeGuesser<'_> {
+    fn guess(&mut self, history: &[Guess]) -> Cow<'static, str> {
+        let mut node = &self.tree.root;
+        for (i, guess) in history.iter().enumerate() {
+            node = match node.children.get(&guess.mask) {
+                Some(child) if node.guess == guess.word => child,
+                _ => panic!("the strategy tree has no guess {}", after(&history[..=i])),
+            };
+        }
+        node.guess.clone().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Tree;
+    use crate::algorithms::Popular;
+    use crate::Wordle;
+
+    const ANSWERS: [&str; 4] = ["cigar", "rebut", "sissy", "humph"];
+
+    #[test]
+    fn compiled_tree_plays_like_the_guesser() {
+        let w = Wordle::new();
+        let tree = Tree::compile(&Popular::default, ANSWERS).unwrap();
+        for answer in ANSWERS {
+            assert_eq!(
+                w.play(answer, tree.guesser()),
+                w.play(answer, Popular::default())
+            );
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let tree = Tree::compile(&Popular::default, ANSWERS).unwrap();
+        let mut out = Vec::new();
+        tree.write(&mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.starts_with("# dictionary "));
+        assert_eq!(
+            text.lines().filter(|l| !l.starts_with('#')).count(),
+            ANSWERS.len()
+        );
+        assert_eq!(text.parse::<Tree>().unwrap(), tree);
+    }
+
+    #[test]
+    fn parse() {
+        let tree: Tree = "# comment\n\
+            tares XYYXX bound XXXXX cigar GGGGG\n\
+            tares YXYYX rebus GGGGX rebut GGGGG\n\
+            tares GGGGG"
+            .parse()
+            .unwrap();
+        assert_eq!(tree.paths().len(), 3);
+        let w = Wordle::new();
+        assert_eq!(w.play("tares", tree.guesser()), Some(1));
+        assert_eq!(w.play("rebut", tree.guesser()), Some(3));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!("".parse::<Tree>().is_err());
+        assert!("tares".parse::<Tree>().is_err());
+        assert!("tares XYXXQ cigar GGGGG".parse::<Tree>().is_err());
+        assert!("tares XYXXX".parse::<Tree>().is_err());
+        // Two different guesses after the same feedback.
+        assert!(
+            "tares XYYXX bound XXXXX cigar GGGGG\ntares XYYXX cigar GGGGG"
+                .parse::<Tree>()
+                .is_err()
+        );
+        assert!("# dictionary 0000000000000000\ntares GGGGG"
+            .parse::<Tree>()
+            .is_err());
+    }
+
+    #[test]
+    fn score() {
+        let tree: Tree = "tares XYYXX bound XXXXX cigar GGGGG\ntares GGGGG"
+            .parse()
+            .unwrap();
+        assert_eq!(tree.score("tares"), Some(1));
+        assert_eq!(tree.score("cigar"), Some(3));
+        assert_eq!(tree.score("rebut"), None);
+    }
+
+    #[test]
+    fn verify_compiled() {
+        let tree = Tree::compile(&Popular::default, ANSWERS).unwrap();
+        let v = tree.verify(&ANSWERS);
+        assert!(v.is_ok(), "{:?}", v.problems);
+        assert_eq!(v.solved, ANSWERS.len());
+        let w = Wordle::new();
+        let total: usize = ANSWERS
+            .iter()
+            .map(|answer| w.play(answer, Popular::default()).unwrap())
+            .sum();
+        assert_eq!(v.total_guesses, total);
+    }
+
+    #[test]
+    fn verify_incomplete() {
+        let tree: Tree = "tares XYYXX bound XXXXX cigar GGGGG".parse().unwrap();
+        let v = tree.verify(&["cigar", "rebut"]);
+        assert_eq!(v.solved, 1);
+        assert_eq!(v.problems.len(), 1);
+    }
+
+    #[test]
+    fn verify_illegal() {
+        let tree: Tree = "tares XYYXX zzzzz XXXXX cigar GGGGG".parse().unwrap();
+        assert!(!tree.verify(&["cigar"]).is_ok());
+    }
+
+    #[test]
+    fn verify_hard_mode() {
+        // Reveals a yellow 'a' and 'r', but 'bound' uses neither.
+        let tree: Tree = "tares XYYXX bound XXXXX cigar GGGGG".parse().unwrap();
+        assert!(tree.verify(&["cigar"]).is_ok());
+        let tree = tree.with_hard_mode(true);
+        assert!(!tree.verify(&["cigar"]).is_ok());
+
+        let mut out = Vec::new();
+        tree.write(&mut out).unwrap();
+        let parsed: Tree = String::from_utf8(out).unwrap().parse().unwrap();
+        assert!(parsed.claims_hard_mode());
+    }
+}
@@ -0,0 +1,23 @@
+use std::path::Path;
+use wordle_solver::scenario::Scenario;
+
+/// Runs every scenario in `tests/scenarios` through the solver binary.
+#[test]
+fn scenarios() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/scenarios");
+    let mut paths: Vec<_> = std::fs::read_dir(&dir)
+        .expect("could not list the scenarios")
+        .map(|entry| entry.expect("could not list the scenarios").path())
+        .collect();
+    paths.sort();
+    assert!(!paths.is_empty());
+    for path in paths {
+        let scenario: Scenario = std::fs::read_to_string(&path)
+            .expect("could not read the scenario")
+            .parse()
+            .unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+        if let Err(e) = scenario.run(Path::new(env!("CARGO_BIN_EXE_wordle_solver"))) {
+            panic!("{}: {}", path.display(), e);
+        }
+    }
+}